@@ -0,0 +1,63 @@
+use anyhow::{Context, bail};
+use gam3du_framework_common::api::ApiDescriptor;
+use pico_args::Arguments;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Checks api descriptors against the schema and performs the semantic validation.
+///
+/// Reports every problem found in all files and fails if there has been at least one.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let mut paths = Vec::new();
+    while let Some(path) = args.opt_free_from_str::<PathBuf>()? {
+        paths.push(path);
+    }
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+    if paths.is_empty() {
+        bail!("no api descriptor given");
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let json = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let problems = check(&json);
+        if problems.is_empty() {
+            println!("{}: ok", path.display());
+        } else {
+            failed = true;
+            for problem in &problems {
+                eprintln!("{}: {problem}", path.display());
+            }
+        }
+    }
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Lists all problems of a descriptor, each prefixed with the path to the offending element.
+fn check(json: &[u8]) -> Vec<String> {
+    let value = match serde_json::from_slice(json) {
+        Ok(value) => value,
+        Err(error) => return vec![format!("not a valid JSON document: {error}")],
+    };
+
+    let violations = ApiDescriptor::check_json(&value);
+    if !violations.is_empty() {
+        // the semantic checks require a successfully deserialized descriptor
+        return violations.iter().map(ToString::to_string).collect();
+    }
+
+    match serde_json::from_value::<ApiDescriptor>(value) {
+        Ok(api) => match api.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(ToString::to_string).collect(),
+        },
+        Err(error) => vec![format!("$: {error}")],
+    }
+}
@@ -0,0 +1,51 @@
+use crate::load;
+use anyhow::bail;
+use gam3du_framework_common::api::Compatibility;
+use pico_args::Arguments;
+use std::{path::PathBuf, process::ExitCode};
+
+/// Prints all changes between two api descriptors.
+///
+/// Fails if there are breaking changes without a version bump that reflects this.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let old_path: PathBuf = args.free_from_str()?;
+    let new_path: PathBuf = args.free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    let old = load(&old_path)?;
+    let new = load(&new_path)?;
+
+    let changes = old.diff(&new);
+    for change in &changes {
+        println!("{change}");
+    }
+
+    let is_breaking = changes
+        .iter()
+        .any(|change| change.compatibility == Compatibility::Breaking);
+
+    println!();
+    println!(
+        "{count} change(s), version {old_version} -> {new_version}",
+        count = changes.len(),
+        old_version = old.version,
+        new_version = new.version,
+    );
+
+    if is_breaking && new.version.satisfies(old.version) {
+        eprintln!(
+            "error: version {} claims to be compatible with {}, but there are breaking changes",
+            new.version, old.version
+        );
+        return Ok(ExitCode::FAILURE);
+    }
+
+    if !changes.is_empty() && new.version == old.version {
+        eprintln!("warning: the api has changed, but the version has not been increased");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,183 @@
+mod html;
+mod markdown;
+
+use crate::load;
+use anyhow::{Context, bail};
+use gam3du_framework_common::api::{
+    ApiDescriptor, FunctionDescriptor, Identifier, Locale, Localized, ParameterDescriptor, Phrases,
+    RichText, TypeDescriptor, Value,
+};
+use pico_args::Arguments;
+use runtime_python_bindgen::{identifier, typ, value};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+/// Generates a reference of an api descriptor for students and teachers.
+///
+/// The format (Markdown or HTML) is chosen by the extension of the output file. With `--locale`
+/// the reference uses the aliases and translated texts of that language.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let locale = args
+        .opt_value_from_str::<_, String>("--locale")?
+        .map(|locale| Locale(Cow::Owned(locale)));
+    if let Some(ref locale) = locale
+        && !locale.is_valid()
+    {
+        bail!("invalid locale `{locale}`; use a language tag like `de` or `de-CH`");
+    }
+    let api_path: PathBuf = args.free_from_str()?;
+    let out_path: PathBuf = args.free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    let api = load(&api_path)?;
+
+    let generate: fn(&mut BufWriter<File>, &Reference<'_>) -> io::Result<()> = match out_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("md") => markdown::generate,
+        Some("html" | "htm") => html::generate,
+        _ => bail!(
+            "unknown documentation format of {}; use `.md` or `.html`",
+            out_path.display()
+        ),
+    };
+
+    let file = File::create(&out_path)
+        .with_context(|| format!("failed to create {}", out_path.display()))?;
+    let mut out = BufWriter::new(file);
+    let reference = Reference {
+        api: &api,
+        locale: locale.as_ref(),
+        phrases: Phrases::for_locale(locale.as_ref()),
+    };
+    generate(&mut out, &reference)
+        .with_context(|| format!("failed to generate {}", out_path.display()))?;
+    out.flush()?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The api to document and the language to use
+struct Reference<'api> {
+    api: &'api ApiDescriptor,
+    locale: Option<&'api Locale>,
+    phrases: &'static Phrases,
+}
+
+impl Reference<'_> {
+    /// The name of a function within this reference, used for links.
+    fn function_name(&self, target: &Identifier) -> String {
+        self.api.functions.get(target).map_or_else(
+            || identifier(target),
+            |function| identifier(function.localized(self.locale).name),
+        )
+    }
+}
+
+/// Describes the set of values accepted by a type in plain words.
+fn describe_type(typ: &TypeDescriptor, phrases: &Phrases) -> String {
+    match *typ {
+        TypeDescriptor::Integer(ref range) => {
+            // the upper bound is exclusive but students expect to see the largest value
+            phrases
+                .integer
+                .replace("{start}", &range.start.to_string())
+                .replace("{end}", &range.end.saturating_sub(1).to_string())
+        }
+        TypeDescriptor::Float => phrases.float.to_owned(),
+        TypeDescriptor::Boolean => phrases.boolean.to_owned(),
+        TypeDescriptor::String => phrases.string.to_owned(),
+        TypeDescriptor::List(ref element) => phrases
+            .list
+            .replace("{element}", &describe_type(element, phrases)),
+        TypeDescriptor::Tuple(ref elements) => {
+            let elements = elements
+                .iter()
+                .map(|element| describe_type(element, phrases))
+                .collect::<Vec<_>>();
+            phrases.tuple.replace("{elements}", &elements.join(", "))
+        }
+        TypeDescriptor::Record(ref fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, field)| {
+                    format!("{}: {}", identifier(name), describe_type(field, phrases))
+                })
+                .collect::<Vec<_>>();
+            phrases.record.replace("{fields}", &fields.join(", "))
+        }
+        TypeDescriptor::Enumeration(ref variants) => {
+            let variants = variants
+                .iter()
+                .map(|variant| value(&Value::Enumeration(variant.clone())))
+                .collect::<Vec<_>>();
+            phrases
+                .enumeration
+                .replace("{variants}", &variants.join(", "))
+        }
+        TypeDescriptor::Optional(ref inner) => phrases
+            .optional
+            .replace("{inner}", &describe_type(inner, phrases)),
+    }
+}
+
+/// Renders the signature of a function the way it is called from Python.
+fn python_signature(function: &FunctionDescriptor, locale: Option<&Locale>) -> String {
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = identifier(parameter.localized(locale).name);
+            let type_hint = typ(&parameter.typ);
+            default_value(parameter).map_or_else(
+                || format!("{name}: {type_hint}"),
+                |default| format!("{name}: {type_hint} = {default}"),
+            )
+        })
+        .collect::<Vec<_>>();
+    let returns = function
+        .returns
+        .as_ref()
+        .map_or_else(String::new, |returns| format!(" -> {}", typ(&returns.typ)));
+    format!(
+        "{}({}){returns}",
+        identifier(function.localized(locale).name),
+        parameters.join(", ")
+    )
+}
+
+/// Renders the default value of a parameter as Python literal.
+fn default_value(parameter: &ParameterDescriptor) -> Option<String> {
+    parameter
+        .default
+        .as_ref()
+        .filter(|default| !matches!(**default, Value::Unit))
+        .map(value)
+}
+
+/// The most detailed explanation of a parameter.
+fn parameter_text<'parameter>(
+    parameter: &'parameter ParameterDescriptor,
+    locale: Option<&Locale>,
+) -> &'parameter RichText {
+    let texts = parameter.localized(locale);
+    if texts.description.0.is_empty() {
+        texts.caption
+    } else {
+        texts.description
+    }
+}
+
+/// The description of an element if it adds anything to its caption.
+fn details<'text>(caption: &RichText, description: &'text RichText) -> Option<&'text RichText> {
+    (description.0 != caption.0 && !description.0.is_empty()).then_some(description)
+}
@@ -0,0 +1,242 @@
+use super::{Reference, default_value, describe_type, details, parameter_text, python_signature};
+use gam3du_framework_common::api::{
+    EventDescriptor, FunctionDescriptor, Identifier, Localized, ParameterDescriptor, RichText,
+};
+use runtime_python_bindgen::identifier;
+use std::io::{self, Write};
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 50em; margin: auto; padding: 1em; line-height: 1.4; }
+code, pre { font-family: monospace; background: #f0f0f0; }
+pre { padding: 0.5em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; vertical-align: top; }
+section { margin-bottom: 2em; }
+";
+
+/// Writes the reference as a single self-contained HTML page.
+pub(super) fn generate(out: &mut impl Write, reference: &Reference<'_>) -> io::Result<()> {
+    let Reference {
+        api,
+        locale,
+        phrases,
+    } = *reference;
+    let texts = api.localized(locale);
+    let name = |target: &Identifier| reference.function_name(target);
+    writeln!(out, "<!DOCTYPE html>")?;
+    match locale {
+        Some(locale) => writeln!(out, "<html lang=\"{}\">", escape(locale.as_ref()))?,
+        None => writeln!(out, "<html>")?,
+    }
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\" />")?;
+    writeln!(
+        out,
+        "<title>{}</title>",
+        escape(&texts.caption.document().to_plain(&name))
+    )?;
+    writeln!(out, "<style>{STYLE}</style>")?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+
+    writeln!(out, "<h1>{}</h1>", inline(reference, texts.caption))?;
+    let version = escape(phrases.api_version)
+        .replace(
+            "{name}",
+            &format!("<code>{}</code>", escape(api.name.as_ref())),
+        )
+        .replace("{version}", &api.version.to_string());
+    writeln!(out, "<p>{version}</p>")?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", html(reference, details))?;
+    }
+
+    if !api.functions.is_empty() {
+        writeln!(out, "<h2>{}</h2>", escape(phrases.functions))?;
+        writeln!(out, "<ul>")?;
+        for function in api.functions.values() {
+            let function_texts = function.localized(locale);
+            writeln!(
+                out,
+                "<li><a href=\"#{}\"><code>{}</code></a>: {}</li>",
+                anchor("function", &function.name),
+                escape(&identifier(function_texts.name)),
+                inline(reference, function_texts.caption)
+            )?;
+        }
+        writeln!(out, "</ul>")?;
+        for function in api.functions.values() {
+            generate_function(out, reference, function)?;
+        }
+    }
+
+    if !api.events.is_empty() {
+        writeln!(out, "<h2>{}</h2>", escape(phrases.events))?;
+        for event in api.events.values() {
+            generate_event(out, reference, event)?;
+        }
+    }
+
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+
+    Ok(())
+}
+
+fn generate_function(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    function: &FunctionDescriptor,
+) -> io::Result<()> {
+    let texts = function.localized(reference.locale);
+    writeln!(
+        out,
+        "<section id=\"{}\">",
+        anchor("function", &function.name)
+    )?;
+    writeln!(
+        out,
+        "<h3><code>{}</code></h3>",
+        escape(&identifier(texts.name))
+    )?;
+    writeln!(out, "<p>{}</p>", inline(reference, texts.caption))?;
+    writeln!(
+        out,
+        "<pre><code>{}</code></pre>",
+        escape(&python_signature(function, reference.locale))
+    )?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", html(reference, details))?;
+    }
+
+    generate_parameters(out, reference, &function.parameters)?;
+
+    if let Some(ref returns) = function.returns {
+        write!(
+            out,
+            "<p><strong>{}</strong> {}",
+            escape(reference.phrases.returns),
+            escape(&describe_type(&returns.typ, reference.phrases))
+        )?;
+        let text = inline(reference, parameter_text(returns, reference.locale));
+        if !text.is_empty() {
+            write!(out, ": {text}")?;
+        }
+        writeln!(out, "</p>")?;
+    }
+
+    writeln!(out, "</section>")?;
+
+    Ok(())
+}
+
+fn generate_event(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    event: &EventDescriptor,
+) -> io::Result<()> {
+    let texts = event.localized(reference.locale);
+    writeln!(out, "<section id=\"{}\">", anchor("event", &event.name))?;
+    writeln!(
+        out,
+        "<h3><code>on_{}</code></h3>",
+        escape(&identifier(texts.name))
+    )?;
+    writeln!(out, "<p>{}</p>", inline(reference, texts.caption))?;
+    writeln!(
+        out,
+        "<pre><code>on_{}(handler)</code></pre>",
+        escape(&identifier(texts.name))
+    )?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", html(reference, details))?;
+    }
+
+    generate_parameters(out, reference, &event.parameters)?;
+
+    writeln!(out, "</section>")?;
+
+    Ok(())
+}
+
+fn generate_parameters(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    parameters: &[ParameterDescriptor],
+) -> io::Result<()> {
+    if parameters.is_empty() {
+        return Ok(());
+    }
+
+    let phrases = reference.phrases;
+    // the default column would only say "required" otherwise
+    let defaults = parameters
+        .iter()
+        .any(|parameter| parameter.default.is_some());
+    writeln!(out, "<table>")?;
+    write!(
+        out,
+        "<tr><th>{}</th><th>{}</th>",
+        escape(phrases.parameter),
+        escape(phrases.typ)
+    )?;
+    if defaults {
+        write!(out, "<th>{}</th>", escape(phrases.default))?;
+    }
+    writeln!(out, "<th>{}</th></tr>", escape(phrases.description))?;
+    for parameter in parameters {
+        write!(
+            out,
+            "<tr><td><code>{}</code></td><td>{}</td>",
+            escape(&identifier(parameter.localized(reference.locale).name)),
+            escape(&describe_type(&parameter.typ, phrases))
+        )?;
+        if defaults {
+            let default = default_value(parameter).map_or_else(
+                || escape(phrases.required),
+                |default| format!("<code>{}</code>", escape(&default)),
+            );
+            write!(out, "<td>{default}</td>")?;
+        }
+        writeln!(
+            out,
+            "<td>{}</td></tr>",
+            inline(reference, parameter_text(parameter, reference.locale))
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    Ok(())
+}
+
+/// Renders the markup of a text; links point to the sections of the functions.
+fn html(reference: &Reference<'_>, text: &RichText) -> String {
+    let name = |target: &Identifier| reference.function_name(target);
+    let target = |target: &Identifier| anchor("function", target);
+    text.document().to_html(&name, &target)
+}
+
+/// Renders a text for places that only accept inline elements.
+fn inline(reference: &Reference<'_>, text: &RichText) -> String {
+    let rendered = html(reference, text);
+    // a single paragraph does not need to be enclosed
+    match rendered
+        .strip_prefix("<p>")
+        .and_then(|inner| inner.strip_suffix("</p>"))
+    {
+        Some(inner) if !inner.contains("<p>") => inner.to_owned(),
+        _ => rendered.replace('\n', " "),
+    }
+}
+
+/// A unique id of a function or event within the page
+fn anchor(kind: &str, name: &Identifier) -> String {
+    format!("{kind}-{}", name.as_ref().replace(' ', "-"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,184 @@
+use super::{Reference, default_value, describe_type, details, parameter_text, python_signature};
+use gam3du_framework_common::api::{
+    EventDescriptor, FunctionDescriptor, Identifier, Localized, ParameterDescriptor, RichText,
+};
+use runtime_python_bindgen::identifier;
+use std::io::{self, Write};
+
+/// Writes the reference as a single Markdown document.
+pub(super) fn generate(out: &mut impl Write, reference: &Reference<'_>) -> io::Result<()> {
+    let Reference {
+        api,
+        locale,
+        phrases,
+    } = *reference;
+    let texts = api.localized(locale);
+    writeln!(out, "# {}", single_line(reference, texts.caption))?;
+    writeln!(out)?;
+    let version = phrases
+        .api_version
+        .replace("{name}", &format!("`{}`", api.name))
+        .replace("{version}", &api.version.to_string());
+    writeln!(out, "{version}")?;
+    writeln!(out)?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", markdown(reference, details))?;
+        writeln!(out)?;
+    }
+
+    if !api.functions.is_empty() {
+        writeln!(out, "## {}", phrases.functions)?;
+        writeln!(out)?;
+        for function in api.functions.values() {
+            let function_texts = function.localized(locale);
+            writeln!(
+                out,
+                "- [`{}`](#{}): {}",
+                identifier(function_texts.name),
+                identifier(function_texts.name),
+                single_line(reference, function_texts.caption)
+            )?;
+        }
+        writeln!(out)?;
+        for function in api.functions.values() {
+            generate_function(out, reference, function)?;
+        }
+    }
+
+    if !api.events.is_empty() {
+        writeln!(out, "## {}", phrases.events)?;
+        writeln!(out)?;
+        for event in api.events.values() {
+            generate_event(out, reference, event)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_function(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    function: &FunctionDescriptor,
+) -> io::Result<()> {
+    let texts = function.localized(reference.locale);
+    writeln!(out, "### `{}`", identifier(texts.name))?;
+    writeln!(out)?;
+    writeln!(out, "{}", single_line(reference, texts.caption))?;
+    writeln!(out)?;
+    writeln!(out, "```python")?;
+    writeln!(out, "{}", python_signature(function, reference.locale))?;
+    writeln!(out, "```")?;
+    writeln!(out)?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", markdown(reference, details))?;
+        writeln!(out)?;
+    }
+
+    generate_parameters(out, reference, &function.parameters)?;
+
+    if let Some(ref returns) = function.returns {
+        write!(
+            out,
+            "**{}** {}",
+            reference.phrases.returns,
+            describe_type(&returns.typ, reference.phrases)
+        )?;
+        let text = single_line(reference, parameter_text(returns, reference.locale));
+        if text.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, ": {text}")?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn generate_event(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    event: &EventDescriptor,
+) -> io::Result<()> {
+    let texts = event.localized(reference.locale);
+    writeln!(out, "### `on_{}`", identifier(texts.name))?;
+    writeln!(out)?;
+    writeln!(out, "{}", single_line(reference, texts.caption))?;
+    writeln!(out)?;
+    writeln!(out, "```python")?;
+    writeln!(out, "on_{}(handler)", identifier(texts.name))?;
+    writeln!(out, "```")?;
+    writeln!(out)?;
+    if let Some(details) = details(texts.caption, texts.description) {
+        writeln!(out, "{}", markdown(reference, details))?;
+        writeln!(out)?;
+    }
+
+    generate_parameters(out, reference, &event.parameters)?;
+
+    Ok(())
+}
+
+fn generate_parameters(
+    out: &mut impl Write,
+    reference: &Reference<'_>,
+    parameters: &[ParameterDescriptor],
+) -> io::Result<()> {
+    if parameters.is_empty() {
+        return Ok(());
+    }
+
+    let phrases = reference.phrases;
+    // the default column would only say "required" otherwise
+    let defaults = parameters
+        .iter()
+        .any(|parameter| parameter.default.is_some());
+    let mut headers = vec![phrases.parameter, phrases.typ];
+    if defaults {
+        headers.push(phrases.default);
+    }
+    headers.push(phrases.description);
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    let separators = headers
+        .iter()
+        .map(|header| "-".repeat(header.chars().count() + 2))
+        .collect::<Vec<_>>();
+    writeln!(out, "|{}|", separators.join("|"))?;
+    for parameter in parameters {
+        write!(
+            out,
+            "| `{}` | {} |",
+            identifier(parameter.localized(reference.locale).name),
+            table_cell(&describe_type(&parameter.typ, phrases))
+        )?;
+        if defaults {
+            let default = default_value(parameter).map_or_else(
+                || phrases.required.to_owned(),
+                |default| format!("`{default}`"),
+            );
+            write!(out, " {default} |")?;
+        }
+        let text = single_line(reference, parameter_text(parameter, reference.locale));
+        writeln!(out, " {} |", table_cell(&text))?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Renders the markup of a text; links point to the headings of the functions.
+fn markdown(reference: &Reference<'_>, text: &RichText) -> String {
+    let name = |target: &Identifier| reference.function_name(target);
+    text.document().to_markdown(&name, Some(&name))
+}
+
+/// Renders a text for places that cannot contain line breaks.
+fn single_line(reference: &Reference<'_>, text: &RichText) -> String {
+    markdown(reference, text).replace('\n', " ")
+}
+
+/// Keeps the text from breaking the table layout.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,27 @@
+use crate::load;
+use anyhow::bail;
+use gam3du_framework_common::message::{ClientToServerMessage, HandshakeMessage};
+use pico_args::Arguments;
+use std::{path::PathBuf, process::ExitCode};
+
+/// Prints the handshake a client built for the api descriptor has to send first.
+///
+/// Controllers talking JSON lines may send the printed line as it is; it carries the fingerprint
+/// the server compares with the one of its own descriptor.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let api_path: PathBuf = args.free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    let api = load(&api_path)?;
+    let handshake = ClientToServerMessage::Handshake(HandshakeMessage {
+        api: api.name.clone(),
+        version: api.version,
+        fingerprint: api.fingerprint(),
+    });
+    println!("{}", serde_json::to_string(&handshake)?);
+
+    Ok(ExitCode::SUCCESS)
+}
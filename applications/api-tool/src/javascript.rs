@@ -0,0 +1,53 @@
+use crate::load;
+use anyhow::{Context, bail};
+use pico_args::Arguments;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// Generates an ES module and its TypeScript typings for an api descriptor.
+///
+/// The typings will be written next to the module using the extension `.d.ts`.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let api_path: PathBuf = args.free_from_str()?;
+    let module_path: PathBuf = args.free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    let api = load(&api_path)?;
+    let typings_path = module_path.with_extension("d.ts");
+
+    write(&module_path, &api_path, |out| {
+        runtime_javascript_bindgen::generate_module(out, &api)
+    })?;
+    write(&typings_path, &api_path, |out| {
+        runtime_javascript_bindgen::generate_typings(out, &api)
+    })?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write(
+    out_path: &Path,
+    api_path: &Path,
+    generate: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> anyhow::Result<()> {
+    let file = File::create(out_path)
+        .with_context(|| format!("failed to create {}", out_path.display()))?;
+    let mut out = BufWriter::new(file);
+    writeln!(
+        out,
+        "// This file has been generated automatically and shall not be edited by hand!"
+    )?;
+    writeln!(out, "// generator: cargo api javascript")?;
+    writeln!(out, "// api descriptor: {}", api_path.display())?;
+    writeln!(out)?;
+    generate(&mut out).with_context(|| format!("failed to generate {}", out_path.display()))?;
+    out.flush()?;
+    Ok(())
+}
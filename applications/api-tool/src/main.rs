@@ -0,0 +1,81 @@
+//! Command line tool to inspect and compare api descriptors and generate code from them
+#![expect(
+    clippy::print_stdout,
+    clippy::print_stderr,
+    reason = "This is a normal thing to do for a console application"
+)]
+
+mod check;
+mod diff;
+mod docs;
+mod handshake;
+mod javascript;
+mod rust;
+mod schema;
+
+use anyhow::Context;
+use gam3du_framework_common::api::ApiDescriptor;
+use pico_args::Arguments;
+use std::{fs, path::Path, process::ExitCode};
+
+fn main() -> anyhow::Result<ExitCode> {
+    let mut args = Arguments::from_env();
+
+    let Some(subcommand) = args.subcommand()? else {
+        print_help();
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    match subcommand.as_str() {
+        "diff" => diff::run(args),
+        "docs" => docs::run(args),
+        "rust" => rust::run(args),
+        "javascript" => javascript::run(args),
+        "schema" => schema::run(args),
+        "check" => check::run(args),
+        "handshake" => handshake::run(args),
+        unknown => {
+            eprintln!("unknown command: {unknown}");
+            print_help();
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+fn print_help() {
+    eprintln!("Compare two revisions of an api descriptor and classify the changes:");
+    eprintln!("\tcargo api diff <old.api.json> <new.api.json>");
+    eprintln!();
+
+    eprintln!("Generate a reference as Markdown or HTML (chosen by the extension of the output):");
+    eprintln!("\tcargo api docs [--locale <de>] <api.json> <output.md|output.html>");
+    eprintln!();
+
+    eprintln!("Generate a typed Rust client (written to stdout if no output file is given):");
+    eprintln!("\tcargo api rust <api.json> [<output.rs>]");
+    eprintln!();
+
+    eprintln!("Generate a JavaScript client module and its TypeScript typings (<output.d.ts>):");
+    eprintln!("\tcargo api javascript <api.json> <output.mjs>");
+    eprintln!();
+
+    eprintln!(
+        "Export the JSON Schema of api descriptors (written to stdout if no output file is given):"
+    );
+    eprintln!("\tcargo api schema [<output.json>]");
+    eprintln!();
+
+    eprintln!("Check api descriptors against the schema and report all problems:");
+    eprintln!("\tcargo api check <api.json>...");
+    eprintln!();
+
+    eprintln!("Print the handshake a JSON lines controller has to send before any request:");
+    eprintln!("\tcargo api handshake <api.json>");
+    eprintln!();
+}
+
+/// Reads and validates the api descriptor at the given location.
+fn load(path: &Path) -> anyhow::Result<ApiDescriptor> {
+    let json = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    ApiDescriptor::load(&json).with_context(|| format!("failed to load {}", path.display()))
+}
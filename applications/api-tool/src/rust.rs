@@ -0,0 +1,49 @@
+use crate::load;
+use anyhow::{Context, bail};
+use gam3du_framework_common::api::ApiDescriptor;
+use pico_args::Arguments;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// Generates a typed Rust client for an api descriptor.
+///
+/// The code will be written to the given file or to stdout if no file has been given.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let api_path: PathBuf = args.free_from_str()?;
+    let out_path: Option<PathBuf> = args.opt_free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    let api = load(&api_path)?;
+
+    match out_path {
+        Some(out_path) => {
+            let file = File::create(&out_path)
+                .with_context(|| format!("failed to create {}", out_path.display()))?;
+            let mut out = BufWriter::new(file);
+            generate(&mut out, &api_path, &api)?;
+            out.flush()?;
+        }
+        None => generate(&mut io::stdout().lock(), &api_path, &api)?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn generate(out: &mut impl Write, api_path: &Path, api: &ApiDescriptor) -> anyhow::Result<()> {
+    writeln!(
+        out,
+        "// This file has been generated automatically and shall not be edited by hand!"
+    )?;
+    writeln!(out, "// generator: cargo api rust")?;
+    writeln!(out, "// api descriptor: {}", api_path.display())?;
+    writeln!(out)?;
+    runtime_rust_bindgen::generate(out, api)
+        .with_context(|| format!("failed to generate a client for {}", api_path.display()))
+}
@@ -0,0 +1,39 @@
+use anyhow::{Context, bail};
+use gam3du_framework_common::api::ApiDescriptor;
+use pico_args::Arguments;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+/// Writes the JSON Schema of api descriptors.
+///
+/// The schema will be written to the given file or to stdout if no file has been given.
+pub(crate) fn run(mut args: Arguments) -> anyhow::Result<ExitCode> {
+    let out_path: Option<PathBuf> = args.opt_free_from_str()?;
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        bail!("unexpected arguments: {remaining:?}");
+    }
+
+    match out_path {
+        Some(out_path) => {
+            let file = File::create(&out_path)
+                .with_context(|| format!("failed to create {}", out_path.display()))?;
+            let mut out = BufWriter::new(file);
+            generate(&mut out)?;
+            out.flush()?;
+        }
+        None => generate(&mut io::stdout().lock())?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn generate(out: &mut impl Write) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &ApiDescriptor::json_schema())?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -1,31 +1,40 @@
 #![allow(missing_docs, reason = "TODO remove before release")]
 #![expect(
     clippy::unwrap_used,
+    clippy::panic,
     // clippy::expect_used,
     reason = "TODO remove before release"
 )]
 
-use gam3du_framework_common::api::ApiDescriptor;
+use gam3du_framework_common::api::{ApiDescriptor, Capabilities};
 use runtime_python_bindgen::{Config, PyIdentifier};
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::Write;
 
 const API_DESCRIPTOR: &str = "control.api.json";
+const CAPABILITIES_DIR: &str = "capabilities";
+/// Exported by the engine from `EngineApi` in `engines/robot/src/api.rs`
+const PLUGIN_API_DESCRIPTOR: &str = "../../engines/robot/api.json";
+/// Name of the native module the engine offers to its plugin; see `engines/robot/src/plugin/python.rs`
+const PLUGIN_INTERNAL_MODULE_NAME: &str = "robot_plugin_api_internal";
 
 fn main() {
     println!("cargo::rerun-if-changed={API_DESCRIPTOR}");
+    println!("cargo::rerun-if-changed={CAPABILITIES_DIR}");
+    println!("cargo::rerun-if-changed={PLUGIN_API_DESCRIPTOR}");
 
     // TODO make the engine a command line parameter
-    let api_json = std::fs::read_to_string(API_DESCRIPTOR).unwrap();
-    let api: ApiDescriptor = serde_json::from_str(&api_json).unwrap();
+    let api_json = std::fs::read(API_DESCRIPTOR).unwrap();
+    let api =
+        ApiDescriptor::load(&api_json).unwrap_or_else(|error| panic!("{API_DESCRIPTOR}: {error}"));
     let api_name = api.name.file();
 
     // Generate sync api
     {
         let api_bindings = format!("python/control/{api_name}_api.py");
         let mut out = new_out_file(api_bindings);
-        write_header(&mut out);
+        write_header(&mut out, API_DESCRIPTOR);
         runtime_python_bindgen::generate(&mut out, &api, &Config { sync: true }).unwrap();
     }
 
@@ -33,9 +42,66 @@ fn main() {
     {
         let api_bindings = format!("python/control/{api_name}_api_async.py");
         let mut out = new_out_file(api_bindings);
-        write_header(&mut out);
+        write_header(&mut out, API_DESCRIPTOR);
         runtime_python_bindgen::generate(&mut out, &api, &Config { sync: false }).unwrap();
     }
+
+    // Generate localized apis
+    for locale in api.locales() {
+        let module_name = runtime_python_bindgen::localized_module_name(&api, locale);
+        let api_bindings = format!("python/control/{module_name}.py");
+        let mut out = new_out_file(api_bindings);
+        write_header(&mut out, API_DESCRIPTOR);
+        runtime_python_bindgen::generate_localized(&mut out, &api, locale).unwrap();
+    }
+
+    // Generate restricted apis
+    for entry in std::fs::read_dir(CAPABILITIES_DIR).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        println!("cargo::rerun-if-changed={}", path.display());
+        let capabilities = Capabilities::load(&std::fs::read(&path).unwrap(), &api)
+            .unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+        let module_name = runtime_python_bindgen::restricted_module_name(&api, &capabilities);
+        let api_bindings = format!("python/control/{module_name}.py");
+        let mut out = new_out_file(api_bindings);
+        write_header(&mut out, API_DESCRIPTOR);
+        runtime_python_bindgen::generate_restricted(&mut out, &api, &capabilities).unwrap();
+    }
+
+    // Generate stub of the plugin api for IDE support
+    {
+        let plugin_api_json = std::fs::read(PLUGIN_API_DESCRIPTOR).unwrap();
+        let plugin_api = ApiDescriptor::load(&plugin_api_json)
+            .unwrap_or_else(|error| panic!("{PLUGIN_API_DESCRIPTOR}: {error}"));
+        let api_stub = format!("python/plugin/{}_api.pyi", plugin_api.name.file());
+        let mut out = new_out_file(api_stub);
+        write_header(&mut out, PLUGIN_API_DESCRIPTOR);
+        runtime_python_bindgen::generate_native_stub(
+            &mut out,
+            &plugin_api,
+            PLUGIN_INTERNAL_MODULE_NAME,
+        )
+        .unwrap();
+    }
+
+    // Generate web api
+    {
+        let api_bindings = format!("web/static/{api_name}_api.mjs");
+        let mut out = new_out_file(api_bindings);
+        write_js_header(&mut out);
+        runtime_javascript_bindgen::generate_module(&mut out, &api).unwrap();
+    }
+
+    // Generate typings of the web api
+    {
+        let api_typings = format!("web/static/{api_name}_api.d.ts");
+        let mut out = new_out_file(api_typings);
+        write_js_header(&mut out);
+        runtime_javascript_bindgen::generate_typings(&mut out, &api).unwrap();
+    }
 }
 
 fn new_out_file(api_bindings: String) -> BufWriter<File> {
@@ -43,13 +109,24 @@ fn new_out_file(api_bindings: String) -> BufWriter<File> {
     BufWriter::new(out_file)
 }
 
-fn write_header(out: &mut BufWriter<File>) {
+fn write_header(out: &mut BufWriter<File>, api_descriptor: &str) {
     writeln!(
         out,
         "# This file has been generated automatically and shall not be edited by hand!"
     )
     .unwrap();
     writeln!(out, "# generator: applications/robot/build.rs").unwrap();
-    writeln!(out, "# api descriptor: {API_DESCRIPTOR}").unwrap();
+    writeln!(out, "# api descriptor: {api_descriptor}").unwrap();
+    writeln!(out).unwrap();
+}
+
+fn write_js_header(out: &mut BufWriter<File>) {
+    writeln!(
+        out,
+        "// This file has been generated automatically and shall not be edited by hand!"
+    )
+    .unwrap();
+    writeln!(out, "// generator: applications/robot/build.rs").unwrap();
+    writeln!(out, "// api descriptor: {API_DESCRIPTOR}").unwrap();
     writeln!(out).unwrap();
 }
@@ -1,12 +1,28 @@
 //! Native implementation of the Robot game
+//!
+//! By default, the robot is controlled by the embedded Python runtime. Passing `--listen <address>`
+//! waits for an external api client to connect via TCP instead, while `--http <address>` accepts
+//! calls as JSON via HTTP (see [`application_robot::webserver`]).
+//!
+//! Controllers written in any language may talk JSON lines (see [`JsonLinesApiServerEndpoint`]):
+//! `--stdio` reads requests from the standard input of this process, while
+//! `--spawn <program> [<arguments>…]` starts the controller as child process.
+//!
+//! Any of these sessions can be recorded to a file using `--record <file>` (see
+//! [`RecordingApiServerEndpoint`]). Passing `--replay <file>` feeds a recording back into the engine
+//! instead of running a controller.
+//!
+//! Passing `--capabilities <file>` only permits the functions listed in the given file (see
+//! [`Capabilities`]); all other requests will be rejected.
 
 #![expect(
     clippy::todo,
     clippy::unwrap_used,
+    clippy::panic,
     reason = "TODO remove before launch"
 )]
 
-use application_robot::APPLICATION_TITLE;
+use application_robot::{APPLICATION_TITLE, CONTROL_API_JSON, webserver::HttpGateway};
 use engine_robot::{plugin::PythonPlugin, GameLoop, GameState, RendererBuilder};
 use gam3du_framework::{
     application::{Application, GameLoopRunner},
@@ -14,23 +30,39 @@ use gam3du_framework::{
 };
 use gam3du_framework_common::module::Module;
 use gam3du_framework_common::{
-    api::ApiDescriptor,
-    api_channel::{NativeApiClientEndpoint, NativeApiServerEndpoint},
+    api::{ApiDescriptor, Capabilities},
+    api_channel::{
+        ApiServerEndpoint, DEFAULT_QUEUE_CAPACITY, JsonLinesApiServerEndpoint,
+        NativeApiClientEndpoint, NativeApiServerEndpoint, OverflowPolicy, QueueConfig,
+        RecordingApiServerEndpoint, ReplayApiServerEndpoint, RestrictedApiServerEndpoint,
+        TcpApiServerEndpoint, bounded_channel,
+    },
     event::{ApplicationEvent, FrameworkEvent},
 };
 use lib_file_storage::{FileStorage, StaticStorage};
+use pico_args::Arguments;
 use runtime_python::PythonRuntimeBuilder;
 use std::{
+    ffi::OsString,
     fmt::{self, Display},
+    net::TcpListener,
     path::{Path, PathBuf},
-    process::ExitCode,
-    sync::{self, mpsc::channel, Arc},
+    process::{Command, ExitCode},
+    sync::{
+        self,
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+        Arc,
+    },
     thread::JoinHandle,
 };
 use tracing::{debug, error, info};
 use web_time::Instant;
 use winit::event_loop::{ControlFlow, EventLoop};
 
+/// Tells the HTTP gateway to stop
+static EXIT_FLAG: AtomicBool = AtomicBool::new(false);
+
 fn main() -> ExitCode {
     match guarded_main() {
         Ok(()) => ExitCode::SUCCESS,
@@ -68,8 +100,59 @@ struct NativeGameLoopRunner {
 }
 
 impl NativeGameLoopRunner {
+    /// Like [`Self::new`], but records the traffic of the endpoint if a file has been given and
+    /// restricts it to the capabilities read from another file.
+    fn with_options(
+        robot_api_engine_endpoint: impl ApiServerEndpoint + 'static,
+        record_path: Option<&Path>,
+        capabilities_path: Option<&Path>,
+        game_state: Arc<sync::RwLock<Box<GameState>>>,
+        event_receiver: sync::mpsc::Receiver<FrameworkEvent>,
+    ) -> Self {
+        let Some(record_path) = record_path else {
+            return Self::restricted(
+                robot_api_engine_endpoint,
+                capabilities_path,
+                game_state,
+                event_receiver,
+            );
+        };
+
+        info!("recording api traffic to {}", record_path.display());
+        let recording_endpoint =
+            RecordingApiServerEndpoint::create(robot_api_engine_endpoint, record_path)
+                .unwrap_or_else(|error| panic!("{}: {error}", record_path.display()));
+        Self::restricted(
+            recording_endpoint,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    }
+
+    /// Like [`Self::new`], but rejects all functions not permitted by the capabilities file.
+    fn restricted(
+        robot_api_engine_endpoint: impl ApiServerEndpoint + 'static,
+        capabilities_path: Option<&Path>,
+        game_state: Arc<sync::RwLock<Box<GameState>>>,
+        event_receiver: sync::mpsc::Receiver<FrameworkEvent>,
+    ) -> Self {
+        let Some(capabilities_path) = capabilities_path else {
+            return Self::new(robot_api_engine_endpoint, game_state, event_receiver);
+        };
+
+        let capabilities_json = std::fs::read(capabilities_path)
+            .unwrap_or_else(|error| panic!("{}: {error}", capabilities_path.display()));
+        let capabilities = Capabilities::load(&capabilities_json, robot_api_engine_endpoint.api())
+            .unwrap_or_else(|error| panic!("{}: {error}", capabilities_path.display()));
+        info!("permitting only the functions of `{}`", capabilities.name);
+        let restricted_endpoint =
+            RestrictedApiServerEndpoint::new(robot_api_engine_endpoint, capabilities);
+        Self::new(restricted_endpoint, game_state, event_receiver)
+    }
+
     fn new(
-        robot_api_engine_endpoint: NativeApiServerEndpoint,
+        robot_api_engine_endpoint: impl ApiServerEndpoint + 'static,
         game_state: Arc<sync::RwLock<Box<GameState>>>,
         event_receiver: sync::mpsc::Receiver<FrameworkEvent>,
     ) -> Self {
@@ -131,11 +214,13 @@ impl GameLoopRunner for NativeGameLoopRunner {
 // #[expect(clippy::too_many_lines, reason = "TODO split this up later")]
 #[expect(clippy::unnecessary_wraps, reason = "TODO")]
 fn async_main() -> ApplicationResult<()> {
+    let options = Options::from_env();
+
     let mut storage = StaticStorage::default();
 
     storage.store(
         Path::new("applications/robot/control.api.json"),
-        include_bytes!("../../control.api.json").into(),
+        CONTROL_API_JSON.into(),
     );
 
     let window_event_loop = EventLoop::new().unwrap();
@@ -159,13 +244,7 @@ fn async_main() -> ApplicationResult<()> {
     //     window_event_receiver,
     // );
 
-    // let (python_thread, python_signal_handler, robot_api_engine_endpoint) = start_python_robot(
-    let (_python_thread, engine_server_endpoint) = start_python_robot(
-        &storage,
-        Path::new("applications/robot/control.api.json"),
-        Path::new("../applications/robot/python/control").to_path_buf(),
-        "robot",
-    );
+    let robot_api_descriptor_path = Path::new("applications/robot/control.api.json");
 
     // let (robot_api_script_endpoint, robot_api_engine_endpoint) = api::channel(robot_api);
 
@@ -174,8 +253,10 @@ fn async_main() -> ApplicationResult<()> {
     //     thread::spawn(move || {})
     // };
 
-    let game_loop_runner = NativeGameLoopRunner::new(
-        engine_server_endpoint,
+    let game_loop_runner = create_game_loop_runner(
+        options,
+        &storage,
+        robot_api_descriptor_path,
         Arc::clone(&shared_game_state),
         event_receiver,
     );
@@ -199,6 +280,9 @@ fn async_main() -> ApplicationResult<()> {
     drop(application);
     debug!("main: window event loop exited");
 
+    debug!("main: instruct webserver to stop now");
+    EXIT_FLAG.store(true, Ordering::Relaxed);
+
     // info!("Normal operation. Waiting for any task to terminate …");
     // let mut debug_timer = Instant::now();
     // loop {
@@ -382,18 +466,17 @@ fn start_python_robot(
     robot_api_descriptor_path: &Path,
     python_sys_path: PathBuf,
     python_main_module: impl Into<String> + Send + 'static,
+    queue: QueueConfig,
 ) -> (
     JoinHandle<()>,
     // UserSignalSender,
     NativeApiServerEndpoint,
 ) {
-    let api_json = storage
-        .get_content(Path::new(robot_api_descriptor_path))
-        .unwrap();
-    let api: ApiDescriptor = serde_json::from_slice(&api_json).unwrap();
+    let api = load_api(storage, robot_api_descriptor_path);
 
-    let (script_to_engine_sender, script_to_engine_receiver) = channel();
-    let (engine_to_script_sender, engine_to_script_receiver) = channel();
+    let (script_to_engine_sender, script_to_engine_receiver) = bounded_channel(queue);
+    let (engine_to_script_sender, engine_to_script_receiver) =
+        bounded_channel(response_queue(queue));
 
     let server_endpoint = NativeApiServerEndpoint::new(
         api.clone(),
@@ -425,6 +508,203 @@ fn start_python_robot(
     )
 }
 
+fn start_http_gateway(
+    storage: &dyn FileStorage,
+    robot_api_descriptor_path: &Path,
+    http_address: String,
+    queue: QueueConfig,
+) -> (JoinHandle<()>, NativeApiServerEndpoint) {
+    let api = load_api(storage, robot_api_descriptor_path);
+
+    let (gateway_to_engine_sender, gateway_to_engine_receiver) = bounded_channel(queue);
+    let (engine_to_gateway_sender, engine_to_gateway_receiver) =
+        bounded_channel(response_queue(queue));
+
+    let server_endpoint = NativeApiServerEndpoint::new(
+        api.clone(),
+        gateway_to_engine_receiver,
+        engine_to_gateway_sender,
+    );
+
+    let client_endpoint =
+        NativeApiClientEndpoint::new(api, gateway_to_engine_sender, engine_to_gateway_receiver);
+
+    let webserver_thread = std::thread::Builder::new()
+        .spawn(move || {
+            let mut gateway = HttpGateway::new(&*http_address, client_endpoint)
+                .unwrap_or_else(|error| panic!("cannot listen on {http_address}: {error}"));
+            info!("thread[webserver]: serving the robot api on http://{http_address}");
+            gateway.run(&EXIT_FLAG);
+            debug!("thread[webserver]: exit");
+        })
+        .unwrap();
+
+    (webserver_thread, server_endpoint)
+}
+
+/// The command line options
+struct Options {
+    listen_address: Option<String>,
+    http_address: Option<String>,
+    stdio: bool,
+    record_path: Option<PathBuf>,
+    replay_path: Option<PathBuf>,
+    capabilities_path: Option<PathBuf>,
+    controller: Option<String>,
+    controller_args: Vec<OsString>,
+    /// Limits the requests waiting for the engine
+    queue: QueueConfig,
+}
+
+impl Options {
+    fn from_env() -> Self {
+        let mut args = Arguments::from_env();
+        let listen_address = args.opt_value_from_str("--listen").unwrap();
+        let http_address = args.opt_value_from_str("--http").unwrap();
+        let stdio = args.contains("--stdio");
+        let record_path = args.opt_value_from_str("--record").unwrap();
+        let replay_path = args.opt_value_from_str("--replay").unwrap();
+        let capabilities_path = args.opt_value_from_str("--capabilities").unwrap();
+        let controller = args.opt_value_from_str("--spawn").unwrap();
+        let queue_capacity: Option<usize> = args.opt_value_from_str("--queue-capacity").unwrap();
+        let queue_policy: Option<OverflowPolicy> =
+            args.opt_value_from_str("--queue-policy").unwrap();
+        let controller_args = args.finish();
+
+        Self {
+            listen_address,
+            http_address,
+            stdio,
+            record_path,
+            replay_path,
+            capabilities_path,
+            controller,
+            controller_args,
+            queue: QueueConfig::new(
+                queue_capacity.unwrap_or(DEFAULT_QUEUE_CAPACITY),
+                queue_policy.unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Connects the engine to the controller chosen by the command line options.
+fn create_game_loop_runner(
+    options: Options,
+    storage: &dyn FileStorage,
+    robot_api_descriptor_path: &Path,
+    game_state: Arc<sync::RwLock<Box<GameState>>>,
+    event_receiver: sync::mpsc::Receiver<FrameworkEvent>,
+) -> NativeGameLoopRunner {
+    let Options {
+        listen_address,
+        http_address,
+        stdio,
+        record_path,
+        replay_path,
+        capabilities_path,
+        controller,
+        controller_args,
+        queue,
+    } = options;
+    let record_path = record_path.as_deref();
+    let capabilities_path = capabilities_path.as_deref();
+
+    if let Some(replay_path) = replay_path {
+        let api = load_api(storage, robot_api_descriptor_path);
+        info!("replaying api traffic from {}", replay_path.display());
+        let engine_server_endpoint = ReplayApiServerEndpoint::open(api, &replay_path)
+            .unwrap_or_else(|error| panic!("{}: {error}", replay_path.display()));
+
+        NativeGameLoopRunner::with_options(
+            engine_server_endpoint,
+            record_path,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    } else if let Some(listen_address) = listen_address {
+        let api = load_api(storage, robot_api_descriptor_path);
+        let listener = TcpListener::bind(&listen_address).unwrap();
+        info!("waiting for an api client to connect to {listen_address}");
+        let (stream, _) = listener.accept().unwrap();
+        let engine_server_endpoint = TcpApiServerEndpoint::with_queue(api, stream, queue).unwrap();
+        info!(
+            "api client connected from {}",
+            engine_server_endpoint.peer_addr()
+        );
+
+        NativeGameLoopRunner::with_options(
+            engine_server_endpoint,
+            record_path,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    } else if stdio || controller.is_some() {
+        let api = load_api(storage, robot_api_descriptor_path);
+        let engine_server_endpoint = if let Some(controller) = controller {
+            info!("starting controller `{controller}`");
+            JsonLinesApiServerEndpoint::spawn(
+                api,
+                Command::new(controller).args(controller_args),
+                queue,
+            )
+            .unwrap()
+        } else {
+            info!("reading requests from standard input");
+            JsonLinesApiServerEndpoint::stdio(api, queue).unwrap()
+        };
+
+        NativeGameLoopRunner::with_options(
+            engine_server_endpoint,
+            record_path,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    } else if let Some(http_address) = http_address {
+        let (_webserver_thread, engine_server_endpoint) =
+            start_http_gateway(storage, robot_api_descriptor_path, http_address, queue);
+
+        NativeGameLoopRunner::with_options(
+            engine_server_endpoint,
+            record_path,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    } else {
+        // let (python_thread, python_signal_handler, robot_api_engine_endpoint) = start_python_robot(
+        let (_python_thread, engine_server_endpoint) = start_python_robot(
+            storage,
+            robot_api_descriptor_path,
+            Path::new("../applications/robot/python/control").to_path_buf(),
+            "robot",
+            queue,
+        );
+
+        NativeGameLoopRunner::with_options(
+            engine_server_endpoint,
+            record_path,
+            capabilities_path,
+            game_state,
+            event_receiver,
+        )
+    }
+}
+
+/// Limits the messages waiting for a client; the engine must never wait for a client to catch up.
+fn response_queue(request_queue: QueueConfig) -> QueueConfig {
+    QueueConfig::new(request_queue.capacity, OverflowPolicy::Reject)
+}
+
+fn load_api(storage: &dyn FileStorage, api_descriptor_path: &Path) -> ApiDescriptor {
+    let api_json = storage.get_content(api_descriptor_path).unwrap();
+    ApiDescriptor::load(&api_json)
+        .unwrap_or_else(|error| panic!("{}: {error}", api_descriptor_path.display()))
+}
+
 type ApplicationResult<T> = Result<T, ApplicationError>;
 
 #[derive(Debug)]
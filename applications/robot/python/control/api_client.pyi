@@ -1,4 +1,4 @@
-from typing import Any, Union
+from typing import Any, Callable, Union
 
 # print("api_client.py loaded")
 
@@ -14,7 +14,65 @@ class Result:
     def value(self) -> Union[Any, BaseException]: ...
 
 
-def message(name: str, *parameter: Any) -> Handle: ...
+# Raised when the server rejected a request.
+class RequestError(RuntimeError): ...
+
+class UnknownFunctionError(RequestError): ...
+
+class WrongArgumentCountError(RequestError): ...
+
+class TypeMismatchError(RequestError): ...
+
+class OutOfRangeError(RequestError): ...
+
+class BusyError(RequestError): ...
+
+class CancelledError(RequestError): ...
+
+class EngineRejectedError(RequestError): ...
+
+class DeadlineExceededError(RequestError): ...
+
+class NotPermittedError(RequestError): ...
+
+class HandshakeRequiredError(RequestError): ...
+
+# The builtin `ConnectionError` is raised by `message`, `poll`, `subscribe`, `unsubscribe` and
+# `dispatch_events` once the server has said goodbye or vanished.
+
+
+# Arguments may be passed by position or by the names of their parameters (e.g. `duration=500`);
+# omitted parameters take their default values.
+# `timeout` is given in seconds; `None` uses the api's default timeout.
+def message(api_name: str, name: str, *parameter: Any, timeout: Union[float, None] = None, **keyword_parameter: Any) -> Handle: ...
+
+
+def set_default_timeout(api_name: str, timeout: Union[float, None]): ...
 
 
 def poll(name: Handle) -> Result: ...
+
+
+def cancel(name: Handle): ...
+
+
+# Calls made between `begin_batch` and `send_batch` are collected instead of being sent; polling
+# their handles yields `None` right away.
+def begin_batch(api_name: str): ...
+
+
+# Polling the handle yields a list with the result of each call performed; a failed call is the
+# last entry and represented by its exception.
+def send_batch(api_name: str, timeout: Union[float, None]) -> Handle: ...
+
+
+def discard_batch(api_name: str): ...
+
+
+def subscribe(api_name: str, event: str, handler: Callable[..., None]): ...
+
+
+def unsubscribe(api_name: str, event: str): ...
+
+
+def dispatch_events(api_name: str): ...
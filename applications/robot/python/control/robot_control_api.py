@@ -2,38 +2,124 @@
 # generator: applications/robot/build.rs
 # api descriptor: control.api.json
 
+"""A simple robot that can be moved across a 2D-plane and draw lines
+
+Once upon a time there was a lonely robot with the serial number ``#C0D1E``. …
+"""
+
 import robot_control_api_async
+from robot_control_api_async import RequestError, UnknownFunctionError, WrongArgumentCountError, TypeMismatchError, OutOfRangeError, BusyError, CancelledError, EngineRejectedError, DeadlineExceededError, NotPermittedError, HandshakeRequiredError
+
+def set_default_timeout(timeout: float | None):
+	"""Sets the number of seconds to wait for a response unless a function is given its own `timeout`
+
+	`None` waits indefinitely, which is the initial setting.
+	"""
+	robot_control_api_async.set_default_timeout(timeout)
+
+def set_height(height: float, *, timeout: float | None = None):
+	"""Sets the height of the tile at the robot's current location
 
-def set_height(height: float):
-	future = robot_control_api_async.set_height(height)
+	Args:
+		height: height of the tile
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	future = robot_control_api_async.set_height(height, timeout=timeout)
 	return future
 
-def move_forward(duration: int = 500) -> bool:
-	future = robot_control_api_async.move_forward(duration)
+def move_forward(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot move to the next tile in its current orientation
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	future = robot_control_api_async.move_forward(duration, timeout=timeout)
 	return future
 
-def jump(duration: int = 500) -> bool:
-	future = robot_control_api_async.jump(duration)
+def jump(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot jump to the next tile in its current orientation
+
+	Args:
+		duration: Duration of the jump animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	future = robot_control_api_async.jump(duration, timeout=timeout)
 	return future
 
-def draw_forward(duration: int = 500) -> bool:
-	future = robot_control_api_async.draw_forward(duration)
+def draw_forward(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot move to the next tile in its current orientation and draw a line onto the floor
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	future = robot_control_api_async.draw_forward(duration, timeout=timeout)
 	return future
 
-def turn_left(duration: int = 300):
-	future = robot_control_api_async.turn_left(duration)
+def turn_left(duration: int = 300, *, timeout: float | None = None):
+	"""Turns the robot 45° in a counter-clockwise direction
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `300`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	future = robot_control_api_async.turn_left(duration, timeout=timeout)
 	return future
 
-def turn_right(duration: int = 300):
-	future = robot_control_api_async.turn_right(duration)
+def turn_right(duration: int = 300, *, timeout: float | None = None):
+	"""Turns the robot 45° in a clockwise direction
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `300`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	future = robot_control_api_async.turn_right(duration, timeout=timeout)
 	return future
 
-def robot_color_rgb(red: float, green: float, blue: float):
-	future = robot_control_api_async.robot_color_rgb(red, green, blue)
+def robot_color_rgb(red: float, green: float, blue: float, *, timeout: float | None = None):
+	"""Set the color of the robot
+
+	Args:
+		red: Amount of red (0.0 to 1.0)
+		green: Amount of green (0.0 to 1.0)
+		blue: Amount of blue (0.0 to 1.0)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	future = robot_control_api_async.robot_color_rgb(red, green, blue, timeout=timeout)
 	return future
 
-def paint_tile():
-	future = robot_control_api_async.paint_tile()
+def paint_tile(*, timeout: float | None = None):
+	"""Fill the current tile with the robots current color
+
+	Args:
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	future = robot_control_api_async.paint_tile(timeout=timeout)
 	return future
 
+def on_robot_stopped(handler):
+	"""The robot finished its current animation
+
+	The robot stopped moving, turning or jumping, either because the animation has been completed or because it has been cut short
+
+	Args:
+		handler: called without arguments whenever the event occurs
+	"""
+	robot_control_api_async.on_robot_stopped(handler)
+
+def dispatch_events():
+	robot_control_api_async.dispatch_events()
+
+from robot_control_api_async import batch
+
 
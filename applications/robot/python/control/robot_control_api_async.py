@@ -2,78 +2,241 @@
 # generator: applications/robot/build.rs
 # api descriptor: control.api.json
 
-import api_client
+"""A simple robot that can be moved across a 2D-plane and draw lines
 
-def set_height(height: float):
-	handle = api_client.message("set height", height)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def move_forward(duration: int = 500) -> bool:
-	handle = api_client.message("move forward", duration)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def jump(duration: int = 500) -> bool:
-	handle = api_client.message("jump", duration)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def draw_forward(duration: int = 500) -> bool:
-	handle = api_client.message("draw forward", duration)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def turn_left(duration: int = 300):
-	handle = api_client.message("turn left", duration)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def turn_right(duration: int = 300):
-	handle = api_client.message("turn right", duration)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def robot_color_rgb(red: float, green: float, blue: float):
-	handle = api_client.message("robot color rgb", red, green, blue)
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
-
-
-def paint_tile():
-	handle = api_client.message("paint tile")
-	while True:
-		result = api_client.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
+Once upon a time there was a lonely robot with the serial number ``#C0D1E``. …
+"""
 
+import api_client
+from api_client import RequestError, UnknownFunctionError, WrongArgumentCountError, TypeMismatchError, OutOfRangeError, BusyError, CancelledError, EngineRejectedError, DeadlineExceededError, NotPermittedError, HandshakeRequiredError
+
+def set_default_timeout(timeout: float | None):
+	"""Sets the number of seconds to wait for a response unless a function is given its own `timeout`
+
+	`None` waits indefinitely, which is the initial setting.
+	"""
+	api_client.set_default_timeout("robot control", timeout)
+
+def set_height(height: float, *, timeout: float | None = None):
+	"""Sets the height of the tile at the robot's current location
+
+	Args:
+		height: height of the tile
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	handle = api_client.message("robot control", "set height", height, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def move_forward(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot move to the next tile in its current orientation
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	handle = api_client.message("robot control", "move forward", duration, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def jump(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot jump to the next tile in its current orientation
+
+	Args:
+		duration: Duration of the jump animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	handle = api_client.message("robot control", "jump", duration, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def draw_forward(duration: int = 500, *, timeout: float | None = None) -> bool:
+	"""Makes the robot move to the next tile in its current orientation and draw a line onto the floor
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `500`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+
+	Returns:
+		`bool`
+	"""
+	handle = api_client.message("robot control", "draw forward", duration, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def turn_left(duration: int = 300, *, timeout: float | None = None):
+	"""Turns the robot 45° in a counter-clockwise direction
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `300`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	handle = api_client.message("robot control", "turn left", duration, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def turn_right(duration: int = 300, *, timeout: float | None = None):
+	"""Turns the robot 45° in a clockwise direction
+
+	Args:
+		duration: Duration of the move animation. The duration is in milliseconds and must be smaller than 10000. (Default: `300`)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	handle = api_client.message("robot control", "turn right", duration, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def robot_color_rgb(red: float, green: float, blue: float, *, timeout: float | None = None):
+	"""Set the color of the robot
+
+	Args:
+		red: Amount of red (0.0 to 1.0)
+		green: Amount of green (0.0 to 1.0)
+		blue: Amount of blue (0.0 to 1.0)
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	handle = api_client.message("robot control", "robot color rgb", red, green, blue, timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def paint_tile(*, timeout: float | None = None):
+	"""Fill the current tile with the robots current color
+
+	Args:
+		timeout: seconds to wait for the response; `None` uses the default timeout
+	"""
+	handle = api_client.message("robot control", "paint tile", timeout=timeout)
+	try:
+		while True:
+			result = api_client.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		api_client.cancel(handle)
+		raise
+
+
+def on_robot_stopped(handler):
+	"""The robot finished its current animation
+
+	The robot stopped moving, turning or jumping, either because the animation has been completed or because it has been cut short
+
+	Args:
+		handler: called without arguments whenever the event occurs
+	"""
+	api_client.subscribe("robot control", "robot stopped", handler)
+
+def dispatch_events():
+	api_client.dispatch_events("robot control")
+
+class batch:
+	"""Collects all calls made within a `with` block and sends them to the server at once
+
+	The server performs the calls one after another and stops at the first failure. Within the
+	block, calls return `None` right away. Once the block has been left, `results` lists the
+	outcome of each call performed; a failed call is the last entry and its exception is raised.
+
+	Args:
+		timeout: seconds to wait for the whole batch; `None` waits indefinitely
+	"""
+
+	def __init__(self, *, timeout: float | None = None):
+		self.timeout = timeout
+		self.results = None
+
+	def __enter__(self):
+		api_client.begin_batch("robot control")
+		return self
+
+	def __exit__(self, exception_type, exception, traceback):
+		if exception is not None:
+			# nothing has been sent yet, so the calls are dropped along with the block
+			api_client.discard_batch("robot control")
+			return False
+		handle = api_client.send_batch("robot control", self.timeout)
+		try:
+			while True:
+				result = api_client.poll(handle)
+				if result.is_done():
+					self.results = result.get_value()
+					break
+		except KeyboardInterrupt:
+			# the script has been stopped while waiting, so the server shall stop as well
+			api_client.cancel(handle)
+			raise
+		if self.results and isinstance(self.results[-1], BaseException):
+			raise self.results[-1]
+		return False
 
 
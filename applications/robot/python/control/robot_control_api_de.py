@@ -0,0 +1,116 @@
+# This file has been generated automatically and shall not be edited by hand!
+# generator: applications/robot/build.rs
+# api descriptor: control.api.json
+
+"""Ein einfacher Roboter, der sich über eine Ebene bewegen und Linien zeichnen kann
+
+Once upon a time there was a lonely robot with the serial number ``#C0D1E``. …
+"""
+
+import robot_control_api
+from robot_control_api import RequestError, UnknownFunctionError, WrongArgumentCountError, TypeMismatchError, OutOfRangeError, BusyError, CancelledError, EngineRejectedError, DeadlineExceededError, NotPermittedError, HandshakeRequiredError
+
+def setze_höhe(höhe: float, *, timeout: float | None = None):
+	"""Setzt die Höhe des Feldes, auf dem der Roboter steht
+
+	Args:
+		höhe: Höhe des Feldes
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+	"""
+	return robot_control_api.set_height(höhe, timeout=timeout)
+
+def gehe_vorwärts(dauer: int = 500, *, timeout: float | None = None) -> bool:
+	"""Bewegt den Roboter auf das nächste Feld in Blickrichtung
+
+	Args:
+		dauer: Dauer der Bewegung in Millisekunden; sie muss kleiner als 10000 sein. (Standardwert: `500`)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+
+	Returns:
+		`bool`: ob der Roboter das nächste Feld erreicht hat
+	"""
+	if not 0 <= dauer < 10000:
+		raise ValueError("dauer muss zwischen 0 und 9999 liegen, ist aber " + repr(dauer))
+	return robot_control_api.move_forward(dauer, timeout=timeout)
+
+def springe(dauer: int = 500, *, timeout: float | None = None) -> bool:
+	"""Lässt den Roboter auf das nächste Feld in Blickrichtung springen
+
+	Args:
+		dauer: Dauer des Sprungs in Millisekunden; sie muss kleiner als 10000 sein. (Standardwert: `500`)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+
+	Returns:
+		`bool`: ob der Roboter das nächste Feld erreicht hat
+	"""
+	if not 0 <= dauer < 10000:
+		raise ValueError("dauer muss zwischen 0 und 9999 liegen, ist aber " + repr(dauer))
+	return robot_control_api.jump(dauer, timeout=timeout)
+
+def zeichne_vorwärts(dauer: int = 500, *, timeout: float | None = None) -> bool:
+	"""Bewegt den Roboter auf das nächste Feld in Blickrichtung und zeichnet dabei eine Linie auf den Boden
+
+	Args:
+		dauer: Dauer der Bewegung in Millisekunden; sie muss kleiner als 10000 sein. (Standardwert: `500`)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+
+	Returns:
+		`bool`: ob der Roboter das nächste Feld erreicht hat
+	"""
+	if not 0 <= dauer < 10000:
+		raise ValueError("dauer muss zwischen 0 und 9999 liegen, ist aber " + repr(dauer))
+	return robot_control_api.draw_forward(dauer, timeout=timeout)
+
+def drehe_links(dauer: int = 300, *, timeout: float | None = None):
+	"""Dreht den Roboter um 45° gegen den Uhrzeigersinn
+
+	Args:
+		dauer: Dauer der Drehung in Millisekunden; sie muss kleiner als 10000 sein. (Standardwert: `300`)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+	"""
+	if not 0 <= dauer < 10000:
+		raise ValueError("dauer muss zwischen 0 und 9999 liegen, ist aber " + repr(dauer))
+	return robot_control_api.turn_left(dauer, timeout=timeout)
+
+def drehe_rechts(dauer: int = 300, *, timeout: float | None = None):
+	"""Dreht den Roboter um 45° im Uhrzeigersinn
+
+	Args:
+		dauer: Dauer der Drehung in Millisekunden; sie muss kleiner als 10000 sein. (Standardwert: `300`)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+	"""
+	if not 0 <= dauer < 10000:
+		raise ValueError("dauer muss zwischen 0 und 9999 liegen, ist aber " + repr(dauer))
+	return robot_control_api.turn_right(dauer, timeout=timeout)
+
+def roboterfarbe_rgb(rot: float, grün: float, blau: float, *, timeout: float | None = None):
+	"""Setzt die Farbe des Roboters
+
+	Args:
+		rot: Rotanteil (0.0 bis 1.0)
+		grün: Grünanteil (0.0 bis 1.0)
+		blau: Blauanteil (0.0 bis 1.0)
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+	"""
+	return robot_control_api.robot_color_rgb(rot, grün, blau, timeout=timeout)
+
+def male_feld(*, timeout: float | None = None):
+	"""Füllt das aktuelle Feld mit der aktuellen Farbe des Roboters
+
+	Args:
+		timeout: Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit
+	"""
+	return robot_control_api.paint_tile(timeout=timeout)
+
+def on_roboter_angehalten(handler):
+	"""Der Roboter hat seine aktuelle Animation beendet
+
+	Der Roboter hat aufgehört, sich zu bewegen, zu drehen oder zu springen, weil die Animation entweder abgeschlossen oder abgebrochen wurde
+	"""
+	robot_control_api.on_robot_stopped(handler)
+
+from robot_control_api import dispatch_events
+
+from robot_control_api import set_default_timeout
+from robot_control_api import batch
+
@@ -0,0 +1,20 @@
+# This file has been generated automatically and shall not be edited by hand!
+# generator: applications/robot/build.rs
+# api descriptor: control.api.json
+
+"""A simple robot that can be moved across a 2D-plane and draw lines
+
+Once upon a time there was a lonely robot with the serial number ``#C0D1E``. …
+
+Only the functions available in ``lesson 1`` are offered.
+"""
+
+from robot_control_api import RequestError, UnknownFunctionError, WrongArgumentCountError, TypeMismatchError, OutOfRangeError, BusyError, CancelledError, EngineRejectedError, DeadlineExceededError, NotPermittedError, HandshakeRequiredError
+
+from robot_control_api import move_forward
+from robot_control_api import turn_left
+from robot_control_api import on_robot_stopped
+from robot_control_api import dispatch_events
+from robot_control_api import set_default_timeout
+from robot_control_api import batch
+
@@ -2,3 +2,19 @@
 
 def send_boolean_response(api_name: str, request_id: int, value: bool):
 	pass
+
+# Answers a request with a value of the type returned by the function being called;
+# functions without return type are answered by `None`.
+def send_response(api_name: str, request_id: int, value):
+	pass
+
+# Rejects a request because another one is still being processed.
+def send_busy_response(api_name: str, request_id: int):
+	pass
+
+# Rejects a request for the given reason.
+def send_rejected_response(api_name: str, request_id: int, reason: str):
+	pass
+
+def send_event(api_name: str, event_name: str, *parameter):
+	pass
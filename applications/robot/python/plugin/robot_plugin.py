@@ -1,9 +1,10 @@
 from api_server import (
-    send_boolean_response
+    send_boolean_response, send_busy_response, send_event
 )
 
 from robot_plugin_api import (
-    move_forward, paint_tile, robot_color_rgb, turn, log_trace, log_debug, log_error, log_info, log_warn, set_height, jump
+    move_forward, paint_tile, robot_color_rgb, turn, log_trace, log_debug, log_error, log_info, log_warn, set_height, jump,
+    stop
 )
 
 log_info("robot_plugin.py loaded")
@@ -13,16 +14,32 @@ current_command = 0
 def on_robot_stopped():
     global current_command
     log_trace("on_robot_stopped(%s)" % hex(current_command))
+    # clients are notified even if they did not wait for the animation
+    send_event("robot control", "robot stopped")
+    if not current_command:
+        # no command is waiting for the robot (e.g. it has been cancelled)
+        return
 
     send_boolean_response("robot control", current_command, True)
     log_trace("resetting command(%s)" % hex(current_command))
     current_command = 0
 
+def on_cancel(request_id):
+    global current_command
+    log_trace("on_cancel(%s)" % hex(request_id))
+    if current_command != request_id:
+        return
+
+    # the robot must not report the end of the animation for a cancelled command
+    current_command = 0
+    stop()
+
 def on_set_height(request_id, height):
     global current_command
     log_trace("on_set_height(%s, %s)" % (hex(request_id), height))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     set_height(height)
     send_boolean_response("robot control", request_id, True)
@@ -31,7 +48,8 @@ def on_move_forward(request_id, duration):
     global current_command
     log_trace("on_move_forward(%s, %s)" % (hex(request_id), duration))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     if not move_forward(False, duration):
         send_boolean_response("robot control", request_id, False)
@@ -42,7 +60,8 @@ def on_jump(request_id, duration):
     global current_command
     log_trace("on_jump(%s, %s)" % (hex(request_id), duration))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     if not jump(duration):
         send_boolean_response("robot control", request_id, False)
@@ -53,7 +72,8 @@ def on_draw_forward(request_id, duration):
     global current_command
     log_trace("on_draw_forward(%s, %s)" % (hex(request_id), duration))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     if not move_forward(True, duration):
         send_boolean_response("robot control", request_id, False)
@@ -64,7 +84,8 @@ def on_turn_left(request_id, duration):
     global current_command
     log_trace("on_turn_left(%s, %s)" % (hex(request_id), duration))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     turn(1, duration)
     current_command = request_id
@@ -73,7 +94,8 @@ def on_turn_right(request_id, duration):
     global current_command
     log_trace("on_turn_right(%s, %s)" % (hex(request_id), duration))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     turn(-1, duration)
     current_command = request_id
@@ -82,7 +104,8 @@ def on_robot_color_rgb(request_id, red, green, blue):
     global current_command
     log_trace("on_robot_color_rgb(%s, [%s, %s, %s])" % (hex(request_id), red, green, blue))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     robot_color_rgb(red, green, blue)
     send_boolean_response("robot control", request_id, True)
@@ -91,7 +114,8 @@ def on_paint_tile(request_id):
     global current_command
     log_trace("on_paint_tile(%s)" % hex(request_id))
     if current_command:
-        log_error("pending command")
+        send_busy_response("robot control", request_id)
+        return
 
     paint_tile()
     send_boolean_response("robot control", request_id, True)
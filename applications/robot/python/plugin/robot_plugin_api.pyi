@@ -1,37 +1,72 @@
-# TODO this should be generated
+# This file has been generated automatically and shall not be edited by hand!
+# generator: applications/robot/build.rs
+# api descriptor: ../../engines/robot/api.json
 
-# print("robot_plugin_api.py loaded")
+from robot_plugin_api_internal import *
 
+def set_height(height: float):
+	"""Sets the height of the tile at the robot's current location
 
-def set_height(height: float) -> bool:
-	pass
+	Args:
+		height: height of the tile
+	"""
+	...
 
 def move_forward(draw: bool, duration: int = 500) -> bool:
-	pass
+	"""Makes the robot move to the next tile in its current orientation
+
+	Makes the robot move to the next tile in its current orientation
+
+	Returns ``false`` if the robot was unable to move.
+
+	Args:
+		draw: draw a line onto the floor while moving
+		duration: duration of the move animation in milliseconds (Default: `500`)
+
+	Returns:
+		`bool`
+	"""
+	...
 
 def jump(duration: int = 500) -> bool:
-	pass
+	"""Makes the robot jump to the next tile in its current orientation
 
-def turn(steps_ccw: int, duration: int = 300):
-	pass
+	Makes the robot jump to the next tile in its current orientation
 
-def robot_color_rgb(red: float, green: float, blue: float):
-	pass
+	Returns ``false`` if the robot was unable to jump.
 
-def paint_tile():
-	pass
+	Args:
+		duration: duration of the jump animation in milliseconds (Default: `500`)
 
-def log_error(message: str):
-	pass
+	Returns:
+		`bool`
+	"""
+	...
 
-def log_warn(message: str):
-	pass
+def turn(steps_ccw: int, duration: int = 300):
+	"""Makes the robot turn in place
+
+	Args:
+		steps_ccw: 45° steps to turn counterclockwise (negative values turn clockwise)
+		duration: duration of the turn animation in milliseconds (Default: `300`)
+	"""
+	...
+
+def robot_color_rgb(red: float, green: float, blue: float):
+	"""Sets the color of the robot
+
+	Args:
+		red: red component between 0.0 and 1.0
+		green: green component between 0.0 and 1.0
+		blue: blue component between 0.0 and 1.0
+	"""
+	...
 
-def log_info(message: str):
-	pass
+def paint_tile():
+	"""Paints the tile at the robot's current location"""
+	...
 
-def log_debug(message: str):
-	pass
+def stop():
+	"""Stops the robot by completing its current animation immediately"""
+	...
 
-def log_trace(message: str):
-	pass
@@ -0,0 +1,22 @@
+# Stub for IDE support only.
+# The actual module is provided by the engine, see `engines/robot/src/plugin/python.rs`.
+# The api functions wrapping `call` are covered by the generated `robot_plugin_api.pyi`.
+
+# Calls the api function of the given name; prefer the wrappers in `robot_plugin_api`.
+def call(function: str, *arguments):
+	pass
+
+def log_error(message: str):
+	pass
+
+def log_warn(message: str):
+	pass
+
+def log_info(message: str):
+	pass
+
+def log_debug(message: str):
+	pass
+
+def log_trace(message: str):
+	pass
@@ -19,6 +19,14 @@
 /// Name to be used as readable window title for this application
 pub const APPLICATION_TITLE: &str = "Robot";
 
+/// The descriptor of the api controlling the robot.
+///
+/// All parts of the application embed this very copy, so they cannot disagree about the api.
+pub const CONTROL_API_JSON: &str = include_str!("../control.api.json");
+
+#[cfg(not(target_family = "wasm"))]
+pub mod webserver;
+
 // ///////////////////////// native section /////////////////////////
 
 // #[cfg(not(target_family = "wasm"))]
@@ -1,59 +1,321 @@
-// static EXIT_FLAG: AtomicBool = AtomicBool::new(false);
-
-// let webserver_thread = {
-//     let event_sender = event_sender.clone();
-//     let api = api.clone();
-//     thread::spawn(move || {
-//         debug!("thread[webserver]: starting server");
-//         http_server(&event_sender, &api, &EXIT_FLAG);
-//         debug!("thread[webserver]: exit");
-//     })
-// };
-
-// debug!("thread[game loop]: instruct webserver to stop now");
-// EXIT_FLAG.store(true, Ordering::Relaxed);
-
-// debug!("Waiting for webserver to exit …");
-// webserver_thread.join().unwrap();
-
-// fn http_server(command_sender: &Sender<EngineEvent>, api: &Api, exit_flag: &'static AtomicBool) {
-//     let server = Server::http("0.0.0.0:8000").unwrap();
-
-//     'next_request: loop {
-//         let request = match server.recv_timeout(Duration::from_millis(50)) {
-//             Ok(Some(request)) => request,
-//             Ok(None) => {
-//                 if exit_flag.load(Ordering::Relaxed) {
-//                     break 'next_request;
-//                 }
-//                 continue 'next_request;
-//             }
-//             Err(error) => {
-//                 error!("{error}");
-//                 break 'next_request;
-//             }
-//         };
-
-//         let url = request.url();
-//         let Some(url) = url.strip_prefix(&format!("/{}/", api.name)) else {
-//             request
-//                 .respond(Response::from_string("unknown api").with_status_code(404))
-//                 .unwrap();
-//             continue;
-//         };
-
-//         let command = Identifier(url.to_owned());
-
-//         let response = Response::from_string(format!("{command:?}"));
-
-//         // FIXME: Extract parameters from response
-//         command_sender
-//             .send(EngineEvent::RobotEvent {
-//                 command: Identifier(url.to_owned()),
-//                 parameters: vec![],
-//             })
-//             .unwrap();
-
-//         request.respond(response).unwrap();
-//     }
-// }
+//! Exposes an api via HTTP, so the robot can be controlled by anything able to send JSON.
+//!
+//! - `GET /{api}` returns the [`ApiDescriptor`](gam3du_framework_common::api::ApiDescriptor) of
+//!   the api
+//! - `POST /{api}/{function}` calls a function; the body is a JSON object mapping parameter names
+//!   to their arguments (e.g. `{"duration": 500}`), omitted parameters take their default value
+//!
+//! Names containing spaces need to be percent-encoded, e.g. `/robot%20control/move%20forward`.
+//!
+//! A successful call answers with `{"result": …}`. Otherwise the answer is
+//! `{"error": "<kind>", "message": "…"}` with a fitting status code, where `kind` names the variant
+//! of [`RequestError`] or is `InvalidArguments` if the body did not fit the function's parameters.
+//! Once the engine has gone away, all calls are answered with `503 Service Unavailable` and the
+//! kind `Disconnected`. While the engine cannot keep up with the calls, they are answered with the
+//! same status but the kind `Busy`. If the engine rejected the gateway's handshake, the gateway
+//! does not forward any calls and answers them with the same status but the kind
+//! `HandshakeRejected`.
+
+use gam3du_framework_common::{
+    api::{FunctionDescriptor, Identifier, Value},
+    api_channel::{ApiClientEndpoint, ChannelError},
+    message::{HandshakeError, RequestError, ServerToClientMessage},
+};
+use serde_json::json;
+use std::{
+    error::Error,
+    io::Cursor,
+    net::ToSocketAddrs,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use tracing::{debug, error, warn};
+
+/// The time a call may take unless configured otherwise using [`HttpGateway::set_timeout`]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Forwards HTTP requests to the api server connected to the given endpoint.
+pub struct HttpGateway<Endpoint: ApiClientEndpoint> {
+    server: Server,
+    endpoint: Endpoint,
+    timeout: Duration,
+    /// The reason the api server refused the gateway, if it did
+    rejected: Option<HandshakeError>,
+}
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+impl<Endpoint: ApiClientEndpoint> HttpGateway<Endpoint> {
+    /// Starts listening for HTTP requests on the given address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be used for listening.
+    pub fn new(
+        address: impl ToSocketAddrs,
+        endpoint: Endpoint,
+    ) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+        let server = Server::http(address)?;
+        Ok(Self {
+            server,
+            endpoint,
+            timeout: DEFAULT_TIMEOUT,
+            rejected: None,
+        })
+    }
+
+    /// Limits the time the server may take to answer a call.
+    ///
+    /// Calls exceeding this time will be cancelled and answered with `504 Gateway Timeout`.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Answers HTTP requests until the `exit_flag` has been set.
+    pub fn run(&mut self, exit_flag: &AtomicBool) {
+        if let Err(error) = self.endpoint.handshake() {
+            warn!("cannot greet the api server: {error}");
+        }
+
+        'next_request: loop {
+            let mut request = match self.server.recv_timeout(Duration::from_millis(50)) {
+                Ok(Some(request)) => request,
+                Ok(None) => {
+                    if exit_flag.load(Ordering::Relaxed) {
+                        break 'next_request;
+                    }
+                    continue 'next_request;
+                }
+                Err(error) => {
+                    error!("{error}");
+                    break 'next_request;
+                }
+            };
+
+            debug!("{} {}", request.method(), request.url());
+            let response = self.handle(&mut request);
+            if let Err(error) = request.respond(response) {
+                warn!("failed to send http response: {error}");
+            }
+        }
+    }
+
+    fn handle(&mut self, request: &mut Request) -> HttpResponse {
+        let url = request.url();
+        let path = url.split_once('?').map_or(url, |(path, _query)| path);
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .map(percent_decode)
+            .collect::<Option<Vec<_>>>();
+
+        let api = self.endpoint.api();
+        let (api_name, function_name) = match segments.as_deref() {
+            Some([api_name]) => (api_name, None),
+            Some([api_name, function_name]) => (api_name, Some(function_name)),
+            _ => return error_response(404, "NotFound", "unknown location"),
+        };
+        if *api_name != api.name.0 {
+            return error_response(404, "NotFound", &format!("unknown api {api_name:?}"));
+        }
+
+        match (request.method(), function_name) {
+            (Method::Get, None) => json_response(200, &json!(api)),
+            (Method::Post, Some(function_name)) => {
+                let function_name = match Identifier::try_from(function_name.clone()) {
+                    Ok(function_name) => function_name,
+                    Err(error) => {
+                        let message = format!("invalid function name {function_name:?}: {error}");
+                        return error_response(404, "UnknownFunction", &message);
+                    }
+                };
+                let Some(function) = api.functions.get(&function_name).cloned() else {
+                    let error = RequestError::UnknownFunction {
+                        function: function_name,
+                    };
+                    return request_error_response(&error);
+                };
+                match read_arguments(request, &function) {
+                    Ok(arguments) => self
+                        .call(function, arguments)
+                        .unwrap_or_else(|error| channel_error_response(&error)),
+                    Err(message) => error_response(400, "InvalidArguments", &message),
+                }
+            }
+            _ => error_response(405, "MethodNotAllowed", "method not allowed"),
+        }
+    }
+
+    /// Sends a request and waits for the server to answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has gone away.
+    fn call(
+        &mut self,
+        function: FunctionDescriptor,
+        arguments: Vec<Value>,
+    ) -> Result<HttpResponse, ChannelError> {
+        if let Some(error) = &self.rejected {
+            return Ok(handshake_error_response(error));
+        }
+        let id = self.endpoint.send_command_with_timeout(
+            function.name,
+            arguments,
+            Some(self.timeout),
+        )?;
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            match self.endpoint.poll_response()? {
+                Some(ServerToClientMessage::Response(response)) if response.id == id => {
+                    let result = json!({ "result": response.result.to_json() });
+                    return Ok(json_response(200, &result));
+                }
+                Some(ServerToClientMessage::ErrorResponse(response)) if response.id == id => {
+                    return Ok(request_error_response(&response.error));
+                }
+                Some(ServerToClientMessage::Cancelled(cancelled)) if cancelled == id => {
+                    return Ok(request_error_response(&RequestError::Cancelled));
+                }
+                Some(ServerToClientMessage::HandshakeResponse(response)) => {
+                    if let Err(error) = response.result {
+                        error!("the api server rejected the gateway: {error}");
+                        let rejected = handshake_error_response(&error);
+                        self.rejected = Some(error);
+                        return Ok(rejected);
+                    }
+                }
+                Some(ServerToClientMessage::Goodbye) => return Err(ChannelError::Disconnected),
+                Some(message) => debug!("ignoring unrelated message: {message:?}"),
+                None if Instant::now() >= deadline => {
+                    self.endpoint.cancel(id)?;
+                    return Ok(request_error_response(&RequestError::DeadlineExceeded));
+                }
+                None => thread::sleep(Duration::from_millis(1)),
+            }
+        }
+    }
+}
+
+/// Reads the body of a call and checks it against the function's parameters.
+fn read_arguments(
+    request: &mut Request,
+    function: &FunctionDescriptor,
+) -> Result<Vec<Value>, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|error| format!("failed to read request body: {error}"))?;
+
+    let json = if body.trim().is_empty() {
+        serde_json::Value::Object(serde_json::Map::new())
+    } else {
+        serde_json::from_str(&body).map_err(|error| format!("malformed JSON: {error}"))?
+    };
+    let serde_json::Value::Object(arguments) = json else {
+        return Err("expected a JSON object mapping parameter names to arguments".to_owned());
+    };
+
+    function
+        .arguments_from_json(&arguments)
+        .map_err(|error| error.to_string())
+}
+
+/// Decodes `%XX` sequences within a single segment of a path.
+fn percent_decode(segment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut remaining = segment.bytes();
+    while let Some(byte) = remaining.next() {
+        if byte == b'%' {
+            let high = char::from(remaining.next()?).to_digit(16)?;
+            let low = char::from(remaining.next()?).to_digit(16)?;
+            bytes.push(u8::try_from(high * 16 + low).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn request_error_response(error: &RequestError) -> HttpResponse {
+    let status = match error {
+        RequestError::UnknownFunction { .. } => 404,
+        RequestError::WrongArgumentCount { .. }
+        | RequestError::TypeMismatch { .. }
+        | RequestError::OutOfRange { .. } => 400,
+        RequestError::NotPermitted { .. } => 403,
+        RequestError::Cancelled => 409,
+        RequestError::EngineRejected { .. } => 422,
+        RequestError::Busy | RequestError::HandshakeRequired => 503,
+        RequestError::DeadlineExceeded => 504,
+    };
+    // the name of the variant, just like `serde` would tag it
+    let kind = match json!(error) {
+        serde_json::Value::String(kind) => kind,
+        serde_json::Value::Object(variant) => variant.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    };
+    error_response(status, &kind, &error.to_string())
+}
+
+fn channel_error_response(error: &ChannelError) -> HttpResponse {
+    if matches!(error, ChannelError::Busy) {
+        return request_error_response(&RequestError::Busy);
+    }
+    error_response(
+        503,
+        "Disconnected",
+        &format!("the engine is not available: {error}"),
+    )
+}
+
+fn handshake_error_response(error: &HandshakeError) -> HttpResponse {
+    error_response(
+        503,
+        "HandshakeRejected",
+        &format!("the engine rejected the gateway: {error}"),
+    )
+}
+
+fn error_response(status: u16, kind: &str, message: &str) -> HttpResponse {
+    json_response(status, &json!({ "error": kind, "message": message }))
+}
+
+fn json_response(status: u16, json: &serde_json::Value) -> HttpResponse {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .unwrap_or_else(|()| unreachable!("the header is well-formed"));
+    Response::from_string(json.to_string())
+        .with_status_code(StatusCode(status))
+        .with_header(content_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::percent_decode;
+
+    #[test]
+    fn segments_are_percent_decoded() {
+        assert_eq!(percent_decode("jump").as_deref(), Some("jump"));
+        assert_eq!(
+            percent_decode("move%20forward").as_deref(),
+            Some("move forward")
+        );
+        assert_eq!(percent_decode("%2f%2F").as_deref(), Some("//"));
+        assert_eq!(percent_decode("gr%C3%BC%C3%9Fe").as_deref(), Some("grüße"));
+        assert_eq!(percent_decode("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn malformed_segments_are_rejected() {
+        // incomplete or non-hexadecimal escapes
+        assert_eq!(percent_decode("move%2"), None);
+        assert_eq!(percent_decode("move%"), None);
+        assert_eq!(percent_decode("move%zz"), None);
+        // not UTF-8
+        assert_eq!(percent_decode("%C3"), None);
+    }
+}
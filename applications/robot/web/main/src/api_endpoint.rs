@@ -1,6 +1,6 @@
 use gam3du_framework_common::{
     api::ApiDescriptor,
-    api_channel::ApiServerEndpoint,
+    api_channel::{ApiServerEndpoint, ChannelError, QueueMetrics},
     message::{ClientToServerMessage, ServerToClientMessage},
 };
 use tracing::debug;
@@ -23,28 +23,36 @@ impl WasmApiServerEndpoint {
 }
 
 impl ApiServerEndpoint for WasmApiServerEndpoint {
-    fn send_to_client(&self, message: ServerToClientMessage) {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
         debug!("send_to_client: {message:#?}");
-        let bytes = bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap();
+        let bytes = bincode::serde::encode_to_vec(&message, bincode::config::standard())
+            .map_err(|error| ChannelError::Malformed(error.to_string()))?;
         debug!("send_to_client: {bytes:?}");
-        self.sender.post_message(&bytes.into()).unwrap();
+        self.sender
+            .post_message(&bytes.into())
+            .map_err(|error| ChannelError::Transport(format!("{error:?}")))
     }
 
-    fn poll_request(&self) -> Option<ClientToServerMessage> {
-        let message = APPLICATION_STATE.with_borrow_mut(|state| state.client_messages.pop_front());
-
-        message.map(|request_bytes| {
-            debug!("received bytes from PythonWorker: {request_bytes:?}");
-            let request: ClientToServerMessage =
-                bincode::serde::decode_from_slice(&request_bytes, bincode::config::standard())
-                    .unwrap()
-                    .0;
-            debug!("forwarding request to plugin: {request:#?}");
-            request
-        })
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        let message = APPLICATION_STATE.with_borrow_mut(|state| state.client_messages.pop());
+
+        message
+            .map(|request_bytes| {
+                debug!("received bytes from PythonWorker: {request_bytes:?}");
+                let (request, _): (ClientToServerMessage, _) =
+                    bincode::serde::decode_from_slice(&request_bytes, bincode::config::standard())
+                        .map_err(|error| ChannelError::Malformed(error.to_string()))?;
+                debug!("forwarding request to plugin: {request:#?}");
+                Ok(request)
+            })
+            .transpose()
     }
 
     fn api(&self) -> &ApiDescriptor {
         &self.api
     }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(APPLICATION_STATE.with_borrow(|state| state.client_messages.metrics()))
+    }
 }
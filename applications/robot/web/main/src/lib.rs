@@ -14,22 +14,24 @@
 mod api_endpoint;
 
 use crate::api_endpoint::WasmApiServerEndpoint;
-use application_robot::APPLICATION_TITLE;
+use application_robot::{APPLICATION_TITLE, CONTROL_API_JSON};
 use engine_robot::{GameLoop, GameState, RendererBuilder, plugin::PythonPlugin};
 use gam3du_framework::application::{Application, GameLoopRunner};
 use gam3du_framework_common::{
-    api::ApiDescriptor, event::FrameworkEvent, message::ServerToClientMessage,
+    api::ApiDescriptor,
+    api_channel::{BoundedQueue, DEFAULT_QUEUE_CAPACITY, OverflowPolicy, QueueConfig, busy_answer},
+    event::FrameworkEvent,
+    message::{ClientToServerMessage, ServerToClientMessage},
 };
 use runtime_python::PythonRuntimeBuilder;
 use std::{
     cell::RefCell,
-    collections::VecDeque,
     mem,
     path::Path,
     rc::Rc,
     sync::{Arc, RwLock, mpsc},
 };
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::{
     JsCast,
@@ -44,21 +46,26 @@ use web_time::Instant;
 use winit::{event_loop::EventLoop, platform::web::EventLoopExtWeb};
 
 // const CONTROL_API_PATH: &str = "applications/robot/control.api.json";
-const API_JSON: &str = include_str!("../../../control.api.json");
 
+/// The size in bytes of the buffer passing messages to the blocked Python worker
 const CHANNEL_CAPACITY: u32 = 0x1_0000;
 
+/// Limits the messages from the Python worker waiting for the engine.
+///
+/// The main thread must never block, so requests exceeding the capacity are answered as busy.
+const CLIENT_QUEUE: QueueConfig = QueueConfig::new(DEFAULT_QUEUE_CAPACITY, OverflowPolicy::Reject);
+
 pub(crate) struct ApplicationState {
     server_port: Option<MessagePort>,
     client_port: Option<MessagePort>,
-    pub(crate) client_messages: VecDeque<Vec<u8>>,
+    pub(crate) client_messages: BoundedQueue<Vec<u8>>,
 }
 
 impl ApplicationState {
     const INIT: Self = Self {
         server_port: None,
         client_port: None,
-        client_messages: VecDeque::new(),
+        client_messages: BoundedQueue::new(CLIENT_QUEUE),
     };
 }
 
@@ -95,7 +102,7 @@ pub fn init() -> Result<(), JsValue> {
 
     info!("registering event handler for incoming client messages");
 
-    let client_message_handler = get_on_client_message();
+    let client_message_handler = get_on_client_message(server_port.clone());
     server_port.set_onmessage(Some(client_message_handler.as_ref().unchecked_ref()));
     mem::forget(client_message_handler);
 
@@ -117,17 +124,23 @@ pub fn init() -> Result<(), JsValue> {
 }
 
 /// Create a closure to act on the messages sent by the client
-fn get_on_client_message() -> Closure<dyn FnMut(MessageEvent)> {
+///
+/// Requests rejected or dropped because the queue is full will be answered via `server_port` right
+/// away.
+fn get_on_client_message(server_port: MessagePort) -> Closure<dyn FnMut(MessageEvent)> {
     Closure::new(move |event: MessageEvent| {
         let data = event.data();
         debug!("Received request: {data:?}");
 
         let bytes = Uint8Array::new(&data).to_vec();
 
-        APPLICATION_STATE.with_borrow_mut(|state| {
+        let lost = APPLICATION_STATE.with_borrow_mut(|state| {
             debug!("enqueueing client message: {bytes:?}");
-            state.client_messages.push_back(bytes);
+            state.client_messages.push(bytes).unwrap_or_else(Some)
         });
+        if let Some(bytes) = lost {
+            reject_client_message(&server_port, &bytes);
+        }
 
         // let result = match event.data().as_bool().unwrap() {
         //     true => "even",
@@ -144,6 +157,33 @@ fn get_on_client_message() -> Closure<dyn FnMut(MessageEvent)> {
     })
 }
 
+/// Tells the client that its message has been rejected or dropped as the engine cannot keep up.
+fn reject_client_message(server_port: &MessagePort, bytes: &[u8]) {
+    let message: ClientToServerMessage =
+        match bincode::serde::decode_from_slice(bytes, bincode::config::standard()) {
+            Ok((message, _)) => message,
+            Err(error) => {
+                warn!("dropping malformed client message: {error}");
+                return;
+            }
+        };
+    warn!("dropping client message as the queue is full: {message:?}");
+
+    let Some(answer) = busy_answer(&message) else {
+        return;
+    };
+    let answer = match bincode::serde::encode_to_vec(&answer, bincode::config::standard()) {
+        Ok(answer) => answer,
+        Err(error) => {
+            warn!("failed to encode the answer to a rejected message: {error}");
+            return;
+        }
+    };
+    if let Err(error) = server_port.post_message(&answer.into()) {
+        warn!("failed to answer a rejected message: {error:?}");
+    }
+}
+
 // /// connect an api client to this instance by sending its underlying share buffers
 // #[wasm_bindgen]
 // pub fn connect_api_client(message_port: MessagePort) {
@@ -242,7 +282,8 @@ pub fn start() -> Result<(), JsValue> {
     // );
 
     // let api_json = storage.get_content(Path::new(CONTROL_API_PATH)).unwrap();
-    let robot_api: ApiDescriptor = serde_json::from_str(API_JSON).unwrap();
+    let robot_api =
+        ApiDescriptor::load(CONTROL_API_JSON.as_bytes()).map_err(|error| error.to_string())?;
 
     let api_server_message_port = APPLICATION_STATE
         .with_borrow_mut(|state| state.server_port.take())
@@ -369,7 +410,8 @@ impl PythonWorker {
     /// Create a closure to act on the messages sent by the server.
     ///
     /// called whenever the engine sends a response message back to the python runtime
-    /// the message will be forwarded to the shared channel of the blocked worker
+    /// the message will be forwarded to the shared channel of the blocked worker; messages not
+    /// fitting into the channel will be dropped
     fn get_on_server_message(
         sender: spsc::Sender<ServerToClientMessage>,
     ) -> Closure<dyn FnMut(MessageEvent)> {
@@ -378,11 +420,18 @@ impl PythonWorker {
             debug!("on_server_message: {data:?}");
 
             let bytes = Uint8Array::new(&data).to_vec();
-            let message = bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
-                .unwrap()
-                .0;
-
-            sender.send(&message).unwrap();
+            let message: ServerToClientMessage =
+                match bincode::serde::decode_from_slice(&bytes, bincode::config::standard()) {
+                    Ok((message, _)) => message,
+                    Err(error) => {
+                        warn!("dropping malformed server message: {error}");
+                        return;
+                    }
+                };
+
+            if let Err(error) = sender.send(&message) {
+                warn!("dropping server message as the Python worker cannot keep up: {error:?}");
+            }
         })
     }
 
@@ -1,6 +1,6 @@
 use gam3du_framework_common::{
     api::ApiDescriptor,
-    api_channel::ApiClientEndpoint,
+    api_channel::{ApiClientEndpoint, ChannelError},
     message::{ClientToServerMessage, ServerToClientMessage},
 };
 use tracing::debug;
@@ -26,18 +26,25 @@ impl ApiClientEndpoint for WasmApiClientEndpoint {
         &self.api
     }
 
-    fn send_to_server(&self, message: ClientToServerMessage) {
+    fn send_to_server(&self, message: ClientToServerMessage) -> Result<(), ChannelError> {
         debug!("send_to_server: {message:#?}");
-        let bytes = bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap();
+        let bytes = bincode::serde::encode_to_vec(&message, bincode::config::standard())
+            .map_err(|error| ChannelError::Malformed(error.to_string()))?;
         debug!("send_to_server: {bytes:?}");
 
         let global = js_sys::global()
             .dyn_into::<DedicatedWorkerGlobalScope>()
-            .unwrap();
-        global.post_message(&bytes.into()).unwrap();
+            .map_err(|_global| {
+                ChannelError::Transport("not running within a dedicated worker".to_owned())
+            })?;
+        global
+            .post_message(&bytes.into())
+            .map_err(|error| ChannelError::Transport(format!("{error:?}")))
     }
 
-    fn poll_response(&self) -> Option<ServerToClientMessage> {
-        self.receiver.recv(None).unwrap()
+    fn poll_response(&self) -> Result<Option<ServerToClientMessage>, ChannelError> {
+        self.receiver
+            .recv(None)
+            .map_err(|error| ChannelError::Transport(format!("{error:?}")))
     }
 }
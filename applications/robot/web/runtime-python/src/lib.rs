@@ -15,6 +15,7 @@ mod api_client;
 use getrandom as _;
 
 use api_client::WasmApiClientEndpoint;
+use application_robot::CONTROL_API_JSON;
 use gam3du_framework::init_logger;
 use gam3du_framework_common::{api::ApiDescriptor, message::ServerToClientMessage};
 use runtime_python::PythonRuntimeBuilder;
@@ -23,8 +24,6 @@ use tracing::info;
 use wasm_bindgen::prelude::*;
 use wasm_rs_shared_channel::spsc::{self, SharedChannel};
 
-const API_JSON: &str = include_str!("../../../control.api.json");
-
 // #[wasm_bindgen(raw_module = "./worker.mjs")]
 // extern "C" {
 //     /// sends requests to an api server (the game engine)
@@ -103,7 +102,14 @@ pub fn run(source: &str) -> Result<(), JsValue> {
     )
     .decode();
 
-    let robot_api: ApiDescriptor = serde_json::from_str(API_JSON).map_err(|err| err.to_string())?;
+    let robot_control_api_de_module = rustpython::vm::py_freeze!(
+        module_name = "robot_control_api_de",
+        file = "../../python/control/robot_control_api_de.py"
+    )
+    .decode();
+
+    let robot_api =
+        ApiDescriptor::load(CONTROL_API_JSON.as_bytes()).map_err(|err| err.to_string())?;
 
     APPLICATION_STATE.with_borrow_mut(|state| {
         let Some(receiver) = state.receiver.take() else {
@@ -116,6 +122,7 @@ pub fn run(source: &str) -> Result<(), JsValue> {
         python_runtime_builder.add_frozen_module("robot", robot_control_module);
         python_runtime_builder.add_frozen_module("robot_api", robot_control_api_module);
         python_runtime_builder.add_frozen_module("robot_api_async", robot_control_api_async_module);
+        python_runtime_builder.add_frozen_module("robot_api_de", robot_control_api_de_module);
 
         let mut runtime = python_runtime_builder.build();
 
@@ -0,0 +1,1419 @@
+// This file has been generated automatically and shall not be edited by hand!
+// generator: applications/robot/build.rs
+// api descriptor: control.api.json
+
+// --- api client runtime (shared by all generated api modules) ---
+
+// Messages are encoded the same way `bincode` (v2, standard configuration) encodes them via `serde`:
+// little endian, variable length integers and enum variants encoded by their index.
+// In JavaScript, messages and values use the externally tagged representation `serde_json` would
+// produce, e.g. `{ Request: { id, command, arguments: [{ Integer: 500 }] } }`.
+
+class Writer {
+    #bytes = [];
+
+    byte(value) {
+        this.#bytes.push(value);
+    }
+
+    // little endian integer of a fixed number of bytes
+    fixed(value, size) {
+        let remaining = BigInt(value);
+        for (let index = 0; index < size; index++) {
+            this.#bytes.push(Number(remaining & 0xffn));
+            remaining >>= 8n;
+        }
+    }
+
+    varint(value) {
+        const big = BigInt(value);
+        if (big < 0n) {
+            throw new RangeError(`cannot encode negative value ${big} as unsigned integer`);
+        } else if (big < 251n) {
+            this.byte(Number(big));
+        } else if (big < 1n << 16n) {
+            this.byte(251);
+            this.fixed(big, 2);
+        } else if (big < 1n << 32n) {
+            this.byte(252);
+            this.fixed(big, 4);
+        } else if (big < 1n << 64n) {
+            this.byte(253);
+            this.fixed(big, 8);
+        } else {
+            this.byte(254);
+            this.fixed(big, 16);
+        }
+    }
+
+    zigzag(value) {
+        const big = BigInt(value);
+        this.varint(big < 0n ? -big * 2n - 1n : big * 2n);
+    }
+
+    f32(value) {
+        const view = new DataView(new ArrayBuffer(4));
+        view.setFloat32(0, value, true);
+        for (let index = 0; index < 4; index++) {
+            this.byte(view.getUint8(index));
+        }
+    }
+
+    bool(value) {
+        this.byte(value ? 1 : 0);
+    }
+
+    string(value) {
+        const bytes = new TextEncoder().encode(value);
+        this.varint(bytes.length);
+        this.#bytes.push(...bytes);
+    }
+
+    sequence(elements, encodeElement) {
+        this.varint(elements.length);
+        for (const element of elements) {
+            encodeElement(this, element);
+        }
+    }
+
+    finish() {
+        return new Uint8Array(this.#bytes);
+    }
+}
+
+class Reader {
+    #bytes;
+    #offset = 0;
+
+    constructor(bytes) {
+        // copying also detaches the data from shared buffers which `TextDecoder` cannot handle
+        this.#bytes = Uint8Array.from(bytes);
+    }
+
+    byte() {
+        if (this.#offset >= this.#bytes.length) {
+            throw new RangeError("unexpected end of message");
+        }
+        return this.#bytes[this.#offset++];
+    }
+
+    fixed(size) {
+        let value = 0n;
+        for (let index = 0; index < size; index++) {
+            value |= BigInt(this.byte()) << BigInt(8 * index);
+        }
+        return value;
+    }
+
+    varint() {
+        const first = this.byte();
+        switch (first) {
+            case 251: return this.fixed(2);
+            case 252: return this.fixed(4);
+            case 253: return this.fixed(8);
+            case 254: return this.fixed(16);
+            case 255: throw new RangeError("invalid variable length integer");
+            default: return BigInt(first);
+        }
+    }
+
+    // lengths and enum variants
+    index() {
+        return Number(this.varint());
+    }
+
+    zigzag() {
+        const value = this.varint();
+        return value & 1n ? -(value >> 1n) - 1n : value >> 1n;
+    }
+
+    f32() {
+        const bytes = new Uint8Array([this.byte(), this.byte(), this.byte(), this.byte()]);
+        return new DataView(bytes.buffer).getFloat32(0, true);
+    }
+
+    bool() {
+        const value = this.byte();
+        if (value > 1) {
+            throw new RangeError(`invalid boolean ${value}`);
+        }
+        return value === 1;
+    }
+
+    string() {
+        const length = this.index();
+        if (this.#offset + length > this.#bytes.length) {
+            throw new RangeError("unexpected end of message");
+        }
+        const bytes = this.#bytes.subarray(this.#offset, this.#offset + length);
+        this.#offset += length;
+        return new TextDecoder("utf-8", { fatal: true }).decode(bytes);
+    }
+
+    sequence(decodeElement) {
+        const length = this.index();
+        const elements = [];
+        for (let index = 0; index < length; index++) {
+            elements.push(decodeElement(this));
+        }
+        return elements;
+    }
+
+    finish(message) {
+        if (this.#offset !== this.#bytes.length) {
+            throw new RangeError(`${this.#bytes.length - this.#offset} unexpected trailing bytes`);
+        }
+        return message;
+    }
+}
+
+// Splits an externally tagged enum into its variant name and content.
+function variant(tagged) {
+    if (typeof tagged === "string") {
+        return [tagged, null];
+    }
+    const entries = Object.entries(tagged);
+    if (entries.length !== 1) {
+        throw new TypeError(`expected exactly one variant, got ${JSON.stringify(tagged)}`);
+    }
+    return entries[0];
+}
+
+function unknownVariant(type, name) {
+    return new TypeError(`unknown ${type} variant ${JSON.stringify(name)}`);
+}
+
+const VALUE_VARIANTS = [
+    "Unit", "Integer", "Float", "Boolean", "String", "List", "Tuple", "Record", "Enumeration",
+    "Optional",
+];
+
+function encodeValue(writer, value) {
+    const [name, content] = variant(value);
+    const index = VALUE_VARIANTS.indexOf(name);
+    if (index < 0) {
+        throw unknownVariant("Value", name);
+    }
+    writer.varint(index);
+    switch (name) {
+        case "Unit": break;
+        case "Integer": writer.zigzag(content); break;
+        case "Float": writer.f32(content); break;
+        case "Boolean": writer.bool(content); break;
+        case "String": writer.string(content); break;
+        case "List":
+        case "Tuple": writer.sequence(content, encodeValue); break;
+        case "Record":
+            writer.sequence(Object.entries(content), (writer, [field, value]) => {
+                writer.string(field);
+                encodeValue(writer, value);
+            });
+            break;
+        case "Enumeration": writer.string(content); break;
+        case "Optional": encodeOption(writer, content, encodeValue); break;
+    }
+}
+
+function decodeValue(reader) {
+    const index = reader.index();
+    switch (VALUE_VARIANTS[index]) {
+        case "Unit": return "Unit";
+        case "Integer": return { Integer: Number(reader.zigzag()) };
+        case "Float": return { Float: reader.f32() };
+        case "Boolean": return { Boolean: reader.bool() };
+        case "String": return { String: reader.string() };
+        case "List": return { List: reader.sequence(decodeValue) };
+        case "Tuple": return { Tuple: reader.sequence(decodeValue) };
+        case "Record":
+            return {
+                Record: Object.fromEntries(
+                    reader.sequence((reader) => [reader.string(), decodeValue(reader)]),
+                ),
+            };
+        case "Enumeration": return { Enumeration: reader.string() };
+        case "Optional": return { Optional: decodeOption(reader, decodeValue) };
+        default: throw unknownVariant("Value", index);
+    }
+}
+
+function encodeOption(writer, value, encodeInner) {
+    if (value === null || value === undefined) {
+        writer.byte(0);
+    } else {
+        writer.byte(1);
+        encodeInner(writer, value);
+    }
+}
+
+function decodeOption(reader, decodeInner) {
+    return reader.bool() ? decodeInner(reader) : null;
+}
+
+const TYPE_VARIANTS = [
+    "Integer", "Float", "Boolean", "String", "List", "Tuple", "Record", "Enumeration", "Optional",
+];
+
+function encodeRange(writer, range) {
+    const [start, end] = Array.isArray(range) ? range : [range.start, range.end];
+    writer.zigzag(start);
+    writer.zigzag(end);
+}
+
+function decodeRange(reader) {
+    return { start: Number(reader.zigzag()), end: Number(reader.zigzag()) };
+}
+
+function encodeType(writer, type) {
+    const [name, content] = variant(type);
+    const index = TYPE_VARIANTS.indexOf(name);
+    if (index < 0) {
+        throw unknownVariant("TypeDescriptor", name);
+    }
+    writer.varint(index);
+    switch (name) {
+        case "Integer": encodeRange(writer, content); break;
+        case "Float":
+        case "Boolean":
+        case "String": break;
+        case "List":
+        case "Optional": encodeType(writer, content); break;
+        case "Tuple": writer.sequence(content, encodeType); break;
+        case "Record":
+            writer.sequence(Object.entries(content), (writer, [field, type]) => {
+                writer.string(field);
+                encodeType(writer, type);
+            });
+            break;
+        case "Enumeration": writer.sequence(content, (writer, name) => writer.string(name)); break;
+    }
+}
+
+function decodeType(reader) {
+    const index = reader.index();
+    switch (TYPE_VARIANTS[index]) {
+        case "Integer": return { Integer: decodeRange(reader) };
+        case "Float": return "Float";
+        case "Boolean": return "Boolean";
+        case "String": return "String";
+        case "List": return { List: decodeType(reader) };
+        case "Tuple": return { Tuple: reader.sequence(decodeType) };
+        case "Record":
+            return {
+                Record: Object.fromEntries(
+                    reader.sequence((reader) => [reader.string(), decodeType(reader)]),
+                ),
+            };
+        case "Enumeration": return { Enumeration: reader.sequence((reader) => reader.string()) };
+        case "Optional": return { Optional: decodeType(reader) };
+        default: throw unknownVariant("TypeDescriptor", index);
+    }
+}
+
+const REQUEST_STATUSES = ["Pending", "Unknown"];
+
+const REQUEST_ERRORS = [
+    "UnknownFunction", "WrongArgumentCount", "TypeMismatch", "OutOfRange", "Busy", "Cancelled",
+    "EngineRejected", "DeadlineExceeded", "NotPermitted", "HandshakeRequired",
+];
+
+function encodeRequestError(writer, error) {
+    const [name, content] = variant(error);
+    const index = REQUEST_ERRORS.indexOf(name);
+    if (index < 0) {
+        throw unknownVariant("RequestError", name);
+    }
+    writer.varint(index);
+    switch (name) {
+        case "UnknownFunction": writer.string(content.function); break;
+        case "WrongArgumentCount":
+            writer.string(content.function);
+            writer.varint(content.expected);
+            writer.varint(content.actual);
+            break;
+        case "TypeMismatch":
+            writer.string(content.parameter);
+            encodeType(writer, content.expected);
+            encodeValue(writer, content.value);
+            break;
+        case "OutOfRange":
+            writer.string(content.parameter);
+            encodeRange(writer, content.range);
+            writer.zigzag(content.value);
+            break;
+        case "Busy":
+        case "Cancelled": break;
+        case "EngineRejected": writer.string(content.reason); break;
+        case "DeadlineExceeded": break;
+        case "NotPermitted":
+            writer.string(content.function);
+            writer.string(content.capabilities);
+            break;
+        case "HandshakeRequired": break;
+    }
+}
+
+function decodeRequestError(reader) {
+    const index = reader.index();
+    switch (REQUEST_ERRORS[index]) {
+        case "UnknownFunction": return { UnknownFunction: { function: reader.string() } };
+        case "WrongArgumentCount":
+            return {
+                WrongArgumentCount: {
+                    function: reader.string(),
+                    expected: reader.index(),
+                    actual: reader.index(),
+                },
+            };
+        case "TypeMismatch":
+            return {
+                TypeMismatch: {
+                    parameter: reader.string(),
+                    expected: decodeType(reader),
+                    value: decodeValue(reader),
+                },
+            };
+        case "OutOfRange":
+            return {
+                OutOfRange: {
+                    parameter: reader.string(),
+                    range: decodeRange(reader),
+                    value: Number(reader.zigzag()),
+                },
+            };
+        case "Busy": return "Busy";
+        case "Cancelled": return "Cancelled";
+        case "EngineRejected": return { EngineRejected: { reason: reader.string() } };
+        case "DeadlineExceeded": return "DeadlineExceeded";
+        case "NotPermitted":
+            return {
+                NotPermitted: { function: reader.string(), capabilities: reader.string() },
+            };
+        case "HandshakeRequired": return "HandshakeRequired";
+        default: throw unknownVariant("RequestError", index);
+    }
+}
+
+// A readable description of a `RequestError` in the words of its `Display` implementation.
+function describeRequestError(error) {
+    const [name, content] = variant(error);
+    switch (name) {
+        case "UnknownFunction": return `unknown function \`${content.function}\``;
+        case "WrongArgumentCount":
+            return `function \`${content.function}\` takes ${content.expected} arguments but ${content.actual} were given`;
+        case "TypeMismatch":
+            return `argument \`${content.parameter}\` must be a value of ${JSON.stringify(content.expected)} but got ${JSON.stringify(content.value)}`;
+        case "OutOfRange":
+            return `argument \`${content.parameter}\` must be within ${content.range.start}..${content.range.end} but got ${content.value}`;
+        case "Busy": return "the server is busy with another request";
+        case "Cancelled": return "request has been cancelled";
+        case "EngineRejected": return `request rejected: ${content.reason}`;
+        case "DeadlineExceeded": return "request has not been answered in time";
+        case "NotPermitted":
+            return `function \`${content.function}\` is not available in \`${content.capabilities}\``;
+        case "HandshakeRequired": return "the client has not completed a successful handshake";
+        default: return `request failed: ${JSON.stringify(error)}`;
+    }
+}
+
+// Rejects a call the server could not perform; `kind` names the variant of `RequestError`.
+export class RequestFailedError extends Error {
+    constructor(error) {
+        super(describeRequestError(error));
+        this.name = "RequestFailedError";
+        [this.kind, this.details] = variant(error);
+    }
+}
+
+function encodeHandshakeError(writer, error) {
+    const [name, content] = variant(error);
+    switch (name) {
+        case "ApiMismatch":
+            writer.varint(0);
+            writer.string(content.server);
+            writer.string(content.client);
+            break;
+        case "IncompatibleVersion":
+            writer.varint(1);
+            writer.string(content.server);
+            writer.string(content.client);
+            break;
+        case "FingerprintMismatch":
+            writer.varint(2);
+            writer.string(content.version);
+            writer.varint(content.server);
+            writer.varint(content.client);
+            break;
+        default: throw unknownVariant("HandshakeError", name);
+    }
+}
+
+function decodeHandshakeError(reader) {
+    const index = reader.index();
+    switch (index) {
+        case 0: return { ApiMismatch: { server: reader.string(), client: reader.string() } };
+        case 1: return { IncompatibleVersion: { server: reader.string(), client: reader.string() } };
+        case 2:
+            return {
+                FingerprintMismatch: {
+                    version: reader.string(),
+                    server: reader.varint(),
+                    client: reader.varint(),
+                },
+            };
+        default: throw unknownVariant("HandshakeError", index);
+    }
+}
+
+// `std::time::Duration` is encoded as its whole seconds followed by the remaining nanoseconds.
+function encodeDuration(writer, duration) {
+    writer.varint(duration.secs);
+    writer.varint(duration.nanos);
+}
+
+function decodeDuration(reader) {
+    return { secs: reader.varint(), nanos: reader.index() };
+}
+
+// Converts milliseconds into the representation of `std::time::Duration`.
+function millisecondsToDuration(milliseconds) {
+    const whole = Math.floor(milliseconds);
+    return {
+        secs: BigInt(Math.floor(whole / 1000)),
+        nanos: (whole % 1000) * 1_000_000 + Math.round((milliseconds - whole) * 1_000_000),
+    };
+}
+
+function encodeRequest(writer, request) {
+    writer.varint(request.id);
+    writer.string(request.command);
+    writer.sequence(request.arguments, encodeValue);
+    if (request.timeout === null || request.timeout === undefined) {
+        writer.varint(0);
+    } else {
+        writer.varint(1);
+        encodeDuration(writer, request.timeout);
+    }
+}
+
+function decodeRequest(reader) {
+    return {
+        id: reader.varint(),
+        command: reader.string(),
+        arguments: reader.sequence(decodeValue),
+        timeout: reader.index() === 0 ? null : decodeDuration(reader),
+    };
+}
+
+// A batch answers with one `Result<Value, RequestError>` per request performed.
+function encodeOutcome(writer, outcome) {
+    const [name, content] = variant(outcome);
+    switch (name) {
+        case "Ok":
+            writer.varint(0);
+            encodeValue(writer, content);
+            break;
+        case "Err":
+            writer.varint(1);
+            encodeRequestError(writer, content);
+            break;
+        default: throw unknownVariant("Result", name);
+    }
+}
+
+function decodeOutcome(reader) {
+    const index = reader.index();
+    switch (index) {
+        case 0: return { Ok: decodeValue(reader) };
+        case 1: return { Err: decodeRequestError(reader) };
+        default: throw unknownVariant("Result", index);
+    }
+}
+
+export function encodeClientToServerMessage(message) {
+    const writer = new Writer();
+    const [name, content] = variant(message);
+    switch (name) {
+        case "Handshake":
+            writer.varint(0);
+            writer.string(content.api);
+            writer.string(content.version);
+            writer.varint(content.fingerprint);
+            break;
+        case "Request":
+            writer.varint(1);
+            encodeRequest(writer, content);
+            break;
+        case "Subscribe":
+            writer.varint(2);
+            writer.string(content.event);
+            break;
+        case "Unsubscribe":
+            writer.varint(3);
+            writer.string(content.event);
+            break;
+        case "Cancel":
+            writer.varint(4);
+            writer.varint(content);
+            break;
+        case "Query":
+            writer.varint(5);
+            writer.varint(content);
+            break;
+        case "Goodbye":
+            writer.varint(6);
+            break;
+        case "Batch":
+            writer.varint(7);
+            writer.varint(content.id);
+            writer.sequence(content.requests, encodeRequest);
+            break;
+        default: throw unknownVariant("ClientToServerMessage", name);
+    }
+    return writer.finish();
+}
+
+export function decodeClientToServerMessage(bytes) {
+    const reader = new Reader(bytes);
+    const index = reader.index();
+    switch (index) {
+        case 0:
+            return reader.finish({
+                Handshake: {
+                    api: reader.string(),
+                    version: reader.string(),
+                    fingerprint: reader.varint(),
+                },
+            });
+        case 1: return reader.finish({ Request: decodeRequest(reader) });
+        case 2: return reader.finish({ Subscribe: { event: reader.string() } });
+        case 3: return reader.finish({ Unsubscribe: { event: reader.string() } });
+        case 4: return reader.finish({ Cancel: reader.varint() });
+        case 5: return reader.finish({ Query: reader.varint() });
+        case 6: return reader.finish("Goodbye");
+        case 7:
+            return reader.finish({
+                Batch: { id: reader.varint(), requests: reader.sequence(decodeRequest) },
+            });
+        default: throw unknownVariant("ClientToServerMessage", index);
+    }
+}
+
+export function encodeServerToClientMessage(message) {
+    const writer = new Writer();
+    const [name, content] = variant(message);
+    switch (name) {
+        case "HandshakeResponse": {
+            writer.varint(0);
+            writer.string(content.version);
+            writer.varint(content.fingerprint);
+            const [result, error] = variant(content.result);
+            if (result === "Ok") {
+                writer.varint(0);
+            } else {
+                writer.varint(1);
+                encodeHandshakeError(writer, error);
+            }
+            break;
+        }
+        case "Response":
+            writer.varint(1);
+            writer.varint(content.id);
+            encodeValue(writer, content.result);
+            break;
+        case "ErrorResponse":
+            writer.varint(2);
+            writer.varint(content.id);
+            encodeRequestError(writer, content.error);
+            break;
+        case "Event":
+            writer.varint(3);
+            writer.string(content.event);
+            writer.sequence(content.arguments, encodeValue);
+            break;
+        case "Cancelled":
+            writer.varint(4);
+            writer.varint(content);
+            break;
+        case "Status": {
+            writer.varint(5);
+            writer.varint(content.id);
+            const index = REQUEST_STATUSES.indexOf(content.status);
+            if (index < 0) {
+                throw unknownVariant("RequestStatus", content.status);
+            }
+            writer.varint(index);
+            break;
+        }
+        case "Goodbye":
+            writer.varint(6);
+            break;
+        case "BatchResponse":
+            writer.varint(7);
+            writer.varint(content.id);
+            writer.sequence(content.outcomes, encodeOutcome);
+            break;
+        default: throw unknownVariant("ServerToClientMessage", name);
+    }
+    return writer.finish();
+}
+
+export function decodeServerToClientMessage(bytes) {
+    const reader = new Reader(bytes);
+    const index = reader.index();
+    switch (index) {
+        case 0:
+            return reader.finish({
+                HandshakeResponse: {
+                    version: reader.string(),
+                    fingerprint: reader.varint(),
+                    result: reader.index() === 0 ? { Ok: null } : { Err: decodeHandshakeError(reader) },
+                },
+            });
+        case 1: return reader.finish({ Response: { id: reader.varint(), result: decodeValue(reader) } });
+        case 2: return reader.finish({ ErrorResponse: { id: reader.varint(), error: decodeRequestError(reader) } });
+        case 3:
+            return reader.finish({
+                Event: { event: reader.string(), arguments: reader.sequence(decodeValue) },
+            });
+        case 4: return reader.finish({ Cancelled: reader.varint() });
+        case 5: {
+            const id = reader.varint();
+            const status = reader.index();
+            if (status >= REQUEST_STATUSES.length) {
+                throw unknownVariant("RequestStatus", status);
+            }
+            return reader.finish({ Status: { id, status: REQUEST_STATUSES[status] } });
+        }
+        case 6: return reader.finish("Goodbye");
+        case 7:
+            return reader.finish({
+                BatchResponse: { id: reader.varint(), outcomes: reader.sequence(decodeOutcome) },
+            });
+        default: throw unknownVariant("ServerToClientMessage", index);
+    }
+}
+
+// Identifiers like "steps ccw" become "stepsCcw" in JavaScript.
+function camelCase(identifier) {
+    return identifier.replace(/ ([a-z0-9])/g, (_, character) => character.toUpperCase());
+}
+
+// Converts a native JavaScript value into a tagged value of the given type.
+export function toValue(type, native, path = "value") {
+    const [name, content] = variant(type);
+    const mismatch = () => new TypeError(`${path}: expected ${name}, got ${JSON.stringify(native)}`);
+    switch (name) {
+        case "Integer": {
+            const [start, end] = Array.isArray(content) ? content : [content.start, content.end];
+            if (!Number.isInteger(native)) {
+                throw mismatch();
+            }
+            if (native < start || native >= end) {
+                throw new RangeError(`${path}: ${native} is out of range ${start}..${end}`);
+            }
+            return { Integer: native };
+        }
+        case "Float":
+            if (typeof native !== "number") {
+                throw mismatch();
+            }
+            return { Float: native };
+        case "Boolean":
+            if (typeof native !== "boolean") {
+                throw mismatch();
+            }
+            return { Boolean: native };
+        case "String":
+            if (typeof native !== "string") {
+                throw mismatch();
+            }
+            return { String: native };
+        case "List":
+            if (!Array.isArray(native)) {
+                throw mismatch();
+            }
+            return { List: native.map((element, index) => toValue(content, element, `${path}[${index}]`)) };
+        case "Tuple":
+            if (!Array.isArray(native) || native.length !== content.length) {
+                throw mismatch();
+            }
+            return {
+                Tuple: content.map((element, index) => toValue(element, native[index], `${path}[${index}]`)),
+            };
+        case "Record":
+            if (typeof native !== "object" || native === null) {
+                throw mismatch();
+            }
+            return {
+                Record: Object.fromEntries(
+                    Object.entries(content).map(([field, fieldType]) => [
+                        field,
+                        toValue(fieldType, native[camelCase(field)], `${path}.${camelCase(field)}`),
+                    ]),
+                ),
+            };
+        case "Enumeration":
+            if (!content.includes(native)) {
+                throw mismatch();
+            }
+            return { Enumeration: native };
+        case "Optional":
+            return { Optional: native === null || native === undefined ? null : toValue(content, native, path) };
+        default: throw unknownVariant("TypeDescriptor", name);
+    }
+}
+
+// Converts a tagged value into its native JavaScript representation.
+export function fromValue(value) {
+    const [name, content] = variant(value);
+    switch (name) {
+        case "Unit": return undefined;
+        case "Integer":
+        case "Float":
+        case "Boolean":
+        case "String":
+        case "Enumeration": return content;
+        case "List":
+        case "Tuple": return content.map(fromValue);
+        case "Record":
+            return Object.fromEntries(
+                Object.entries(content).map(([field, value]) => [camelCase(field), fromValue(value)]),
+            );
+        case "Optional": return content === null ? null : fromValue(content);
+        default: throw unknownVariant("Value", name);
+    }
+}
+
+function randomRequestId() {
+    const words = crypto.getRandomValues(new Uint32Array(4));
+    const id = words.reduce((id, word) => (id << 32n) | BigInt(word), 0n);
+    // request ids are never zero
+    return id === 0n ? 1n : id;
+}
+
+// Talks to an api server through a `MessagePort`.
+//
+// All calls return promises that settle once the server responded. Aborting the `signal` passed
+// to a call asks the server to cancel the request; the promise then rejects with an `AbortError`
+// unless the response has already been on its way.
+//
+// A call that has not been answered within its `timeout` (in milliseconds, defaulting to
+// `defaultTimeout`) is cancelled as well and rejects with a `RequestFailedError` of kind
+// `DeadlineExceeded`. The timeout is sent along with the request so the server gives up, too.
+//
+// Once either side said goodbye, all pending calls reject and new calls are refused.
+export class ApiClient {
+    #port;
+    #api;
+    #pending = new Map();
+    #handlers = new Map();
+    #handshake;
+    #closed = null;
+    defaultTimeout = null;
+
+    constructor(port, api, fingerprint) {
+        this.#port = port;
+        this.#api = api;
+        this.ready = new Promise((resolve, reject) => {
+            this.#handshake = { resolve, reject };
+        });
+        port.onmessage = (event) => this.#receive(new Uint8Array(event.data));
+        this.#send({ Handshake: { api: api.name, version: api.version, fingerprint } });
+    }
+
+    call(name, args, { signal, timeout = this.defaultTimeout } = {}) {
+        const descriptor = this.#api.functions[name];
+        if (descriptor === undefined) {
+            return Promise.reject(new TypeError(`unknown function ${JSON.stringify(name)}`));
+        }
+
+        let values;
+        try {
+            values = descriptor.parameters.map((parameter, index) =>
+                toValue(parameter.type, args[index], camelCase(parameter.name)),
+            );
+        } catch (error) {
+            return Promise.reject(error);
+        }
+
+        if (signal?.aborted) {
+            return Promise.reject(signal.reason);
+        }
+        if (this.#closed !== null) {
+            return Promise.reject(this.#closed);
+        }
+
+        const id = randomRequestId();
+        return new Promise((resolve, reject) => {
+            this.#pending.set(id, { resolve, reject });
+            this.#send({
+                Request: {
+                    id,
+                    command: name,
+                    arguments: values,
+                    timeout: timeout === null ? null : millisecondsToDuration(timeout),
+                },
+            });
+            signal?.addEventListener("abort", () => {
+                if (this.#pending.has(id)) {
+                    this.#send({ Cancel: id });
+                }
+            }, { once: true });
+            if (timeout !== null) {
+                setTimeout(() => {
+                    if (this.#pending.has(id)) {
+                        this.#send({ Cancel: id });
+                        this.#settle(id).reject(new RequestFailedError("DeadlineExceeded"));
+                    }
+                }, timeout);
+            }
+        });
+    }
+
+    on(event, handler) {
+        if (this.#api.events[event] === undefined) {
+            throw new TypeError(`unknown event ${JSON.stringify(event)}`);
+        }
+        if (!this.#handlers.has(event)) {
+            this.#handlers.set(event, []);
+            this.#send({ Subscribe: { event } });
+        }
+        this.#handlers.get(event).push(handler);
+    }
+
+    off(event) {
+        if (this.#handlers.delete(event)) {
+            this.#send({ Unsubscribe: { event } });
+        }
+    }
+
+    close() {
+        if (this.#closed === null) {
+            this.#send("Goodbye");
+            this.#disconnect(new Error("the client has been closed"));
+        }
+    }
+
+    #send(message) {
+        if (this.#closed === null) {
+            this.#port.postMessage(encodeClientToServerMessage(message));
+        }
+    }
+
+    #disconnect(error) {
+        this.#closed = error;
+        this.#handshake.reject(error);
+        for (const { reject } of this.#pending.values()) {
+            reject(error);
+        }
+        this.#pending.clear();
+    }
+
+    #receive(bytes) {
+        const [name, content] = variant(decodeServerToClientMessage(bytes));
+        switch (name) {
+            case "HandshakeResponse":
+                if ("Err" in content.result) {
+                    const error = new Error(`incompatible api: ${JSON.stringify(content.result.Err)}`);
+                    this.#handshake.reject(error);
+                    for (const { reject } of this.#pending.values()) {
+                        reject(error);
+                    }
+                    this.#pending.clear();
+                } else {
+                    this.#handshake.resolve();
+                }
+                break;
+            case "Response":
+                this.#settle(content.id)?.resolve(fromValue(content.result));
+                break;
+            case "ErrorResponse":
+                this.#settle(content.id)?.reject(new RequestFailedError(content.error));
+                break;
+            case "Cancelled": {
+                const error = new DOMException("request has been cancelled", "AbortError");
+                this.#settle(content)?.reject(error);
+                break;
+            }
+            case "Event":
+                for (const handler of this.#handlers.get(content.event) ?? []) {
+                    handler(...content.arguments.map(fromValue));
+                }
+                break;
+            case "Goodbye":
+                this.#disconnect(new Error("the server has said goodbye"));
+                break;
+        }
+    }
+
+    #settle(id) {
+        const pending = this.#pending.get(id);
+        this.#pending.delete(id);
+        return pending;
+    }
+}
+
+// --- generated api bindings ---
+
+/** The descriptor of the api this module has been generated for */
+export const API = {
+  "name": "robot control",
+  "version": "0.2.0",
+  "caption": "A simple robot that can be moved across a 2D-plane and draw lines",
+  "description": "Once upon a time there was a lonely robot with the serial number `#C0D1E`. …",
+  "functions": {
+    "set height": {
+      "name": "set height",
+      "caption": "Sets the height of the tile at the robot's current location",
+      "description": "Sets the height of the tile at the robot's current location",
+      "parameters": [
+        {
+          "name": "height",
+          "caption": "height of the tile",
+          "description": "height of the tile",
+          "type": "Float",
+          "default": null,
+          "localizations": {
+            "de": {
+              "alias": "höhe",
+              "caption": "Höhe des Feldes",
+              "description": null
+            }
+          }
+        }
+      ],
+      "returns": null,
+      "localizations": {
+        "de": {
+          "alias": "setze höhe",
+          "caption": "Setzt die Höhe des Feldes, auf dem der Roboter steht",
+          "description": null
+        }
+      }
+    },
+    "move forward": {
+      "name": "move forward",
+      "caption": "Makes the robot move to the next tile in its current orientation",
+      "description": "Makes the robot move to the next tile in its current orientation",
+      "parameters": [
+        {
+          "name": "duration",
+          "caption": "Duration of the move animation",
+          "description": "Duration of the move animation. The duration is in milliseconds and must be smaller than 10000.",
+          "type": {
+            "Integer": {
+              "start": 0,
+              "end": 10000
+            }
+          },
+          "default": {
+            "Integer": 500
+          },
+          "localizations": {
+            "de": {
+              "alias": "dauer",
+              "caption": "Dauer der Bewegung",
+              "description": "Dauer der Bewegung in Millisekunden; sie muss kleiner als 10000 sein."
+            }
+          }
+        }
+      ],
+      "returns": {
+        "name": "return",
+        "caption": "",
+        "description": "",
+        "type": "Boolean",
+        "default": null,
+        "localizations": {
+          "de": {
+            "alias": null,
+            "caption": "ob der Roboter das nächste Feld erreicht hat",
+            "description": null
+          }
+        }
+      },
+      "localizations": {
+        "de": {
+          "alias": "gehe vorwärts",
+          "caption": "Bewegt den Roboter auf das nächste Feld in Blickrichtung",
+          "description": null
+        }
+      }
+    },
+    "jump": {
+      "name": "jump",
+      "caption": "Makes the robot jump to the next tile in its current orientation",
+      "description": "Makes the robot jump to the next tile in its current orientation",
+      "parameters": [
+        {
+          "name": "duration",
+          "caption": "Duration of the jump animation",
+          "description": "Duration of the jump animation. The duration is in milliseconds and must be smaller than 10000.",
+          "type": {
+            "Integer": {
+              "start": 0,
+              "end": 10000
+            }
+          },
+          "default": {
+            "Integer": 500
+          },
+          "localizations": {
+            "de": {
+              "alias": "dauer",
+              "caption": "Dauer des Sprungs",
+              "description": "Dauer des Sprungs in Millisekunden; sie muss kleiner als 10000 sein."
+            }
+          }
+        }
+      ],
+      "returns": {
+        "name": "return",
+        "caption": "",
+        "description": "",
+        "type": "Boolean",
+        "default": null,
+        "localizations": {
+          "de": {
+            "alias": null,
+            "caption": "ob der Roboter das nächste Feld erreicht hat",
+            "description": null
+          }
+        }
+      },
+      "localizations": {
+        "de": {
+          "alias": "springe",
+          "caption": "Lässt den Roboter auf das nächste Feld in Blickrichtung springen",
+          "description": null
+        }
+      }
+    },
+    "draw forward": {
+      "name": "draw forward",
+      "caption": "Makes the robot move to the next tile in its current orientation and draw a line onto the floor",
+      "description": "Makes the robot move to the next tile in its current orientation and draw a line onto the floor",
+      "parameters": [
+        {
+          "name": "duration",
+          "caption": "Duration of the move animation",
+          "description": "Duration of the move animation. The duration is in milliseconds and must be smaller than 10000.",
+          "type": {
+            "Integer": {
+              "start": 0,
+              "end": 10000
+            }
+          },
+          "default": {
+            "Integer": 500
+          },
+          "localizations": {
+            "de": {
+              "alias": "dauer",
+              "caption": "Dauer der Bewegung",
+              "description": "Dauer der Bewegung in Millisekunden; sie muss kleiner als 10000 sein."
+            }
+          }
+        }
+      ],
+      "returns": {
+        "name": "return",
+        "caption": "",
+        "description": "",
+        "type": "Boolean",
+        "default": null,
+        "localizations": {
+          "de": {
+            "alias": null,
+            "caption": "ob der Roboter das nächste Feld erreicht hat",
+            "description": null
+          }
+        }
+      },
+      "localizations": {
+        "de": {
+          "alias": "zeichne vorwärts",
+          "caption": "Bewegt den Roboter auf das nächste Feld in Blickrichtung und zeichnet dabei eine Linie auf den Boden",
+          "description": null
+        }
+      }
+    },
+    "turn left": {
+      "name": "turn left",
+      "caption": "Turns the robot 45° in a counter-clockwise direction",
+      "description": "Turns the robot 45° in a counter-clockwise direction",
+      "parameters": [
+        {
+          "name": "duration",
+          "caption": "Duration of the move animation",
+          "description": "Duration of the move animation. The duration is in milliseconds and must be smaller than 10000.",
+          "type": {
+            "Integer": {
+              "start": 0,
+              "end": 10000
+            }
+          },
+          "default": {
+            "Integer": 300
+          },
+          "localizations": {
+            "de": {
+              "alias": "dauer",
+              "caption": "Dauer der Drehung",
+              "description": "Dauer der Drehung in Millisekunden; sie muss kleiner als 10000 sein."
+            }
+          }
+        }
+      ],
+      "returns": null,
+      "localizations": {
+        "de": {
+          "alias": "drehe links",
+          "caption": "Dreht den Roboter um 45° gegen den Uhrzeigersinn",
+          "description": null
+        }
+      }
+    },
+    "turn right": {
+      "name": "turn right",
+      "caption": "Turns the robot 45° in a clockwise direction",
+      "description": "Turns the robot 45° in a clockwise direction",
+      "parameters": [
+        {
+          "name": "duration",
+          "caption": "Duration of the move animation",
+          "description": "Duration of the move animation. The duration is in milliseconds and must be smaller than 10000.",
+          "type": {
+            "Integer": {
+              "start": 0,
+              "end": 10000
+            }
+          },
+          "default": {
+            "Integer": 300
+          },
+          "localizations": {
+            "de": {
+              "alias": "dauer",
+              "caption": "Dauer der Drehung",
+              "description": "Dauer der Drehung in Millisekunden; sie muss kleiner als 10000 sein."
+            }
+          }
+        }
+      ],
+      "returns": null,
+      "localizations": {
+        "de": {
+          "alias": "drehe rechts",
+          "caption": "Dreht den Roboter um 45° im Uhrzeigersinn",
+          "description": null
+        }
+      }
+    },
+    "robot color rgb": {
+      "name": "robot color rgb",
+      "caption": "Set the color of the robot",
+      "description": "Set the color of the robot",
+      "parameters": [
+        {
+          "name": "red",
+          "caption": "Amount of red (0.0 to 1.0)",
+          "description": "Amount of red (0.0 to 1.0)",
+          "type": "Float",
+          "default": null,
+          "localizations": {
+            "de": {
+              "alias": "rot",
+              "caption": "Rotanteil (0.0 bis 1.0)",
+              "description": null
+            }
+          }
+        },
+        {
+          "name": "green",
+          "caption": "Amount of green (0.0 to 1.0)",
+          "description": "Amount of green (0.0 to 1.0)",
+          "type": "Float",
+          "default": null,
+          "localizations": {
+            "de": {
+              "alias": "grün",
+              "caption": "Grünanteil (0.0 bis 1.0)",
+              "description": null
+            }
+          }
+        },
+        {
+          "name": "blue",
+          "caption": "Amount of blue (0.0 to 1.0)",
+          "description": "Amount of blue (0.0 to 1.0)",
+          "type": "Float",
+          "default": null,
+          "localizations": {
+            "de": {
+              "alias": "blau",
+              "caption": "Blauanteil (0.0 bis 1.0)",
+              "description": null
+            }
+          }
+        }
+      ],
+      "returns": null,
+      "localizations": {
+        "de": {
+          "alias": "roboterfarbe rgb",
+          "caption": "Setzt die Farbe des Roboters",
+          "description": null
+        }
+      }
+    },
+    "paint tile": {
+      "name": "paint tile",
+      "caption": "Fill the current tile with the robots current color",
+      "description": "Fill the current tile with the robots current color",
+      "parameters": [],
+      "returns": null,
+      "localizations": {
+        "de": {
+          "alias": "male feld",
+          "caption": "Füllt das aktuelle Feld mit der aktuellen Farbe des Roboters",
+          "description": null
+        }
+      }
+    }
+  },
+  "events": {
+    "robot stopped": {
+      "name": "robot stopped",
+      "caption": "The robot finished its current animation",
+      "description": "The robot stopped moving, turning or jumping, either because the animation has been completed or because it has been cut short",
+      "parameters": [],
+      "localizations": {
+        "de": {
+          "alias": "roboter angehalten",
+          "caption": "Der Roboter hat seine aktuelle Animation beendet",
+          "description": "Der Roboter hat aufgehört, sich zu bewegen, zu drehen oder zu springen, weil die Animation entweder abgeschlossen oder abgebrochen wurde"
+        }
+      }
+    }
+  },
+  "localizations": {
+    "de": {
+      "alias": null,
+      "caption": "Ein einfacher Roboter, der sich über eine Ebene bewegen und Linien zeichnen kann",
+      "description": null
+    }
+  }
+};
+
+/** The fingerprint of {@link API} */
+export const FINGERPRINT = 0x1b1a1d62658ebcb6n;
+
+/**
+ * A simple robot that can be moved across a 2D-plane and draw lines
+ *
+ * Once upon a time there was a lonely robot with the serial number `#C0D1E`. …
+ */
+export class RobotControlClient extends ApiClient {
+    /** @param {MessagePort} port connected to the api server */
+    constructor(port) {
+        super(port, API, FINGERPRINT);
+    }
+
+    /**
+     * Sets the height of the tile at the robot's current location
+     *
+     * @param {number} height height of the tile
+     * @returns {Promise<void>}
+     */
+    setHeight(height) {
+        return this.call("set height", [height]);
+    }
+
+    /**
+     * Makes the robot move to the next tile in its current orientation
+     *
+     * @param {number} [duration] Duration of the move animation
+     * @returns {Promise<boolean>}
+     */
+    moveForward(duration = 500) {
+        return this.call("move forward", [duration]);
+    }
+
+    /**
+     * Makes the robot jump to the next tile in its current orientation
+     *
+     * @param {number} [duration] Duration of the jump animation
+     * @returns {Promise<boolean>}
+     */
+    jump(duration = 500) {
+        return this.call("jump", [duration]);
+    }
+
+    /**
+     * Makes the robot move to the next tile in its current orientation and draw a line onto the floor
+     *
+     * @param {number} [duration] Duration of the move animation
+     * @returns {Promise<boolean>}
+     */
+    drawForward(duration = 500) {
+        return this.call("draw forward", [duration]);
+    }
+
+    /**
+     * Turns the robot 45° in a counter-clockwise direction
+     *
+     * @param {number} [duration] Duration of the move animation
+     * @returns {Promise<void>}
+     */
+    turnLeft(duration = 300) {
+        return this.call("turn left", [duration]);
+    }
+
+    /**
+     * Turns the robot 45° in a clockwise direction
+     *
+     * @param {number} [duration] Duration of the move animation
+     * @returns {Promise<void>}
+     */
+    turnRight(duration = 300) {
+        return this.call("turn right", [duration]);
+    }
+
+    /**
+     * Set the color of the robot
+     *
+     * @param {number} red Amount of red (0.0 to 1.0)
+     * @param {number} green Amount of green (0.0 to 1.0)
+     * @param {number} blue Amount of blue (0.0 to 1.0)
+     * @returns {Promise<void>}
+     */
+    robotColorRgb(red, green, blue) {
+        return this.call("robot color rgb", [red, green, blue]);
+    }
+
+    /**
+     * Fill the current tile with the robots current color
+     *
+     * @returns {Promise<void>}
+     */
+    paintTile() {
+        return this.call("paint tile", []);
+    }
+
+    /**
+     * The robot finished its current animation
+     *
+     * The robot stopped moving, turning or jumping, either because the animation has been completed or because it has been cut short
+     *
+     * @param {() => void} handler receives the payload of each event
+     */
+    onRobotStopped(handler) {
+        this.on("robot stopped", handler);
+    }
+
+    /** Stops receiving `robot stopped` events. */
+    offRobotStopped() {
+        this.off("robot stopped");
+    }
+}
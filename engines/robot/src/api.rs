@@ -1,8 +1,81 @@
+use gam3du_framework_macros::api;
+
+/// A robot that can be moved across a 2D-plane and paint its floor
+///
+/// This is the api the engine offers to its plugins.
+#[api(name = "robot plugin", version = "0.1.0")]
 pub trait EngineApi {
-    fn set_height(&mut self, height: f32);
-    fn move_forward(&mut self, draw: bool, duration: u64) -> bool;
-    fn jump(&mut self, duration: u64) -> bool;
-    fn turn(&mut self, steps_ccw: i8, duration: u64);
-    fn robot_color_rgb(&mut self, red: f32, green: f32, blue: f32);
+    /// Sets the height of the tile at the robot's current location
+    fn set_height(&mut self, #[api(caption = "height of the tile")] height: f32);
+
+    /// Makes the robot move to the next tile in its current orientation
+    ///
+    /// Returns `false` if the robot was unable to move.
+    fn move_forward(
+        &mut self,
+        #[api(caption = "draw a line onto the floor while moving")] draw: bool,
+        #[api(
+            caption = "duration of the move animation in milliseconds",
+            range = 0..10_000,
+            default = 500
+        )]
+        duration: u64,
+    ) -> bool;
+
+    /// Makes the robot jump to the next tile in its current orientation
+    ///
+    /// Returns `false` if the robot was unable to jump.
+    fn jump(
+        &mut self,
+        #[api(
+            caption = "duration of the jump animation in milliseconds",
+            range = 0..10_000,
+            default = 500
+        )]
+        duration: u64,
+    ) -> bool;
+
+    /// Makes the robot turn in place
+    fn turn(
+        &mut self,
+        #[api(caption = "45° steps to turn counterclockwise (negative values turn clockwise)")]
+        steps_ccw: i8,
+        #[api(
+            caption = "duration of the turn animation in milliseconds",
+            range = 0..10_000,
+            default = 300
+        )]
+        duration: u64,
+    );
+
+    /// Sets the color of the robot
+    fn robot_color_rgb(
+        &mut self,
+        #[api(caption = "red component between 0.0 and 1.0")] red: f32,
+        #[api(caption = "green component between 0.0 and 1.0")] green: f32,
+        #[api(caption = "blue component between 0.0 and 1.0")] blue: f32,
+    );
+
+    /// Paints the tile at the robot's current location
     fn paint_tile(&mut self);
+
+    /// Stops the robot by completing its current animation immediately
+    fn stop(&mut self);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::engine_api;
+
+    /// `api.json` is used to generate the Python stub of the plugin api at build time.
+    #[test]
+    fn exported_descriptor_is_up_to_date() {
+        let exported = include_str!("../api.json");
+        let descriptor = serde_json::to_string_pretty(&engine_api::descriptor()).unwrap();
+        assert_eq!(
+            exported.trim_end(),
+            descriptor,
+            "`engines/robot/api.json` needs to be replaced by the descriptor"
+        );
+    }
 }
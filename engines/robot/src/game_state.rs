@@ -215,4 +215,8 @@ impl EngineApi for GameState {
     fn paint_tile(&mut self) {
         self.paint_tile_();
     }
+
+    fn stop(&mut self) {
+        self.robot.complete_animation(&mut self.event_registries);
+    }
 }
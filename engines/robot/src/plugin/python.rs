@@ -6,14 +6,26 @@ use std::{
 };
 
 use super::Plugin;
-use crate::{GameState, events::GameEvent};
-use gam3du_framework_common::module::Module;
-use runtime_python::{PythonRuntime, PythonRuntimeBuilder};
-use rustpython_vm::pymodule;
-use tracing::debug;
+use crate::{GameState, api::engine_api, events::GameEvent};
+use gam3du_framework_common::{
+    api::{ApiDescriptor, ConversionError, Identifier, dispatch::DispatchError},
+    module::Module,
+};
+use runtime_python::{PythonRuntime, PythonRuntimeBuilder, py_to_value, value_to_py};
+use rustpython_vm::{
+    PyObjectRef, PyResult, VirtualMachine, builtins::PyBaseExceptionRef, pymodule,
+};
+use tracing::{debug, trace};
+
+/// Name of the native module offering the generic `call` function
+const INTERNAL_MODULE_NAME: &str = "robot_plugin_api_internal";
+
+/// Name of the generated module wrapping each api function into a Python function
+const MODULE_NAME: &str = "robot_plugin_api";
 
 thread_local! {
     pub(crate) static VM_GAME_STATE: RefCell<GameState> = RefCell::new(GameState::bogus());
+    static ENGINE_API: ApiDescriptor = engine_api::descriptor();
 }
 
 pub struct PythonPlugin {
@@ -31,7 +43,9 @@ impl PythonPlugin {
     pub fn new(mut runtime_builder: PythonRuntimeBuilder) -> Self {
         let (sender, receiver) = channel();
 
-        runtime_builder.add_native_module("robot_plugin_api", || Box::new(plugin_api::make_module));
+        runtime_builder
+            .add_native_module(INTERNAL_MODULE_NAME, || Box::new(plugin_api::make_module));
+        runtime_builder.add_source_module(MODULE_NAME, Self::generate_wrappers());
         let user_signal = runtime_builder.enable_user_signals();
 
         Self {
@@ -81,6 +95,21 @@ impl PythonPlugin {
         self.runtime.wake();
     }
 
+    /// Generates the Python functions forwarding to the generic `call` of the native module.
+    fn generate_wrappers() -> String {
+        let mut source = Vec::new();
+        ENGINE_API
+            .with(|api| {
+                runtime_python_bindgen::generate_native_wrappers(
+                    &mut source,
+                    api,
+                    INTERNAL_MODULE_NAME,
+                )
+            })
+            .unwrap();
+        String::from_utf8(source).unwrap()
+    }
+
     fn swap_vm_game_state(game_state: &mut GameState) {
         VM_GAME_STATE.with_borrow_mut(|locked_state| {
             mem::swap(locked_state, game_state);
@@ -103,6 +132,65 @@ impl Plugin for PythonPlugin {
     }
 }
 
+/// Converts the arguments of a Python call, dispatches it to the game state and converts the
+/// result back.
+fn call(
+    function: String,
+    arguments: Vec<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyObjectRef> {
+    trace!("pyfunction: call({function:?})");
+    let function = Identifier(function.into());
+
+    let arguments = ENGINE_API.with(|api| {
+        let Some(descriptor) = api.functions.get(&function) else {
+            return Err(dispatch_error(
+                vm,
+                &DispatchError::UnknownFunction(function.clone()),
+            ));
+        };
+        if descriptor.parameters.len() != arguments.len() {
+            return Err(dispatch_error(
+                vm,
+                &DispatchError::WrongArgumentCount {
+                    function: function.clone(),
+                    expected: descriptor.parameters.len(),
+                    actual: arguments.len(),
+                },
+            ));
+        }
+        descriptor
+            .parameters
+            .iter()
+            .zip(&arguments)
+            .map(|(parameter, argument)| py_to_value(vm, &parameter.typ, argument))
+            .collect::<PyResult<Vec<_>>>()
+    })?;
+
+    let result = VM_GAME_STATE
+        .with_borrow_mut(|game_state| engine_api::dispatch(game_state, &function, arguments))
+        .map_err(|error| dispatch_error(vm, &error))?;
+
+    Ok(value_to_py(vm, result))
+}
+
+/// Maps errors to the closest matching built-in Python exception.
+fn dispatch_error(vm: &VirtualMachine, error: &DispatchError) -> PyBaseExceptionRef {
+    let message = error.to_string();
+    match *error {
+        DispatchError::UnknownFunction(_) => vm.new_attribute_error(message),
+        DispatchError::WrongArgumentCount { .. }
+        | DispatchError::InvalidArgument {
+            error: ConversionError::TypeMismatch { .. },
+            ..
+        } => vm.new_type_error(message),
+        DispatchError::InvalidArgument {
+            error: ConversionError::OutOfRange { .. },
+            ..
+        } => vm.new_value_error(message),
+    }
+}
+
 #[pymodule]
 mod plugin_api {
     #![expect(
@@ -110,45 +198,13 @@ mod plugin_api {
         reason = "cannot pass &str in this macro"
     )]
 
-    use super::VM_GAME_STATE;
-    use crate::api::EngineApi;
-    use rustpython_vm::VirtualMachine;
+    use rustpython_vm::{PyObjectRef, PyResult, VirtualMachine, function::PosArgs};
     use tracing::{debug, error, info, trace, warn};
 
     #[pyfunction]
-    fn set_height(height: f32, _vm: &VirtualMachine) {
-        trace!("pyfunction: set_height({height})");
-        VM_GAME_STATE.with_borrow_mut(|game_state| game_state.set_height(height));
-    }
-
-    #[pyfunction]
-    fn move_forward(draw: bool, duration: u64, _vm: &VirtualMachine) -> bool {
-        trace!("pyfunction: move_forward({duration})");
-        VM_GAME_STATE.with_borrow_mut(|game_state| game_state.move_forward(draw, duration))
-    }
-
-    #[pyfunction]
-    fn jump(duration: u64, _vm: &VirtualMachine) -> bool {
-        trace!("pyfunction: jump({duration})");
-        VM_GAME_STATE.with_borrow_mut(|game_state| game_state.jump(duration))
-    }
-
-    #[pyfunction]
-    fn turn(steps_ccw: i8, duration: u64, _vm: &VirtualMachine) {
-        trace!("pyfunction: turn_left({duration})");
-        VM_GAME_STATE.with_borrow_mut(|game_state| game_state.turn(steps_ccw, duration));
-    }
-
-    #[pyfunction]
-    fn paint_tile(_vm: &VirtualMachine) {
-        trace!("pyfunction: paint_tile()");
-        VM_GAME_STATE.with_borrow_mut(EngineApi::paint_tile);
-    }
-
-    #[pyfunction]
-    fn robot_color_rgb(red: f32, green: f32, blue: f32, _vm: &VirtualMachine) {
-        trace!("pyfunction: robot_color_rgb({red}, {green}, {blue})");
-        VM_GAME_STATE.with_borrow_mut(|game_state| game_state.robot_color_rgb(red, green, blue));
+    fn call(function: String, arguments: PosArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::call(function, arguments.into_vec(), vm)
     }
 
     #[pyfunction]
@@ -1,9 +1,32 @@
 //! Contains all the building blocks to specify an API and perform reflection thereon.
 
+mod capabilities;
+mod convert;
+mod diff;
+pub mod dispatch;
+mod json;
+mod locale;
+mod markup;
+mod schema;
+mod validation;
+mod version;
+
+pub use capabilities::{Capabilities, CapabilitiesError};
+pub use convert::{ApiValue, ConversionError, check_range};
+pub use diff::{Change, ChangeKind, Compatibility};
 use indexmap::IndexMap as HashMap;
+pub use json::{JsonError, JsonErrorKind};
+pub use locale::{Locale, Localization, Localized, Phrases, Texts};
+pub use markup::{Block, Document, Inline, LinkName, MarkupError};
+pub use schema::{SchemaViolation, check_schema};
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, convert::Infallible, fmt::Display, ops::Range};
+use std::{borrow::Cow, error::Error, fmt::Display, ops::Range};
+pub use validation::{LoadError, RESERVED_PARAMETER_NAMES, ValidationError, ValidationErrorKind};
+pub use version::{ApiVersion, ApiVersionError};
 
+/// A caption or description of an element.
+///
+/// The text may contain a small subset of Markdown (see [`Document`] for details).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RichText(pub String);
 
@@ -21,6 +44,69 @@ pub struct RichText(pub String);
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Identifier(pub Cow<'static, str>);
 
+impl Identifier {
+    /// Checks whether the given name satisfies the rules of an [`Identifier`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first rule being violated by `name`.
+    pub fn check(name: &str) -> Result<(), IdentifierError> {
+        if name.is_empty() {
+            return Err(IdentifierError::Empty);
+        }
+        if name.starts_with(' ') {
+            return Err(IdentifierError::LeadingSpace);
+        }
+        if name.ends_with(' ') {
+            return Err(IdentifierError::TrailingSpace);
+        }
+        if name.contains("  ") {
+            return Err(IdentifierError::AdjacentSpaces);
+        }
+        match name
+            .chars()
+            .find(|character| !matches!(character, 'a'..='z' | '0'..='9' | ' '))
+        {
+            Some(character) => Err(IdentifierError::InvalidCharacter(character)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks whether this identifier satisfies the rules of an [`Identifier`].
+    ///
+    /// This is only necessary for identifiers that have been constructed directly or deserialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first rule being violated by this identifier.
+    pub fn validate(&self) -> Result<(), IdentifierError> {
+        Self::check(self.as_ref())
+    }
+
+    /// Checks whether the given name satisfies the rules of an alias.
+    ///
+    /// Aliases follow the same rules as [`Identifier`]s but may contain any lowercase letter of
+    /// their language (like `ä` or `ß`) as they will only be used by front-ends of that language.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first rule being violated by `name`.
+    pub fn check_alias(name: &str) -> Result<(), IdentifierError> {
+        // replacing all letters makes the alias subject to the rules of identifiers
+        let ascii = name
+            .chars()
+            .map(|character| {
+                if character.is_alphabetic() && !character.is_uppercase() {
+                    'a'
+                } else {
+                    character
+                }
+            })
+            .collect::<String>();
+        Self::check(&ascii)
+    }
+}
+
 impl AsRef<str> for Identifier {
     fn as_ref(&self) -> &str {
         self.0.as_ref()
@@ -33,28 +119,148 @@ impl Display for Identifier {
     }
 }
 
-#[expect(
-    clippy::infallible_try_from,
-    reason = "// TODO this will change as soon as the validation is implemented"
-)]
 impl TryFrom<String> for Identifier {
-    type Error = Infallible;
+    type Error = IdentifierError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::check(&value)?;
         Ok(Self(Cow::Owned(value)))
     }
 }
 
+/// Describes why a name is not a valid [`Identifier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdentifierError {
+    /// An identifier must contain at least one character
+    Empty,
+    /// Only `a-z`, `0-9` and ` ` are allowed
+    InvalidCharacter(char),
+    /// An identifier may not start with a space
+    LeadingSpace,
+    /// An identifier may not end with a space
+    TrailingSpace,
+    /// Words must be separated by a single space
+    AdjacentSpaces,
+}
+
+impl Display for IdentifierError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Empty => write!(formatter, "identifier is empty"),
+            Self::InvalidCharacter(character) => {
+                write!(
+                    formatter,
+                    "identifier contains the invalid character {character:?}"
+                )
+            }
+            Self::LeadingSpace => write!(formatter, "identifier starts with a space"),
+            Self::TrailingSpace => write!(formatter, "identifier ends with a space"),
+            Self::AdjacentSpaces => write!(formatter, "identifier contains adjacent spaces"),
+        }
+    }
+}
+
+impl Error for IdentifierError {}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ApiDescriptor {
     /// technical name of this API
     pub name: Identifier,
+    /// revision of this API; see [`ApiVersion`] for the compatibility rules
+    #[serde(default)]
+    pub version: ApiVersion,
     /// a single-line explanation what this api is for
     pub caption: RichText,
     /// a multi-line explanation what this api is for
     pub description: RichText,
     /// List of all functions this API provides
     pub functions: HashMap<Identifier, FunctionDescriptor>,
+    /// List of all events a client of this API may subscribe to
+    #[serde(default)]
+    pub events: HashMap<Identifier, EventDescriptor>,
+    /// Translations into other languages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<Locale, Localization>,
+}
+
+impl ApiDescriptor {
+    /// Parses a JSON-encoded API descriptor and validates it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoadError::Schema`] if the input does not match [`ApiDescriptor::json_schema`],
+    /// [`LoadError::Syntax`] if the input cannot be deserialized otherwise and
+    /// [`LoadError::Invalid`] containing all problems found by [`ApiDescriptor::validate`].
+    pub fn load(json: &[u8]) -> Result<Self, LoadError> {
+        let api: Self = serde_json::from_slice(json).map_err(|error| {
+            // the schema points to the actual mistake where serde only sees the symptom
+            match serde_json::from_slice(json) {
+                Ok(value) => {
+                    let violations = Self::check_json(&value);
+                    if violations.is_empty() {
+                        LoadError::Syntax(error)
+                    } else {
+                        LoadError::Schema(violations)
+                    }
+                }
+                Err(_) => LoadError::Syntax(error),
+            }
+        })?;
+        api.validate().map_err(LoadError::Invalid)?;
+        Ok(api)
+    }
+
+    /// Returns a JSON Schema (draft 2020-12) of the serialized form of api descriptors.
+    ///
+    /// Editors can use it to offer completion and validation while writing descriptors by hand.
+    #[must_use]
+    pub fn json_schema() -> serde_json::Value {
+        schema::schema()
+    }
+
+    /// Checks a JSON document against [`ApiDescriptor::json_schema`].
+    ///
+    /// This only covers the structure of the document; use [`ApiDescriptor::validate`] on the
+    /// deserialized descriptor for the remaining checks.
+    #[must_use]
+    pub fn check_json(json: &serde_json::Value) -> Vec<SchemaViolation> {
+        check_schema(&schema::schema(), json)
+    }
+
+    /// Performs all the checks that cannot be expressed by the serialized representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns all problems found rather than stopping at the first one.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let errors = validation::validate(self);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns a hash identifying this revision of the api.
+    ///
+    /// Two descriptors with the same version but different fingerprints have diverged
+    /// without bumping the version.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        version::fingerprint(self)
+    }
+
+    /// Lists all locales any element of this api has been translated into.
+    #[must_use]
+    pub fn locales(&self) -> Vec<&Locale> {
+        locale::locales(self)
+    }
+
+    /// Lists all changes between this (older) and the given (newer) revision of the api.
+    #[must_use]
+    pub fn diff(&self, newer: &Self) -> Vec<Change> {
+        diff::diff(self, newer)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -69,6 +275,60 @@ pub struct FunctionDescriptor {
     pub parameters: Vec<ParameterDescriptor>,
     /// List of all parameters this function requires
     pub returns: Option<ParameterDescriptor>,
+    /// Translations into other languages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<Locale, Localization>,
+}
+
+impl FunctionDescriptor {
+    /// Converts a JSON object mapping parameter names to plain JSON values into the list of
+    /// arguments for this function.
+    ///
+    /// Omitted parameters take their default value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first argument that is missing, unknown or does not match its type.
+    pub fn arguments_from_json(
+        &self,
+        json: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Vec<Value>, JsonError> {
+        json::arguments_from_json(self, json)
+    }
+
+    /// Appends the default values of the trailing parameters that have been omitted.
+    ///
+    /// The arguments stay untouched if any omitted parameter has no default value, so the call will
+    /// be rejected for passing the wrong number of arguments.
+    pub fn fill_defaults(&self, arguments: &mut Vec<Value>) {
+        let Some(omitted) = self.parameters.get(arguments.len()..) else {
+            return;
+        };
+        let Some(defaults) = omitted
+            .iter()
+            .map(|parameter| parameter.default.clone())
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        arguments.extend(defaults);
+    }
+}
+
+/// Something the server notifies its subscribed clients about
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventDescriptor {
+    /// technical name of this event
+    pub name: Identifier,
+    /// a single-line explanation when this event occurs
+    pub caption: RichText,
+    /// a multi-line explanation when this event occurs
+    pub description: RichText,
+    /// List of all values this event carries as payload
+    pub parameters: Vec<ParameterDescriptor>,
+    /// Translations into other languages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<Locale, Localization>,
 }
 
 /// Description of a function parameter or return value
@@ -86,10 +346,13 @@ pub struct ParameterDescriptor {
     pub typ: TypeDescriptor,
     /// If the parameter is omitted, use a default value
     pub default: Option<Value>,
+    /// Translations into other languages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<Locale, Localization>,
 }
 
 /// Describes the set of valid values for a parameter or variable.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[allow(variant_size_differences, reason = "still small enough")]
 pub enum TypeDescriptor {
     /// Any integer value within the defined range. Unsigned values will typically have a lower bound of `0`
@@ -98,7 +361,16 @@ pub enum TypeDescriptor {
     Float,
     Boolean,
     String,
+    /// Any number of elements sharing the same type
     List(Box<TypeDescriptor>),
+    /// A fixed number of elements, each with its own type
+    Tuple(Vec<TypeDescriptor>),
+    /// A fixed set of named fields, each with its own type
+    Record(HashMap<Identifier, TypeDescriptor>),
+    /// Exactly one out of a fixed set of named variants (e.g. the eight directions)
+    Enumeration(Vec<Identifier>),
+    /// Either a value of the inner type or nothing at all
+    Optional(Box<TypeDescriptor>),
 }
 
 impl TypeDescriptor {
@@ -106,15 +378,115 @@ impl TypeDescriptor {
     pub const MAX_UNSIGNED_INTEGER: u64 = (1 << Self::INTEGER_BITS) - 1;
     pub const MAX_SIGNED_INTEGER: i64 = (1 << (Self::INTEGER_BITS - 1)) - 1;
     pub const MIN_INTEGER: i64 = -(1 << (Self::INTEGER_BITS - 1));
+
+    /// Returns whether the given value is a member of the set of values described by this type.
+    #[must_use]
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Integer(range), &Value::Integer(value)) => range.contains(&value),
+            (Self::Float, Value::Float(_))
+            | (Self::Boolean, Value::Boolean(_))
+            | (Self::String, Value::String(_))
+            | (Self::Optional(_), Value::Optional(None)) => true,
+            (Self::List(element_type), Value::List(elements)) => {
+                elements.iter().all(|element| element_type.accepts(element))
+            }
+            (Self::Tuple(element_types), Value::Tuple(elements)) => {
+                element_types.len() == elements.len()
+                    && element_types
+                        .iter()
+                        .zip(elements)
+                        .all(|(element_type, element)| element_type.accepts(element))
+            }
+            (Self::Record(field_types), Value::Record(fields)) => {
+                field_types.len() == fields.len()
+                    && field_types.iter().all(|(name, field_type)| {
+                        fields
+                            .get(name)
+                            .is_some_and(|field| field_type.accepts(field))
+                    })
+            }
+            (Self::Enumeration(variants), Value::Enumeration(variant)) => {
+                variants.contains(variant)
+            }
+            (Self::Optional(inner_type), Value::Optional(Some(inner))) => inner_type.accepts(inner),
+            _ => false,
+        }
+    }
+
+    /// Interprets a plain JSON value (e.g. `500` rather than `{"Integer": 500}`) as a member of
+    /// this type.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first element that does not match its type, together with its JSON path.
+    pub fn value_from_json(&self, json: &serde_json::Value) -> Result<Value, JsonError> {
+        json::value_from_json(self, json, "$")
+    }
 }
 
 /// A value for a parameter.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     Unit,
     Integer(i64),
     Float(f32),
     Boolean(bool),
     String(String),
-    List(Box<Value>),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+    Record(HashMap<Identifier, Value>),
+    Enumeration(Identifier),
+    Optional(Option<Box<Value>>),
+}
+
+impl Value {
+    /// Converts this value into plain JSON (e.g. `500` rather than `{"Integer": 500}`).
+    ///
+    /// Floats which are not finite cannot be represented in JSON and become `null`.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        json::value_to_json(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Identifier, IdentifierError};
+
+    #[test]
+    fn valid_identifiers() {
+        for name in ["move forward", "x", "level 2", "rgb 255 0 0"] {
+            assert_eq!(Identifier::check(name), Ok(()), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_identifiers() {
+        for (name, error) in [
+            ("", IdentifierError::Empty),
+            (" move", IdentifierError::LeadingSpace),
+            ("move ", IdentifierError::TrailingSpace),
+            ("move  forward", IdentifierError::AdjacentSpaces),
+            ("Move", IdentifierError::InvalidCharacter('M')),
+            ("move_forward", IdentifierError::InvalidCharacter('_')),
+            ("vorwärts", IdentifierError::InvalidCharacter('ä')),
+        ] {
+            assert_eq!(Identifier::check(name), Err(error), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn aliases_may_contain_lowercase_letters() {
+        assert_eq!(Identifier::check_alias("gehe vorwärts"), Ok(()));
+        assert_eq!(Identifier::check_alias("straße"), Ok(()));
+        assert_eq!(
+            Identifier::check_alias("Straße"),
+            Err(IdentifierError::InvalidCharacter('S'))
+        );
+        assert_eq!(
+            Identifier::check_alias("gehe  vorwärts"),
+            Err(IdentifierError::AdjacentSpaces)
+        );
+    }
 }
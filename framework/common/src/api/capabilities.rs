@@ -0,0 +1,141 @@
+//! Named allow-lists restricting the functions of an api a client may call.
+//!
+//! Teachers may use them to expose only a few functions in early lessons and unlock the others
+//! later on. A set of capabilities is stored as JSON, e.g.
+//!
+//! ```json
+//! { "name": "lesson 1", "functions": ["move forward", "turn left"] }
+//! ```
+
+use super::{ApiDescriptor, Identifier, IdentifierError};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// The functions of an api a client is permitted to call
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Names this set of capabilities in error messages and generated bindings
+    pub name: Identifier,
+    /// The functions that may be called; all others will be rejected
+    pub functions: Vec<Identifier>,
+}
+
+impl Capabilities {
+    /// Permits calling the given functions of the api.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid [`Identifier`] or the api does not provide one
+    /// of the functions.
+    pub fn new(
+        name: Identifier,
+        api: &ApiDescriptor,
+        functions: impl IntoIterator<Item = Identifier>,
+    ) -> Result<Self, CapabilitiesError> {
+        let capabilities = Self {
+            name,
+            functions: functions.into_iter().collect(),
+        };
+        capabilities.validate(api)?;
+        Ok(capabilities)
+    }
+
+    /// Permits calling all functions of the api.
+    #[must_use]
+    pub fn all(name: Identifier, api: &ApiDescriptor) -> Self {
+        Self {
+            name,
+            functions: api.functions.keys().cloned().collect(),
+        }
+    }
+
+    /// Parses a JSON-encoded set of capabilities and checks it against the api.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be deserialized or does not fit the api.
+    pub fn load(json: &[u8], api: &ApiDescriptor) -> Result<Self, CapabilitiesError> {
+        let capabilities: Self = serde_json::from_slice(json)
+            .map_err(|error| CapabilitiesError::Syntax(error.to_string()))?;
+        capabilities.validate(api)?;
+        Ok(capabilities)
+    }
+
+    /// Checks whether the name is valid and the api provides all permitted functions.
+    ///
+    /// This is only necessary for capabilities that have been constructed directly or
+    /// deserialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found.
+    pub fn validate(&self, api: &ApiDescriptor) -> Result<(), CapabilitiesError> {
+        self.name
+            .validate()
+            .map_err(CapabilitiesError::InvalidName)?;
+        match self
+            .functions
+            .iter()
+            .find(|&function| !api.functions.contains_key(function))
+        {
+            Some(function) => Err(CapabilitiesError::UnknownFunction(function.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns whether the function may be called.
+    #[must_use]
+    pub fn permits(&self, function: &Identifier) -> bool {
+        self.functions.contains(function)
+    }
+
+    /// Returns a copy of the api containing only the permitted functions.
+    ///
+    /// Events are not restricted; the result keeps the name, version and localizations of the api.
+    #[must_use]
+    pub fn restrict(&self, api: &ApiDescriptor) -> ApiDescriptor {
+        let mut restricted = api.clone();
+        restricted
+            .functions
+            .retain(|function, _| self.permits(function));
+        restricted
+    }
+}
+
+/// A set of capabilities does not fit the api it is meant for.
+#[derive(Clone, Debug)]
+pub enum CapabilitiesError {
+    /// The input is not a well-formed set of capabilities
+    Syntax(String),
+    /// The name is not a valid [`Identifier`]
+    InvalidName(IdentifierError),
+    /// A permitted function is not provided by the api
+    UnknownFunction(Identifier),
+}
+
+impl Display for CapabilitiesError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => write!(formatter, "malformed capabilities: {error}"),
+            Self::InvalidName(error) => write!(formatter, "invalid name of capabilities: {error}"),
+            Self::UnknownFunction(function) => {
+                write!(
+                    formatter,
+                    "the api does not provide the function `{function}`"
+                )
+            }
+        }
+    }
+}
+
+impl Error for CapabilitiesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidName(error) => Some(error),
+            Self::Syntax(_) | Self::UnknownFunction(_) => None,
+        }
+    }
+}
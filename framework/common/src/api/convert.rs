@@ -0,0 +1,312 @@
+//! Conversions between native Rust types and [`Value`]s.
+//!
+//! These are mainly used by the code generated by `#[gam3du_framework_macros::api]`, but may be
+//! used directly as well.
+
+use super::{TypeDescriptor, Value};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    ops::Range,
+};
+
+/// A Rust type that has a representation as [`Value`].
+pub trait ApiValue: Sized {
+    /// Describes the set of values this type can represent.
+    #[must_use]
+    fn type_descriptor() -> TypeDescriptor;
+
+    /// Converts this into its api representation.
+    #[must_use]
+    fn into_value(self) -> Value;
+
+    /// Converts the api representation back into this type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a member of [`ApiValue::type_descriptor`].
+    fn try_from_value(value: Value) -> Result<Self, ConversionError>;
+}
+
+/// Reasons why a [`Value`] could not be converted into a Rust type.
+#[derive(Clone, Debug)]
+pub enum ConversionError {
+    /// The value has a different type than expected
+    TypeMismatch {
+        expected: Box<TypeDescriptor>,
+        value: Box<Value>,
+    },
+    /// The integer value is valid in general, but outside of the expected range
+    OutOfRange { range: Range<i64>, value: i64 },
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, value } => {
+                write!(
+                    formatter,
+                    "expected a value of {expected:?} but got {value:?}"
+                )
+            }
+            Self::OutOfRange { range, value } => {
+                write!(formatter, "value {value} is out of range {range:?}")
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+impl ConversionError {
+    fn mismatch<T: ApiValue>(value: Value) -> Self {
+        Self::TypeMismatch {
+            expected: Box::new(T::type_descriptor()),
+            value: Box::new(value),
+        }
+    }
+}
+
+/// Checks whether an integer value lies within the given range.
+///
+/// # Errors
+///
+/// Returns [`ConversionError::OutOfRange`] if the value is outside of the range.
+pub fn check_range(range: Range<i64>, value: i64) -> Result<(), ConversionError> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(ConversionError::OutOfRange { range, value })
+    }
+}
+
+impl ApiValue for () {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Tuple(Vec::new())
+    }
+
+    fn into_value(self) -> Value {
+        Value::Tuple(Vec::new())
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            // functions without return value answer with `Unit`
+            Value::Unit => Ok(()),
+            Value::Tuple(elements) if elements.is_empty() => Ok(()),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+/// Integer types are limited to the range given by [`TypeDescriptor::INTEGER_BITS`].
+macro_rules! integer {
+    ($($typ:ty),*) => {
+        $(
+            impl ApiValue for $typ {
+                fn type_descriptor() -> TypeDescriptor {
+                    let start = i64::try_from(<$typ>::MIN)
+                        .unwrap_or(i64::MIN)
+                        .max(TypeDescriptor::MIN_INTEGER);
+                    let max = if start < 0 {
+                        TypeDescriptor::MAX_SIGNED_INTEGER
+                    } else {
+                        i64::try_from(TypeDescriptor::MAX_UNSIGNED_INTEGER).unwrap_or(i64::MAX)
+                    };
+                    let end = i64::try_from(<$typ>::MAX).unwrap_or(i64::MAX).min(max);
+                    TypeDescriptor::Integer(start..end.saturating_add(1))
+                }
+
+                fn into_value(self) -> Value {
+                    #[allow(
+                        trivial_numeric_casts,
+                        clippy::cast_possible_wrap,
+                        clippy::cast_lossless,
+                        reason = "values beyond the range of the type descriptor are not supported"
+                    )]
+                    Value::Integer(self as i64)
+                }
+
+                fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+                    let Value::Integer(integer) = value else {
+                        return Err(ConversionError::mismatch::<Self>(value));
+                    };
+                    let TypeDescriptor::Integer(range) = Self::type_descriptor() else {
+                        unreachable!("integer types are described by integer ranges");
+                    };
+                    check_range(range, integer)?;
+                    Self::try_from(integer).map_err(|_| ConversionError::mismatch::<Self>(value))
+                }
+            }
+        )*
+    };
+}
+
+integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl ApiValue for f32 {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Float
+    }
+
+    fn into_value(self) -> Value {
+        Value::Float(self)
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Float(value) => Ok(value),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+impl ApiValue for bool {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Boolean
+    }
+
+    fn into_value(self) -> Value {
+        Value::Boolean(self)
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Boolean(value) => Ok(value),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+impl ApiValue for String {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::String
+    }
+
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::String(value) => Ok(value),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+impl<T: ApiValue> ApiValue for Vec<T> {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::List(Box::new(T::type_descriptor()))
+    }
+
+    fn into_value(self) -> Value {
+        Value::List(self.into_iter().map(ApiValue::into_value).collect())
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::List(elements) => elements.into_iter().map(T::try_from_value).collect(),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+impl<T: ApiValue> ApiValue for Option<T> {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Optional(Box::new(T::type_descriptor()))
+    }
+
+    fn into_value(self) -> Value {
+        Value::Optional(self.map(|inner| Box::new(inner.into_value())))
+    }
+
+    fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Optional(None) => Ok(None),
+            Value::Optional(Some(inner)) => T::try_from_value(*inner).map(Some),
+            other => Err(ConversionError::mismatch::<Self>(other)),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ($($element:ident),+) => {
+        impl<$($element: ApiValue),+> ApiValue for ($($element,)+) {
+            fn type_descriptor() -> TypeDescriptor {
+                TypeDescriptor::Tuple(vec![$($element::type_descriptor()),+])
+            }
+
+            #[allow(non_snake_case, reason = "reusing the type parameters as variable names")]
+            fn into_value(self) -> Value {
+                let ($($element,)+) = self;
+                Value::Tuple(vec![$($element.into_value()),+])
+            }
+
+            fn try_from_value(value: Value) -> Result<Self, ConversionError> {
+                let arity = [$(stringify!($element)),+].len();
+                let elements = match value {
+                    Value::Tuple(elements) if elements.len() == arity => elements,
+                    other => return Err(ConversionError::mismatch::<Self>(other)),
+                };
+                let mut elements = elements.into_iter();
+                Ok(($($element::try_from_value(elements.next().unwrap())?,)+))
+            }
+        }
+    };
+}
+
+tuple!(First);
+tuple!(First, Second);
+tuple!(First, Second, Third);
+tuple!(First, Second, Third, Fourth);
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiValue, ConversionError};
+    use crate::api::Value;
+    use std::fmt::Debug;
+
+    /// The value must be a member of the type and convert back into the original.
+    fn round_trip<T: ApiValue + Clone + Debug + PartialEq>(original: &T) {
+        let value = original.clone().into_value();
+        assert!(
+            T::type_descriptor().accepts(&value),
+            "{value:?} is not accepted by {:?}",
+            T::type_descriptor()
+        );
+        assert_eq!(&T::try_from_value(value).unwrap(), original);
+    }
+
+    #[test]
+    fn values_match_their_descriptors() {
+        round_trip(&());
+        round_trip(&-5_i8);
+        round_trip(&u8::MAX);
+        round_trip(&i64::from(i32::MIN));
+        round_trip(&1.5_f32);
+        round_trip(&true);
+        round_trip(&"text".to_owned());
+        round_trip(&vec![1_u16, 2, 3]);
+        round_trip(&Some(7_u32));
+        round_trip(&None::<bool>);
+        round_trip(&(1_u8, "two".to_owned(), (3.0_f32,)));
+    }
+
+    #[test]
+    fn unit_accepts_the_empty_result() {
+        <()>::try_from_value(Value::Unit).unwrap();
+    }
+
+    #[test]
+    fn integers_are_checked() {
+        assert!(matches!(
+            u8::try_from_value(Value::Integer(256)),
+            Err(ConversionError::OutOfRange { value: 256, .. })
+        ));
+        assert!(matches!(
+            u8::try_from_value(Value::Boolean(true)),
+            Err(ConversionError::TypeMismatch { .. })
+        ));
+    }
+}
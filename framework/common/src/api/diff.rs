@@ -0,0 +1,602 @@
+//! Compares two revisions of an [`ApiDescriptor`] and classifies every change by whether existing
+//! clients will continue to work.
+//!
+//! Changes are reported with a JSON path pointing to the affected element of the older revision,
+//! e.g. `$.functions["move forward"].parameters[0].type`.
+
+use super::{
+    ApiDescriptor, EventDescriptor, FunctionDescriptor, Identifier, ParameterDescriptor,
+    TypeDescriptor, Value,
+};
+use std::fmt::{self, Display};
+
+/// A single difference between two revisions of an [`ApiDescriptor`].
+#[derive(Clone, Debug)]
+pub struct Change {
+    /// JSON path to the affected element
+    pub path: String,
+    /// What has been changed
+    pub kind: ChangeKind,
+    /// Whether existing clients will continue to work
+    pub compatibility: Compatibility,
+}
+
+/// Tells whether an existing client can be used with a changed api.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Existing clients will continue to work
+    Compatible,
+    /// Existing clients might fail
+    Breaking,
+}
+
+/// All kinds of changes [`ApiDescriptor::diff`] is able to detect.
+///
+/// Changes to captions and descriptions are not considered.
+#[derive(Clone, Debug)]
+pub enum ChangeKind {
+    /// The technical name of the api has changed
+    ApiRenamed { old: Identifier, new: Identifier },
+    /// A new function has been added
+    FunctionAdded,
+    /// An existing function has been removed
+    FunctionRemoved,
+    /// A new event has been added
+    EventAdded,
+    /// An existing event has been removed
+    EventRemoved,
+    /// A new parameter has been appended
+    ParameterAdded { name: Identifier },
+    /// An existing parameter has been removed
+    ParameterRemoved { name: Identifier },
+    /// The parameter at this position has a different name now
+    ParameterRenamed { old: Identifier, new: Identifier },
+    /// The set of valid values has changed
+    TypeChanged {
+        old: TypeDescriptor,
+        new: TypeDescriptor,
+    },
+    /// The parameter may be omitted now
+    DefaultAdded(Value),
+    /// The parameter must be given now
+    DefaultRemoved(Value),
+    /// Omitting the parameter results in a different value
+    DefaultChanged { old: Value, new: Value },
+    /// The function returns a value now
+    ReturnValueAdded,
+    /// The function no longer returns a value
+    ReturnValueRemoved,
+}
+
+impl Display for Change {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} change at {}: {}",
+            self.compatibility, self.path, self.kind
+        )
+    }
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Compatible => write!(formatter, "compatible"),
+            Self::Breaking => write!(formatter, "breaking"),
+        }
+    }
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ApiRenamed { old, new } => {
+                write!(formatter, "api renamed from {:?} to {:?}", old.0, new.0)
+            }
+            Self::FunctionAdded => write!(formatter, "function added"),
+            Self::FunctionRemoved => write!(formatter, "function removed"),
+            Self::EventAdded => write!(formatter, "event added"),
+            Self::EventRemoved => write!(formatter, "event removed"),
+            Self::ParameterAdded { name } => write!(formatter, "parameter {:?} added", name.0),
+            Self::ParameterRemoved { name } => {
+                write!(formatter, "parameter {:?} removed", name.0)
+            }
+            Self::ParameterRenamed { old, new } => {
+                write!(
+                    formatter,
+                    "parameter renamed from {:?} to {:?}",
+                    old.0, new.0
+                )
+            }
+            Self::TypeChanged { old, new } => {
+                write!(formatter, "type changed from {old:?} to {new:?}")
+            }
+            Self::DefaultAdded(default) => write!(formatter, "default value {default:?} added"),
+            Self::DefaultRemoved(default) => {
+                write!(formatter, "default value {default:?} removed")
+            }
+            Self::DefaultChanged { old, new } => {
+                write!(formatter, "default value changed from {old:?} to {new:?}")
+            }
+            Self::ReturnValueAdded => write!(formatter, "return value added"),
+            Self::ReturnValueRemoved => write!(formatter, "return value removed"),
+        }
+    }
+}
+
+/// The direction values of a parameter are being sent.
+///
+/// Widening the type of a parameter is fine if the client sends it, but might break the client
+/// if it receives it.
+#[derive(Clone, Copy)]
+enum Flow {
+    ToServer,
+    ToClient,
+}
+
+pub(super) fn diff(old: &ApiDescriptor, new: &ApiDescriptor) -> Vec<Change> {
+    let mut differ = Differ::default();
+
+    if old.name != new.name {
+        differ.report(
+            "$.name",
+            ChangeKind::ApiRenamed {
+                old: old.name.clone(),
+                new: new.name.clone(),
+            },
+            Compatibility::Breaking,
+        );
+    }
+
+    for (key, old_function) in &old.functions {
+        let path = format!("$.functions[{:?}]", key.0);
+        match new.functions.get(key) {
+            Some(new_function) => differ.function(&path, old_function, new_function),
+            None => differ.report(path, ChangeKind::FunctionRemoved, Compatibility::Breaking),
+        }
+    }
+    for key in new.functions.keys() {
+        if !old.functions.contains_key(key) {
+            let path = format!("$.functions[{:?}]", key.0);
+            differ.report(path, ChangeKind::FunctionAdded, Compatibility::Compatible);
+        }
+    }
+
+    for (key, old_event) in &old.events {
+        let path = format!("$.events[{:?}]", key.0);
+        match new.events.get(key) {
+            Some(new_event) => differ.event(&path, old_event, new_event),
+            None => differ.report(path, ChangeKind::EventRemoved, Compatibility::Breaking),
+        }
+    }
+    for key in new.events.keys() {
+        if !old.events.contains_key(key) {
+            let path = format!("$.events[{:?}]", key.0);
+            differ.report(path, ChangeKind::EventAdded, Compatibility::Compatible);
+        }
+    }
+
+    differ.changes
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn report(&mut self, path: impl Into<String>, kind: ChangeKind, compatibility: Compatibility) {
+        self.changes.push(Change {
+            path: path.into(),
+            kind,
+            compatibility,
+        });
+    }
+
+    fn function(&mut self, path: &str, old: &FunctionDescriptor, new: &FunctionDescriptor) {
+        self.parameters(path, &old.parameters, &new.parameters, Flow::ToServer);
+
+        let path = format!("{path}.returns");
+        match (&old.returns, &new.returns) {
+            (Some(old), Some(new)) => self.parameter(&path, old, new, Flow::ToClient),
+            (None, Some(_)) => {
+                self.report(
+                    path,
+                    ChangeKind::ReturnValueAdded,
+                    Compatibility::Compatible,
+                );
+            }
+            (Some(_), None) => {
+                self.report(
+                    path,
+                    ChangeKind::ReturnValueRemoved,
+                    Compatibility::Breaking,
+                );
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn event(&mut self, path: &str, old: &EventDescriptor, new: &EventDescriptor) {
+        self.parameters(path, &old.parameters, &new.parameters, Flow::ToClient);
+    }
+
+    /// Parameters are being compared by their position as this is how they're being transmitted.
+    fn parameters(
+        &mut self,
+        path: &str,
+        old: &[ParameterDescriptor],
+        new: &[ParameterDescriptor],
+        flow: Flow,
+    ) {
+        for (index, old_parameter) in old.iter().enumerate() {
+            let path = format!("{path}.parameters[{index}]");
+            match new.get(index) {
+                Some(new_parameter) => self.parameter(&path, old_parameter, new_parameter, flow),
+                None => self.report(
+                    path,
+                    ChangeKind::ParameterRemoved {
+                        name: old_parameter.name.clone(),
+                    },
+                    Compatibility::Breaking,
+                ),
+            }
+        }
+
+        for (index, new_parameter) in new.iter().enumerate().skip(old.len()) {
+            // existing clients won't send it, so the server needs to fill in the default
+            let compatibility = match flow {
+                Flow::ToServer if new_parameter.default.is_some() => Compatibility::Compatible,
+                Flow::ToServer | Flow::ToClient => Compatibility::Breaking,
+            };
+            self.report(
+                format!("{path}.parameters[{index}]"),
+                ChangeKind::ParameterAdded {
+                    name: new_parameter.name.clone(),
+                },
+                compatibility,
+            );
+        }
+    }
+
+    fn parameter(
+        &mut self,
+        path: &str,
+        old: &ParameterDescriptor,
+        new: &ParameterDescriptor,
+        flow: Flow,
+    ) {
+        if old.name != new.name {
+            self.report(
+                format!("{path}.name"),
+                ChangeKind::ParameterRenamed {
+                    old: old.name.clone(),
+                    new: new.name.clone(),
+                },
+                Compatibility::Breaking,
+            );
+        }
+
+        if old.typ != new.typ {
+            let is_compatible = match flow {
+                Flow::ToServer => is_subset(&old.typ, &new.typ),
+                Flow::ToClient => is_subset(&new.typ, &old.typ),
+            };
+            self.report(
+                format!("{path}.type"),
+                ChangeKind::TypeChanged {
+                    old: old.typ.clone(),
+                    new: new.typ.clone(),
+                },
+                if is_compatible {
+                    Compatibility::Compatible
+                } else {
+                    Compatibility::Breaking
+                },
+            );
+        }
+
+        let path = format!("{path}.default");
+        match (&old.default, &new.default) {
+            (None, Some(new)) => self.report(
+                path,
+                ChangeKind::DefaultAdded(new.clone()),
+                Compatibility::Compatible,
+            ),
+            (Some(old), None) => self.report(
+                path,
+                ChangeKind::DefaultRemoved(old.clone()),
+                Compatibility::Breaking,
+            ),
+            (Some(old), Some(new)) if old != new => self.report(
+                path,
+                ChangeKind::DefaultChanged {
+                    old: old.clone(),
+                    new: new.clone(),
+                },
+                Compatibility::Compatible,
+            ),
+            (None, None) | (Some(_), Some(_)) => {}
+        }
+    }
+}
+
+/// Returns whether every value of the `narrow` type is also accepted by the `wide` type.
+fn is_subset(narrow: &TypeDescriptor, wide: &TypeDescriptor) -> bool {
+    match (narrow, wide) {
+        (TypeDescriptor::Integer(narrow), TypeDescriptor::Integer(wide)) => {
+            narrow.is_empty() || (wide.start <= narrow.start && narrow.end <= wide.end)
+        }
+        (TypeDescriptor::Float, TypeDescriptor::Float)
+        | (TypeDescriptor::Boolean, TypeDescriptor::Boolean)
+        | (TypeDescriptor::String, TypeDescriptor::String) => true,
+        (TypeDescriptor::List(narrow), TypeDescriptor::List(wide))
+        | (TypeDescriptor::Optional(narrow), TypeDescriptor::Optional(wide)) => {
+            is_subset(narrow, wide)
+        }
+        (TypeDescriptor::Tuple(narrow), TypeDescriptor::Tuple(wide)) => {
+            narrow.len() == wide.len()
+                && narrow
+                    .iter()
+                    .zip(wide)
+                    .all(|(narrow, wide)| is_subset(narrow, wide))
+        }
+        (TypeDescriptor::Record(narrow), TypeDescriptor::Record(wide)) => {
+            narrow.len() == wide.len()
+                && narrow.iter().all(|(name, narrow)| {
+                    wide.get(name).is_some_and(|wide| is_subset(narrow, wide))
+                })
+        }
+        (TypeDescriptor::Enumeration(narrow), TypeDescriptor::Enumeration(wide)) => {
+            narrow.iter().all(|variant| wide.contains(variant))
+        }
+        (narrow, TypeDescriptor::Optional(wide)) => is_subset(narrow, wide),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::indexing_slicing,
+    reason = "the descriptors are modified by indexing into them"
+)]
+mod tests {
+    use super::{Change, ChangeKind, Compatibility};
+    use crate::api::{ApiDescriptor, Value};
+    use serde_json::json;
+
+    fn api(functions: &serde_json::Value, events: &serde_json::Value) -> ApiDescriptor {
+        serde_json::from_value(json!({
+            "name": "test",
+            "caption": "a test",
+            "description": "a test",
+            "functions": functions,
+            "events": events,
+        }))
+        .unwrap()
+    }
+
+    /// Returns a map containing a single function or event called `test`.
+    fn test(parameters: &serde_json::Value) -> serde_json::Value {
+        json!({ "test": {
+            "name": "test",
+            "caption": "test",
+            "description": "test",
+            "parameters": parameters,
+        }})
+    }
+
+    fn parameter(name: &str, typ: &serde_json::Value) -> serde_json::Value {
+        json!({ "name": name, "caption": name, "description": name, "type": typ })
+    }
+
+    fn integer(start: i64, end: i64) -> serde_json::Value {
+        json!({ "Integer": { "start": start, "end": end } })
+    }
+
+    /// Returns the only change between two revisions of the given functions.
+    fn single_change(old: &serde_json::Value, new: &serde_json::Value) -> Change {
+        let old = api(old, &json!({}));
+        let new = api(new, &json!({}));
+        let mut changes = old.diff(&new);
+        assert_eq!(changes.len(), 1, "{changes:?}");
+        changes.remove(0)
+    }
+
+    /// Checks the only change between two revisions of the given functions and returns its kind.
+    fn assert_change(
+        old: &serde_json::Value,
+        new: &serde_json::Value,
+        path: &str,
+        compatibility: Compatibility,
+    ) -> ChangeKind {
+        let change = single_change(old, new);
+        assert_eq!(change.path, path);
+        assert_eq!(change.compatibility, compatibility, "{}", change.kind);
+        change.kind
+    }
+
+    #[test]
+    fn unchanged_api_has_no_changes() {
+        let json = include_bytes!("../../../../applications/robot/control.api.json");
+        let api: ApiDescriptor = serde_json::from_slice(json).unwrap();
+        assert!(api.diff(&api).is_empty());
+    }
+
+    #[test]
+    fn adding_functions_is_compatible_removing_them_is_breaking() {
+        let path = r#"$.functions["test"]"#;
+        assert!(matches!(
+            assert_change(
+                &json!({}),
+                &test(&json!([])),
+                path,
+                Compatibility::Compatible
+            ),
+            ChangeKind::FunctionAdded
+        ));
+        assert!(matches!(
+            assert_change(&test(&json!([])), &json!({}), path, Compatibility::Breaking),
+            ChangeKind::FunctionRemoved
+        ));
+    }
+
+    #[test]
+    fn appended_parameters_need_a_default() {
+        let old = test(&json!([]));
+        let mut added = parameter("x", &json!("Float"));
+        let path = r#"$.functions["test"].parameters[0]"#;
+
+        let new = test(&json!([added]));
+        assert!(matches!(
+            assert_change(&old, &new, path, Compatibility::Breaking),
+            ChangeKind::ParameterAdded { .. }
+        ));
+
+        added["default"] = json!({ "Float": 1.0 });
+        let defaulted = test(&json!([added]));
+        assert_change(&old, &defaulted, path, Compatibility::Compatible);
+    }
+
+    #[test]
+    fn renaming_and_removing_parameters_is_breaking() {
+        let old = test(&json!([parameter("x", &json!("Float"))]));
+        let renamed = test(&json!([parameter("y", &json!("Float"))]));
+        let removed = test(&json!([]));
+
+        assert!(matches!(
+            assert_change(
+                &old,
+                &renamed,
+                r#"$.functions["test"].parameters[0].name"#,
+                Compatibility::Breaking
+            ),
+            ChangeKind::ParameterRenamed { .. }
+        ));
+        assert!(matches!(
+            assert_change(
+                &old,
+                &removed,
+                r#"$.functions["test"].parameters[0]"#,
+                Compatibility::Breaking
+            ),
+            ChangeKind::ParameterRemoved { .. }
+        ));
+    }
+
+    #[test]
+    fn parameters_may_be_widened_but_not_narrowed() {
+        let narrow = test(&json!([parameter("x", &integer(0, 10))]));
+        let wide = test(&json!([parameter("x", &integer(-10, 20))]));
+        let optional = test(&json!([parameter(
+            "x",
+            &json!({ "Optional": integer(0, 10) })
+        )]));
+        let path = r#"$.functions["test"].parameters[0].type"#;
+
+        assert!(matches!(
+            assert_change(&narrow, &wide, path, Compatibility::Compatible),
+            ChangeKind::TypeChanged { .. }
+        ));
+        assert_change(&wide, &narrow, path, Compatibility::Breaking);
+        assert_change(&narrow, &optional, path, Compatibility::Compatible);
+        assert_change(&optional, &narrow, path, Compatibility::Breaking);
+    }
+
+    #[test]
+    fn return_values_may_be_narrowed_but_not_widened() {
+        let returning = |typ: &serde_json::Value| {
+            let mut returning = test(&json!([]));
+            returning["test"]["returns"] = parameter("result", typ);
+            returning
+        };
+        let nothing = test(&json!([]));
+        let narrow = returning(&integer(0, 10));
+        let wide = returning(&json!({ "Optional": integer(0, 10) }));
+        let path = r#"$.functions["test"].returns"#;
+        let type_path = r#"$.functions["test"].returns.type"#;
+
+        assert_change(&narrow, &wide, type_path, Compatibility::Breaking);
+        assert_change(&wide, &narrow, type_path, Compatibility::Compatible);
+        assert!(matches!(
+            assert_change(&nothing, &narrow, path, Compatibility::Compatible),
+            ChangeKind::ReturnValueAdded
+        ));
+        assert!(matches!(
+            assert_change(&narrow, &nothing, path, Compatibility::Breaking),
+            ChangeKind::ReturnValueRemoved
+        ));
+    }
+
+    #[test]
+    fn enumerations_may_gain_variants() {
+        let few = test(&json!([parameter("x", &json!({ "Enumeration": ["a"] }))]));
+        let many = test(&json!([parameter(
+            "x",
+            &json!({ "Enumeration": ["a", "b"] })
+        )]));
+        let path = r#"$.functions["test"].parameters[0].type"#;
+
+        assert_change(&few, &many, path, Compatibility::Compatible);
+        assert_change(&many, &few, path, Compatibility::Breaking);
+    }
+
+    #[test]
+    fn only_removing_defaults_is_breaking() {
+        let with_default = |default: Option<f64>| {
+            let mut x = parameter("x", &json!("Float"));
+            if let Some(default) = default {
+                x["default"] = json!({ "Float": default });
+            }
+            test(&json!([x]))
+        };
+        let none = with_default(None);
+        let one = with_default(Some(1.0));
+        let two = with_default(Some(2.0));
+        let path = r#"$.functions["test"].parameters[0].default"#;
+
+        assert!(matches!(
+            assert_change(&none, &one, path, Compatibility::Compatible),
+            ChangeKind::DefaultAdded(Value::Float(_))
+        ));
+        assert!(matches!(
+            assert_change(&one, &two, path, Compatibility::Compatible),
+            ChangeKind::DefaultChanged { .. }
+        ));
+        assert!(matches!(
+            assert_change(&one, &none, path, Compatibility::Breaking),
+            ChangeKind::DefaultRemoved(_)
+        ));
+    }
+
+    #[test]
+    fn events_must_keep_their_parameters() {
+        let old = api(&json!({}), &test(&json!([])));
+        let extended = api(&json!({}), &test(&json!([parameter("x", &json!("Float"))])));
+        let removed = api(&json!({}), &json!({}));
+
+        let additions = old.diff(&extended);
+        assert_eq!(additions.len(), 1, "{additions:?}");
+        assert_eq!(additions[0].path, r#"$.events["test"].parameters[0]"#);
+        // existing clients do not expect the additional value
+        assert_eq!(additions[0].compatibility, Compatibility::Breaking);
+
+        let removals = old.diff(&removed);
+        assert_eq!(removals.len(), 1, "{removals:?}");
+        assert!(matches!(removals[0].kind, ChangeKind::EventRemoved));
+        assert_eq!(removals[0].compatibility, Compatibility::Breaking);
+    }
+
+    #[test]
+    fn renaming_the_api_is_breaking() {
+        let old = api(&json!({}), &json!({}));
+        let mut new = old.clone();
+        new.name.0 = "other".into();
+
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1, "{changes:?}");
+        assert_eq!(changes[0].path, "$.name");
+        assert!(matches!(changes[0].kind, ChangeKind::ApiRenamed { .. }));
+        assert_eq!(changes[0].compatibility, Compatibility::Breaking);
+    }
+}
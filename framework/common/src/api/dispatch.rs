@@ -0,0 +1,96 @@
+//! Support for turning requests into calls of native Rust functions.
+//!
+//! The dispatchers generated by `#[gam3du_framework_macros::api]` are built upon these helpers.
+
+use super::{ApiValue, ConversionError, Identifier, Value, check_range};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display},
+    ops::Range,
+};
+
+/// Reasons why a request could not be turned into a function call.
+#[derive(Clone, Debug)]
+pub enum DispatchError {
+    /// The api does not provide a function with this name
+    UnknownFunction(Identifier),
+    /// The number of arguments does not match the number of parameters
+    WrongArgumentCount {
+        function: Identifier,
+        expected: usize,
+        actual: usize,
+    },
+    /// An argument could not be converted into the parameter's type
+    InvalidArgument {
+        parameter: Identifier,
+        error: ConversionError,
+    },
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFunction(function) => write!(formatter, "unknown function `{function}`"),
+            Self::WrongArgumentCount {
+                function,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "function `{function}` takes {expected} arguments but {actual} were given"
+            ),
+            Self::InvalidArgument { parameter, error } => {
+                write!(formatter, "invalid argument `{parameter}`: {error}")
+            }
+        }
+    }
+}
+
+impl Error for DispatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidArgument { error, .. } => Some(error),
+            Self::UnknownFunction(_) | Self::WrongArgumentCount { .. } => None,
+        }
+    }
+}
+
+/// Makes sure that exactly `N` arguments have been passed to a function.
+///
+/// # Errors
+///
+/// Returns [`DispatchError::WrongArgumentCount`] if the number of arguments doesn't match.
+pub fn arguments<const N: usize>(
+    function: &'static str,
+    arguments: Vec<Value>,
+) -> Result<[Value; N], DispatchError> {
+    <[Value; N]>::try_from(arguments).map_err(|arguments| DispatchError::WrongArgumentCount {
+        function: Identifier(Cow::Borrowed(function)),
+        expected: N,
+        actual: arguments.len(),
+    })
+}
+
+/// Converts a single argument into the parameter's type, optionally limiting its range.
+///
+/// # Errors
+///
+/// Returns [`DispatchError::InvalidArgument`] if the argument cannot be converted.
+pub fn argument<T: ApiValue>(
+    parameter: &'static str,
+    range: Option<Range<i64>>,
+    argument: Value,
+) -> Result<T, DispatchError> {
+    let convert = || {
+        if let (Some(range), &Value::Integer(value)) = (range, &argument) {
+            check_range(range, value)?;
+        }
+        T::try_from_value(argument)
+    };
+
+    convert().map_err(|error| DispatchError::InvalidArgument {
+        parameter: Identifier(Cow::Borrowed(parameter)),
+        error,
+    })
+}
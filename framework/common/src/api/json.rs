@@ -0,0 +1,370 @@
+//! Conversions between [`Value`]s and plain JSON as used by web clients.
+//!
+//! Unlike the serialization derived by `serde`, values are not tagged with their type;
+//! e.g. `Value::Integer(500)` becomes `500` and `Value::Optional(None)` becomes `null`.
+//! The [`TypeDescriptor`] of a parameter determines how a JSON value is to be interpreted.
+//!
+//! Each problem will be reported with a JSON path pointing to the offending element,
+//! e.g. `$["duration"]`.
+
+use super::{FunctionDescriptor, Identifier, TypeDescriptor, Value};
+use indexmap::IndexMap as HashMap;
+use serde_json::{Map, Number};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    ops::Range,
+};
+
+/// A JSON value that could not be converted into a [`Value`].
+#[derive(Clone, Debug)]
+pub struct JsonError {
+    /// JSON path to the offending element
+    pub path: String,
+    /// What is wrong with the element
+    pub kind: JsonErrorKind,
+}
+
+/// All kinds of problems found while converting JSON into [`Value`]s.
+#[derive(Clone, Debug)]
+pub enum JsonErrorKind {
+    /// The JSON value cannot represent a value of the expected type
+    TypeMismatch {
+        expected: Box<TypeDescriptor>,
+        json: serde_json::Value,
+    },
+    /// The integer is valid in general, but outside of the expected range
+    OutOfRange { range: Range<i64>, value: i64 },
+    /// The string does not name any variant of the enumeration
+    UnknownVariant(String),
+    /// A tuple has been given with the wrong number of elements
+    WrongLength { expected: usize, actual: usize },
+    /// A field of a record or an argument without default value is missing
+    MissingField(Identifier),
+    /// The object contains a field the record or function does not know about
+    UnknownField(String),
+}
+
+impl Display for JsonError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}: {}", self.path, self.kind)
+    }
+}
+
+impl Display for JsonErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, json } => {
+                write!(formatter, "expected a value of {expected:?} but got {json}")
+            }
+            Self::OutOfRange { range, value } => {
+                write!(formatter, "value {value} is out of range {range:?}")
+            }
+            Self::UnknownVariant(variant) => write!(formatter, "unknown variant {variant:?}"),
+            Self::WrongLength { expected, actual } => {
+                write!(formatter, "expected {expected} elements but got {actual}")
+            }
+            Self::MissingField(name) => write!(formatter, "missing field {:?}", name.0),
+            Self::UnknownField(name) => write!(formatter, "unknown field {name:?}"),
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+/// Converts a value into plain JSON.
+pub(super) fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Unit | Value::Optional(None) => serde_json::Value::Null,
+        &Value::Integer(value) => value.into(),
+        &Value::Float(value) => Number::from_f64(value.into())
+            .map_or(serde_json::Value::Null, serde_json::Value::Number),
+        &Value::Boolean(value) => value.into(),
+        Value::String(value) => value.clone().into(),
+        Value::List(elements) | Value::Tuple(elements) => {
+            elements.iter().map(value_to_json).collect()
+        }
+        Value::Record(fields) => fields
+            .iter()
+            .map(|(name, field)| (name.0.clone().into_owned(), value_to_json(field)))
+            .collect::<Map<_, _>>()
+            .into(),
+        Value::Enumeration(variant) => variant.0.clone().into_owned().into(),
+        Value::Optional(Some(inner)) => value_to_json(inner),
+    }
+}
+
+/// Converts a JSON object mapping parameter names to plain JSON values into a list of arguments.
+pub(super) fn arguments_from_json(
+    function: &FunctionDescriptor,
+    json: &Map<String, serde_json::Value>,
+) -> Result<Vec<Value>, JsonError> {
+    if let Some(unknown) = json.keys().find(|&name| {
+        !function
+            .parameters
+            .iter()
+            .any(|parameter| parameter.name.0 == *name)
+    }) {
+        return Err(JsonError {
+            path: format!("$[{unknown:?}]"),
+            kind: JsonErrorKind::UnknownField(unknown.clone()),
+        });
+    }
+
+    function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let path = format!("$[{:?}]", parameter.name.0);
+            match (json.get(parameter.name.as_ref()), &parameter.default) {
+                (Some(argument), _) => value_from_json(&parameter.typ, argument, &path),
+                (None, Some(default)) => Ok(default.clone()),
+                (None, None) => Err(JsonError {
+                    path: "$".to_owned(),
+                    kind: JsonErrorKind::MissingField(parameter.name.clone()),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Interprets a plain JSON value as a member of the given type.
+pub(super) fn value_from_json(
+    typ: &TypeDescriptor,
+    json: &serde_json::Value,
+    path: &str,
+) -> Result<Value, JsonError> {
+    let error = |kind| JsonError {
+        path: path.to_owned(),
+        kind,
+    };
+    let mismatch = || {
+        error(JsonErrorKind::TypeMismatch {
+            expected: Box::new(typ.clone()),
+            json: json.clone(),
+        })
+    };
+
+    match (typ, json) {
+        (TypeDescriptor::Integer(range), serde_json::Value::Number(number)) => {
+            let value = number.as_i64().ok_or_else(mismatch)?;
+            if !range.contains(&value) {
+                return Err(error(JsonErrorKind::OutOfRange {
+                    range: range.clone(),
+                    value,
+                }));
+            }
+            Ok(Value::Integer(value))
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "floats are transmitted with single precision"
+        )]
+        (TypeDescriptor::Float, serde_json::Value::Number(number)) => {
+            let value = number.as_f64().ok_or_else(mismatch)?;
+            Ok(Value::Float(value as f32))
+        }
+        (TypeDescriptor::Boolean, &serde_json::Value::Bool(value)) => Ok(Value::Boolean(value)),
+        (TypeDescriptor::String, serde_json::Value::String(value)) => {
+            Ok(Value::String(value.clone()))
+        }
+        (TypeDescriptor::List(element_type), serde_json::Value::Array(elements)) => elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| {
+                value_from_json(element_type, element, &format!("{path}[{index}]"))
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::List),
+        (TypeDescriptor::Tuple(element_types), serde_json::Value::Array(elements)) => {
+            if element_types.len() != elements.len() {
+                return Err(error(JsonErrorKind::WrongLength {
+                    expected: element_types.len(),
+                    actual: elements.len(),
+                }));
+            }
+            element_types
+                .iter()
+                .zip(elements)
+                .enumerate()
+                .map(|(index, (element_type, element))| {
+                    value_from_json(element_type, element, &format!("{path}[{index}]"))
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Tuple)
+        }
+        (TypeDescriptor::Record(field_types), serde_json::Value::Object(fields)) => {
+            if let Some(unknown) = fields
+                .keys()
+                .find(|&name| !field_types.keys().any(|field| field.0 == *name))
+            {
+                return Err(error(JsonErrorKind::UnknownField(unknown.clone())));
+            }
+            field_types
+                .iter()
+                .map(|(name, field_type)| {
+                    let field = fields
+                        .get(name.as_ref())
+                        .ok_or_else(|| error(JsonErrorKind::MissingField(name.clone())))?;
+                    let value =
+                        value_from_json(field_type, field, &format!("{path}[{:?}]", name.0))?;
+                    Ok((name.clone(), value))
+                })
+                .collect::<Result<HashMap<_, _>, _>>()
+                .map(Value::Record)
+        }
+        (TypeDescriptor::Enumeration(variants), serde_json::Value::String(variant)) => variants
+            .iter()
+            .find(|known| known.0 == *variant)
+            .map(|known| Value::Enumeration(known.clone()))
+            .ok_or_else(|| error(JsonErrorKind::UnknownVariant(variant.clone()))),
+        (TypeDescriptor::Optional(_), serde_json::Value::Null) => Ok(Value::Optional(None)),
+        (TypeDescriptor::Optional(inner_type), _) => {
+            let inner = value_from_json(inner_type, json, path)?;
+            Ok(Value::Optional(Some(Box::new(inner))))
+        }
+        _ => Err(mismatch()),
+    }
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::indexing_slicing,
+    reason = "the arguments are modified by indexing into them"
+)]
+mod tests {
+    use super::{FunctionDescriptor, Identifier, Value, value_to_json};
+    use serde_json::{Map, json};
+
+    fn function() -> FunctionDescriptor {
+        let parameter = |name: &str, typ, default| {
+            json!({
+                "name": name,
+                "caption": "",
+                "description": "",
+                "type": typ,
+                "default": default,
+            })
+        };
+        let path = json!({ "Optional": { "List": { "Tuple": ["Float", "Boolean"] } } });
+        let target = json!({ "Record": { "x": { "Integer": [0, 10] } } });
+        serde_json::from_value(json!({
+            "name": "drive",
+            "caption": "",
+            "description": "",
+            "parameters": [
+                parameter("duration", json!({ "Integer": [0, 10_000] }), json!({ "Integer": 500 })),
+                parameter("direction", json!({ "Enumeration": ["left", "right"] }), json!(null)),
+                parameter("path", path, json!(null)),
+                parameter("target", target, json!(null)),
+            ],
+            "returns": null,
+        }))
+        .unwrap()
+    }
+
+    fn arguments(json: &serde_json::Value) -> Map<String, serde_json::Value> {
+        json.as_object().unwrap().clone()
+    }
+
+    /// Converts the arguments expecting them to be rejected.
+    fn error(json: &serde_json::Value) -> String {
+        function()
+            .arguments_from_json(&arguments(json))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn arguments_are_ordered_like_the_parameters() {
+        let json = json!({
+            "target": { "x": 3 },
+            "path": [[1.5, true]],
+            "direction": "left",
+        });
+        let expected = [
+            Value::Integer(500),
+            Value::Enumeration(Identifier("left".into())),
+            Value::Optional(Some(Box::new(Value::List(vec![Value::Tuple(vec![
+                Value::Float(1.5),
+                Value::Boolean(true),
+            ])])))),
+            Value::Record(
+                [(Identifier("x".into()), Value::Integer(3))]
+                    .into_iter()
+                    .collect(),
+            ),
+        ];
+        let converted = function().arguments_from_json(&arguments(&json)).unwrap();
+        assert_eq!(converted, expected);
+        assert_eq!(
+            converted.iter().map(value_to_json).collect::<Vec<_>>(),
+            [
+                json!(500),
+                json!("left"),
+                json!([[1.5, true]]),
+                json!({ "x": 3 })
+            ]
+        );
+    }
+
+    #[test]
+    fn null_is_none() {
+        let json =
+            json!({ "duration": 0, "direction": "right", "path": null, "target": { "x": 0 } });
+        let converted = function().arguments_from_json(&arguments(&json)).unwrap();
+        assert_eq!(converted.get(2), Some(&Value::Optional(None)));
+    }
+
+    #[test]
+    fn mismatching_arguments_are_located() {
+        let valid = json!({ "direction": "left", "path": null, "target": { "x": 3 } });
+        let with = |name: &str, argument: serde_json::Value| {
+            let mut json = valid.clone();
+            json[name] = argument;
+            json
+        };
+        let without = |name: &str| {
+            let mut json = valid.clone();
+            json.as_object_mut().unwrap().remove(name);
+            json
+        };
+
+        assert_eq!(
+            error(&with("speed", json!(1))),
+            r#"$["speed"]: unknown field "speed""#
+        );
+        assert_eq!(
+            error(&without("direction")),
+            r#"$: missing field "direction""#
+        );
+        assert_eq!(
+            error(&with("direction", json!("up"))),
+            r#"$["direction"]: unknown variant "up""#
+        );
+        assert_eq!(
+            error(&with("duration", json!(10_000))),
+            r#"$["duration"]: value 10000 is out of range 0..10000"#
+        );
+        assert_eq!(
+            error(&with("duration", json!(1.5))),
+            r#"$["duration"]: expected a value of Integer(0..10000) but got 1.5"#
+        );
+        assert_eq!(
+            error(&with("path", json!([[1.5, true], [2.5]]))),
+            r#"$["path"][1]: expected 2 elements but got 1"#
+        );
+        assert_eq!(
+            error(&with("target", json!({}))),
+            r#"$["target"]: missing field "x""#
+        );
+        assert_eq!(
+            error(&with("target", json!({ "x": 3, "y": 4 }))),
+            r#"$["target"]: unknown field "y""#
+        );
+        assert_eq!(
+            error(&with("target", json!({ "x": "3" }))),
+            r#"$["target"]["x"]: expected a value of Integer(0..10) but got "3""#
+        );
+    }
+}
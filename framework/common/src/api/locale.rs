@@ -0,0 +1,318 @@
+//! Translations of api descriptors into other languages.
+//!
+//! Every named element of an api may carry a [`Localization`] per [`Locale`] providing an alias
+//! and translated texts. Clients in that language still send the canonical names over the wire;
+//! aliases only exist within the generated front-ends.
+
+use super::{
+    ApiDescriptor, EventDescriptor, FunctionDescriptor, Identifier, ParameterDescriptor, RichText,
+};
+use indexmap::IndexMap as HashMap;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt::Display};
+
+/// A language tag like `de` or `de-CH`.
+///
+/// The language consists of two or three lowercase letters and may be followed by an uppercase
+/// region of two letters.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Locale(pub Cow<'static, str>);
+
+impl Locale {
+    /// Returns whether this is a well-formed language tag.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let (language, region) = match self.0.split_once('-') {
+            Some((language, region)) => (language, Some(region)),
+            None => (self.0.as_ref(), None),
+        };
+        (2..=3).contains(&language.len())
+            && language
+                .chars()
+                .all(|character| character.is_ascii_lowercase())
+            && region.is_none_or(|region| {
+                region.len() == 2
+                    && region
+                        .chars()
+                        .all(|character| character.is_ascii_uppercase())
+            })
+    }
+
+    /// The language without the region
+    #[must_use]
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, formatter)
+    }
+}
+
+/// The name and texts of an element in another language.
+///
+/// Everything missing falls back to the canonical name and texts.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Localization {
+    /// alternative technical name in this language (e.g. "gehe vorwärts" for "move forward")
+    #[serde(default)]
+    pub alias: Option<Identifier>,
+    /// translation of the caption
+    #[serde(default)]
+    pub caption: Option<RichText>,
+    /// translation of the description
+    #[serde(default)]
+    pub description: Option<RichText>,
+}
+
+/// The name and texts of an element in a single language
+#[derive(Clone, Copy, Debug)]
+pub struct Texts<'element> {
+    /// either the alias or the canonical name
+    pub name: &'element Identifier,
+    /// a single-line explanation
+    pub caption: &'element RichText,
+    /// a multi-line explanation
+    pub description: &'element RichText,
+}
+
+/// An element of an api which may have been translated into other languages.
+pub trait Localized {
+    /// The canonical name and texts
+    fn texts(&self) -> Texts<'_>;
+
+    /// All translations of this element
+    fn localizations(&self) -> &HashMap<Locale, Localization>;
+
+    /// Returns the name and texts for the given locale falling back to the canonical ones.
+    ///
+    /// A locale with a region (e.g. `de-CH`) falls back to its language (`de`) first. A missing
+    /// description falls back to the translated caption if the canonical one merely repeats it.
+    fn localized(&self, locale: Option<&Locale>) -> Texts<'_> {
+        let canonical = self.texts();
+        let Some(locale) = locale else {
+            return canonical;
+        };
+        let localizations = self.localizations();
+        let language = Locale(Cow::Owned(locale.language().to_owned()));
+        [localizations.get(locale), localizations.get(&language)]
+            .into_iter()
+            .flatten()
+            .rev()
+            .fold(canonical, |texts, localization| {
+                let caption = localization.caption.as_ref().unwrap_or(texts.caption);
+                // a description merely repeating the caption follows its translation
+                let description = localization.description.as_ref().unwrap_or(
+                    if texts.description.0 == texts.caption.0 {
+                        caption
+                    } else {
+                        texts.description
+                    },
+                );
+                Texts {
+                    name: localization.alias.as_ref().unwrap_or(texts.name),
+                    caption,
+                    description,
+                }
+            })
+    }
+}
+
+impl Localized for ApiDescriptor {
+    fn texts(&self) -> Texts<'_> {
+        Texts {
+            name: &self.name,
+            caption: &self.caption,
+            description: &self.description,
+        }
+    }
+
+    fn localizations(&self) -> &HashMap<Locale, Localization> {
+        &self.localizations
+    }
+}
+
+impl Localized for FunctionDescriptor {
+    fn texts(&self) -> Texts<'_> {
+        Texts {
+            name: &self.name,
+            caption: &self.caption,
+            description: &self.description,
+        }
+    }
+
+    fn localizations(&self) -> &HashMap<Locale, Localization> {
+        &self.localizations
+    }
+}
+
+impl Localized for EventDescriptor {
+    fn texts(&self) -> Texts<'_> {
+        Texts {
+            name: &self.name,
+            caption: &self.caption,
+            description: &self.description,
+        }
+    }
+
+    fn localizations(&self) -> &HashMap<Locale, Localization> {
+        &self.localizations
+    }
+}
+
+impl Localized for ParameterDescriptor {
+    fn texts(&self) -> Texts<'_> {
+        Texts {
+            name: &self.name,
+            caption: &self.caption,
+            description: &self.description,
+        }
+    }
+
+    fn localizations(&self) -> &HashMap<Locale, Localization> {
+        &self.localizations
+    }
+}
+
+pub(super) fn locales(api: &ApiDescriptor) -> Vec<&Locale> {
+    let functions = api.functions.values().flat_map(|function| {
+        let parameters = function.parameters.iter().chain(&function.returns);
+        function
+            .localizations
+            .keys()
+            .chain(parameters.flat_map(|parameter| parameter.localizations.keys()))
+    });
+    let events = api.events.values().flat_map(|event| {
+        event.localizations.keys().chain(
+            event
+                .parameters
+                .iter()
+                .flat_map(|parameter| parameter.localizations.keys()),
+        )
+    });
+    let mut locales = Vec::new();
+    for locale in api.localizations.keys().chain(functions).chain(events) {
+        if !locales.contains(&locale) {
+            locales.push(locale);
+        }
+    }
+    locales
+}
+
+/// Fixed phrases generators need to explain an api, translated into all supported languages.
+///
+/// Placeholders in curly braces will be replaced by the generators.
+#[derive(Clone, Copy, Debug)]
+pub struct Phrases {
+    /// heading of the list of functions
+    pub functions: &'static str,
+    /// heading of the list of events
+    pub events: &'static str,
+    /// `Api {name}, version {version}`
+    pub api_version: &'static str,
+    /// column header of parameter names
+    pub parameter: &'static str,
+    /// column header of parameter types
+    pub typ: &'static str,
+    /// column header of default values
+    pub default: &'static str,
+    /// column header of parameter explanations
+    pub description: &'static str,
+    /// shown instead of a default value
+    pub required: &'static str,
+    /// introduces the return value
+    pub returns: &'static str,
+    /// `integer from {start} to {end}`
+    pub integer: &'static str,
+    /// floating point numbers
+    pub float: &'static str,
+    /// `true` or `false`
+    pub boolean: &'static str,
+    /// strings
+    pub string: &'static str,
+    /// `list of {element}`
+    pub list: &'static str,
+    /// `tuple ({elements})`
+    pub tuple: &'static str,
+    /// `record { {fields} }`
+    pub record: &'static str,
+    /// `one of {variants}`
+    pub enumeration: &'static str,
+    /// `{inner} or nothing`
+    pub optional: &'static str,
+    /// `{parameter} must be between {start} and {end} but is {value}`
+    pub out_of_range: &'static str,
+    /// `{parameter} must be one of {variants} but is {value}`
+    pub unknown_variant: &'static str,
+    /// explains the parameter limiting the time to wait for a response
+    pub timeout: &'static str,
+}
+
+impl Phrases {
+    /// The phrases used if the language is not supported
+    pub const ENGLISH: Self = Self {
+        functions: "Functions",
+        events: "Events",
+        api_version: "Api {name}, version {version}",
+        parameter: "Parameter",
+        typ: "Type",
+        default: "Default",
+        description: "Description",
+        required: "required",
+        returns: "Returns",
+        integer: "integer from {start} to {end}",
+        float: "number",
+        boolean: "boolean",
+        string: "text",
+        list: "list of {element}",
+        tuple: "tuple ({elements})",
+        record: "record { {fields} }",
+        enumeration: "one of {variants}",
+        optional: "{inner} or nothing",
+        out_of_range: "{parameter} must be between {start} and {end} but is {value}",
+        unknown_variant: "{parameter} must be one of {variants} but is {value}",
+        timeout: "seconds to wait for the response; `None` uses the default timeout",
+    };
+
+    /// Deutsch
+    pub const GERMAN: Self = Self {
+        functions: "Funktionen",
+        events: "Ereignisse",
+        api_version: "Api {name}, Version {version}",
+        parameter: "Parameter",
+        typ: "Typ",
+        default: "Standardwert",
+        description: "Beschreibung",
+        required: "erforderlich",
+        returns: "Rückgabe",
+        integer: "Ganzzahl von {start} bis {end}",
+        float: "Zahl",
+        boolean: "Wahrheitswert",
+        string: "Text",
+        list: "Liste von {element}",
+        tuple: "Tupel ({elements})",
+        record: "Verbund { {fields} }",
+        enumeration: "eines von {variants}",
+        optional: "{inner} oder nichts",
+        out_of_range: "{parameter} muss zwischen {start} und {end} liegen, ist aber {value}",
+        unknown_variant: "{parameter} muss eines von {variants} sein, ist aber {value}",
+        timeout: "Sekunden, die auf die Antwort gewartet wird; `None` nutzt die Standardwartezeit",
+    };
+
+    /// Returns the phrases of the locale's language (or English if it is not supported).
+    #[must_use]
+    pub fn for_locale(locale: Option<&Locale>) -> &'static Self {
+        match locale.map(Locale::language) {
+            Some("de") => &Self::GERMAN,
+            _ => &Self::ENGLISH,
+        }
+    }
+}
@@ -0,0 +1,662 @@
+//! A small markup language for captions and descriptions.
+//!
+//! The markup is a strict subset of Markdown, so descriptors remain readable as plain JSON:
+//!
+//! - paragraphs are separated by blank lines; single line breaks are treated as spaces
+//! - lines starting with `- ` are items of a list; items continue on lines indented by two spaces
+//! - `*text*` emphasizes a text
+//! - `` `code` `` marks a code span; its content is taken verbatim
+//! - `[move forward]` refers to the function of that name (by its canonical name, even within
+//!   translations)
+//! - a backslash takes the next character literally (e.g. `\*` or `\[`)
+//!
+//! Each [`Document`] can be rendered as plain text, Markdown, Python docstring, HTML or for a
+//! terminal. Renderers receive a function mapping the names of referenced functions to their
+//! representation in the target language.
+
+use super::{Identifier, IdentifierError, RichText};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    iter::Peekable,
+    str::Chars,
+};
+
+/// Maps the name of a referenced function to its representation in the output.
+pub type LinkName<'names> = &'names dyn Fn(&Identifier) -> String;
+
+/// A parsed [`RichText`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    /// paragraphs and lists in order of appearance
+    pub blocks: Vec<Block>,
+}
+
+/// A paragraph or a list
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    /// continuous text
+    Paragraph(Vec<Inline>),
+    /// a bullet list; each item is a single paragraph
+    List(Vec<Vec<Inline>>),
+}
+
+/// A piece of text within a paragraph or list item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inline {
+    /// plain text
+    Text(String),
+    /// verbatim text like code or literal values
+    Code(String),
+    /// emphasized text; cannot contain further emphasis
+    Emphasis(Vec<Inline>),
+    /// a reference to a function of the same api
+    Link(Identifier),
+}
+
+/// Reasons why a [`RichText`] is not well-formed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MarkupError {
+    /// A code span, emphasis or link has not been closed
+    Unterminated {
+        /// the opening character
+        delimiter: char,
+        /// the text following the opening character
+        excerpt: String,
+    },
+    /// An emphasis or link without any content
+    Empty {
+        /// the opening character
+        delimiter: char,
+    },
+    /// The target of a link is not a valid identifier
+    InvalidLink {
+        /// the text between the brackets
+        target: String,
+        /// the first rule being violated
+        error: IdentifierError,
+    },
+    /// A backslash at the very end of a paragraph has nothing to escape
+    TrailingBackslash,
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unterminated { delimiter, excerpt } => {
+                write!(formatter, "unterminated {delimiter:?} before {excerpt:?}")
+            }
+            Self::Empty { delimiter } => write!(formatter, "empty {delimiter:?}"),
+            Self::InvalidLink { target, error } => {
+                write!(formatter, "invalid link target {target:?}: {error}")
+            }
+            Self::TrailingBackslash => write!(formatter, "backslash at the end of a paragraph"),
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+impl RichText {
+    /// Parses the markup of this text.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first construct that is not well-formed.
+    pub fn parse(&self) -> Result<Document, MarkupError> {
+        Document::parse(&self.0)
+    }
+
+    /// Parses the markup of this text, falling back to plain paragraphs if it is not well-formed.
+    ///
+    /// Descriptors are checked when being loaded, so this is only relevant for descriptors that
+    /// have been constructed directly.
+    #[must_use]
+    pub fn document(&self) -> Document {
+        self.parse().unwrap_or_else(|_| Document::plain(&self.0))
+    }
+}
+
+impl Document {
+    /// Parses the given markup.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first construct that is not well-formed.
+    pub fn parse(text: &str) -> Result<Self, MarkupError> {
+        let blocks = split_blocks(text)
+            .into_iter()
+            .map(|block| match block {
+                RawBlock::Paragraph(paragraph) => Ok(Block::Paragraph(parse_inlines(&paragraph)?)),
+                RawBlock::List(items) => items
+                    .iter()
+                    .map(|item| parse_inlines(item))
+                    .collect::<Result<_, _>>()
+                    .map(Block::List),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { blocks })
+    }
+
+    /// Takes each paragraph of the given text literally.
+    #[must_use]
+    pub fn plain(text: &str) -> Self {
+        let blocks = text
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| Block::Paragraph(vec![Inline::Text(paragraph.replace('\n', " "))]))
+            .collect();
+        Self { blocks }
+    }
+
+    /// Returns whether the document contains no text at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns all functions referenced by this document.
+    #[must_use]
+    pub fn links(&self) -> Vec<&Identifier> {
+        fn collect<'document>(
+            inlines: &'document [Inline],
+            links: &mut Vec<&'document Identifier>,
+        ) {
+            for inline in inlines {
+                match *inline {
+                    Inline::Link(ref target) => links.push(target),
+                    Inline::Emphasis(ref inner) => collect(inner, links),
+                    Inline::Text(_) | Inline::Code(_) => {}
+                }
+            }
+        }
+
+        let mut links = Vec::new();
+        for block in &self.blocks {
+            match *block {
+                Block::Paragraph(ref inlines) => collect(inlines, &mut links),
+                Block::List(ref items) => {
+                    for item in items {
+                        collect(item, &mut links);
+                    }
+                }
+            }
+        }
+        links
+    }
+
+    /// Renders the document without any markup.
+    #[must_use]
+    pub fn to_plain(&self, name: LinkName<'_>) -> String {
+        self.render("- ", &|nested| plain_inline(nested, name))
+    }
+
+    /// Renders the document as Markdown.
+    ///
+    /// Links point to `#` followed by the result of `anchor` if given.
+    #[must_use]
+    pub fn to_markdown(&self, name: LinkName<'_>, anchor: Option<LinkName<'_>>) -> String {
+        self.render("- ", &|nested| markdown_inline(nested, name, anchor))
+    }
+
+    /// Renders the document as reStructuredText as used within Python docstrings.
+    ///
+    /// Links refer to Python functions via the `:func:` role.
+    #[must_use]
+    pub fn to_docstring(&self, name: LinkName<'_>) -> String {
+        self.render("- ", &|nested| docstring_inline(nested, name))
+    }
+
+    /// Renders the document as a fragment of HTML.
+    ///
+    /// Links point to `#` followed by the result of `anchor`.
+    #[must_use]
+    pub fn to_html(&self, name: LinkName<'_>, anchor: LinkName<'_>) -> String {
+        let render = |inline: &Inline| html_inline(inline, name, anchor);
+        self.blocks
+            .iter()
+            .map(|block| match *block {
+                Block::Paragraph(ref inlines) => {
+                    format!("<p>{}</p>", render_inlines(inlines, &render))
+                }
+                Block::List(ref items) => {
+                    let items = items
+                        .iter()
+                        .map(|item| format!("<li>{}</li>", render_inlines(item, &render)))
+                        .collect::<Vec<_>>();
+                    format!("<ul>{}</ul>", items.join(""))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the document for a terminal using ANSI escape sequences.
+    #[must_use]
+    pub fn to_terminal(&self, name: LinkName<'_>) -> String {
+        self.render("• ", &|nested| terminal_inline(nested, name))
+    }
+
+    /// Separates blocks by blank lines and puts each list item on its own line.
+    fn render(&self, bullet: &str, inline: &dyn Fn(&Inline) -> String) -> String {
+        self.blocks
+            .iter()
+            .map(|block| match *block {
+                Block::Paragraph(ref inlines) => render_inlines(inlines, inline),
+                Block::List(ref items) => items
+                    .iter()
+                    .map(|item| format!("{bullet}{}", render_inlines(item, inline)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn render_inlines(inlines: &[Inline], inline: &dyn Fn(&Inline) -> String) -> String {
+    inlines.iter().map(inline).collect()
+}
+
+fn plain_inline(inline: &Inline, name: LinkName<'_>) -> String {
+    match *inline {
+        Inline::Text(ref text) | Inline::Code(ref text) => text.clone(),
+        Inline::Emphasis(ref inner) => render_inlines(inner, &|nested| plain_inline(nested, name)),
+        Inline::Link(ref target) => name(target),
+    }
+}
+
+fn markdown_inline(inline: &Inline, name: LinkName<'_>, anchor: Option<LinkName<'_>>) -> String {
+    match *inline {
+        Inline::Text(ref text) => escape_markup(text),
+        Inline::Code(ref code) => code_span(code, "`"),
+        Inline::Emphasis(ref inner) => format!(
+            "*{}*",
+            render_inlines(inner, &|nested| markdown_inline(nested, name, anchor))
+        ),
+        Inline::Link(ref target) => match anchor {
+            Some(anchor) => format!("[`{}`](#{})", name(target), anchor(target)),
+            None => format!("`{}`", name(target)),
+        },
+    }
+}
+
+fn docstring_inline(inline: &Inline, name: LinkName<'_>) -> String {
+    match *inline {
+        Inline::Text(ref text) => text
+            .replace('\\', "\\\\")
+            .replace('*', "\\*")
+            .replace('`', "\\`"),
+        Inline::Code(ref code) => code_span(code, "``"),
+        Inline::Emphasis(ref inner) => {
+            format!(
+                "*{}*",
+                render_inlines(inner, &|nested| docstring_inline(nested, name))
+            )
+        }
+        Inline::Link(ref target) => format!(":func:`{}`", name(target)),
+    }
+}
+
+fn html_inline(inline: &Inline, name: LinkName<'_>, anchor: LinkName<'_>) -> String {
+    match *inline {
+        Inline::Text(ref text) => escape_html(text),
+        Inline::Code(ref code) => format!("<code>{}</code>", escape_html(code)),
+        Inline::Emphasis(ref inner) => format!(
+            "<em>{}</em>",
+            render_inlines(inner, &|nested| html_inline(nested, name, anchor))
+        ),
+        Inline::Link(ref target) => format!(
+            "<a href=\"#{}\"><code>{}</code></a>",
+            escape_html(&anchor(target)),
+            escape_html(&name(target))
+        ),
+    }
+}
+
+fn terminal_inline(inline: &Inline, name: LinkName<'_>) -> String {
+    match *inline {
+        Inline::Text(ref text) => text.clone(),
+        // cyan for code, italic for emphasis and underlined for links
+        Inline::Code(ref code) => format!("\x1b[36m{code}\x1b[39m"),
+        Inline::Emphasis(ref inner) => format!(
+            "\x1b[3m{}\x1b[23m",
+            render_inlines(inner, &|nested| terminal_inline(nested, name))
+        ),
+        Inline::Link(ref target) => format!("\x1b[4m{}\x1b[24m", name(target)),
+    }
+}
+
+/// Encloses code in enough backticks so it cannot terminate the span itself.
+fn code_span(code: &str, delimiter: &str) -> String {
+    if code.contains(delimiter) {
+        format!("{delimiter}` {code} `{delimiter}")
+    } else if code.starts_with('`') || code.ends_with('`') {
+        format!("{delimiter} {code} {delimiter}")
+    } else {
+        format!("{delimiter}{code}{delimiter}")
+    }
+}
+
+/// Keeps plain text from being interpreted as markup.
+fn escape_markup(text: &str) -> String {
+    text.chars()
+        .flat_map(|character| {
+            let escape = matches!(character, '\\' | '`' | '*' | '_' | '[' | ']').then_some('\\');
+            escape.into_iter().chain([character])
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A block whose inline markup has not been parsed yet
+enum RawBlock {
+    Paragraph(String),
+    List(Vec<String>),
+}
+
+fn split_blocks(text: &str) -> Vec<RawBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<RawBlock> = None;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            blocks.extend(current.take());
+        } else if let Some(item) = line.strip_prefix("- ") {
+            if let Some(RawBlock::List(ref mut items)) = current {
+                items.push(item.trim().to_owned());
+            } else {
+                blocks.extend(current.take());
+                current = Some(RawBlock::List(vec![item.trim().to_owned()]));
+            }
+        } else {
+            match current {
+                Some(RawBlock::Paragraph(ref mut paragraph)) => {
+                    paragraph.push(' ');
+                    paragraph.push_str(line.trim());
+                }
+                Some(RawBlock::List(ref mut items)) if line.starts_with("  ") => {
+                    if let Some(item) = items.last_mut() {
+                        item.push(' ');
+                        item.push_str(line.trim());
+                    }
+                }
+                _ => {
+                    blocks.extend(current.take());
+                    current = Some(RawBlock::Paragraph(line.trim().to_owned()));
+                }
+            }
+        }
+    }
+    blocks.extend(current);
+    blocks
+}
+
+fn parse_inlines(text: &str) -> Result<Vec<Inline>, MarkupError> {
+    let mut chars = text.chars().peekable();
+    let (inlines, _) = parse_sequence(&mut chars, false)?;
+    Ok(inlines)
+}
+
+/// Parses inlines until the end of the text or the closing `*` of an emphasis.
+///
+/// Returns whether the sequence has been closed by a `*`.
+fn parse_sequence(
+    chars: &mut Peekable<Chars<'_>>,
+    emphasis: bool,
+) -> Result<(Vec<Inline>, bool), MarkupError> {
+    let mut inlines = Vec::new();
+    let mut text = String::new();
+    while let Some(character) = chars.next() {
+        let inline = match character {
+            '\\' => {
+                text.push(chars.next().ok_or(MarkupError::TrailingBackslash)?);
+                continue;
+            }
+            '`' => Inline::Code(take_until(chars, '`')?),
+            '*' if emphasis => {
+                flush(&mut text, &mut inlines);
+                return Ok((inlines, true));
+            }
+            '*' => {
+                let excerpt = chars.clone().collect::<String>();
+                let (inner, closed) = parse_sequence(chars, true)?;
+                if !closed {
+                    return Err(MarkupError::Unterminated {
+                        delimiter: '*',
+                        excerpt,
+                    });
+                }
+                if inner.is_empty() {
+                    return Err(MarkupError::Empty { delimiter: '*' });
+                }
+                Inline::Emphasis(inner)
+            }
+            '[' => {
+                let target = take_until(chars, ']')?;
+                if target.is_empty() {
+                    return Err(MarkupError::Empty { delimiter: '[' });
+                }
+                match Identifier::try_from(target.clone()) {
+                    Ok(identifier) => Inline::Link(identifier),
+                    Err(error) => return Err(MarkupError::InvalidLink { target, error }),
+                }
+            }
+            _ => {
+                text.push(character);
+                continue;
+            }
+        };
+        flush(&mut text, &mut inlines);
+        inlines.push(inline);
+    }
+    flush(&mut text, &mut inlines);
+    Ok((inlines, false))
+}
+
+/// Takes all characters up to the given delimiter which will be consumed as well.
+fn take_until(chars: &mut Peekable<Chars<'_>>, delimiter: char) -> Result<String, MarkupError> {
+    let excerpt = chars.clone().collect::<String>();
+    let mut content = String::new();
+    for character in chars.by_ref() {
+        if character == delimiter {
+            return Ok(content);
+        }
+        content.push(character);
+    }
+    Err(MarkupError::Unterminated {
+        delimiter: opening(delimiter),
+        excerpt,
+    })
+}
+
+fn opening(closing: char) -> char {
+    match closing {
+        ']' => '[',
+        other => other,
+    }
+}
+
+fn flush(text: &mut String, inlines: &mut Vec<Inline>) {
+    if !text.is_empty() {
+        inlines.push(Inline::Text(std::mem::take(text)));
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic, reason = "unexpected documents fail the test")]
+mod tests {
+    use super::{Block, Document, Inline, MarkupError};
+    use crate::api::{Identifier, IdentifierError, RichText};
+
+    fn text(text: &str) -> Inline {
+        Inline::Text(text.to_owned())
+    }
+
+    fn link(target: &'static str) -> Inline {
+        Inline::Link(Identifier(target.into()))
+    }
+
+    fn paragraph(markup: &str) -> Vec<Inline> {
+        let document = Document::parse(markup).unwrap();
+        let [Block::Paragraph(ref inlines)] = *document.blocks else {
+            panic!("expected a single paragraph, got {document:?}");
+        };
+        inlines.clone()
+    }
+
+    fn error(markup: &str) -> MarkupError {
+        Document::parse(markup).unwrap_err()
+    }
+
+    /// Renders links as the identifier in upper case to tell them apart from plain text.
+    fn name(target: &Identifier) -> String {
+        target.0.to_uppercase()
+    }
+
+    fn anchor(target: &Identifier) -> String {
+        target.0.replace(' ', "-")
+    }
+
+    #[test]
+    fn blocks_are_separated_by_blank_lines_and_list_items() {
+        let document = Document::parse("first\nline\n\nsecond\n- one\n  continued\n- two").unwrap();
+        assert_eq!(
+            document.blocks,
+            [
+                Block::Paragraph(vec![text("first line")]),
+                Block::Paragraph(vec![text("second")]),
+                Block::List(vec![vec![text("one continued")], vec![text("two")]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_markup() {
+        assert_eq!(
+            paragraph(r"a *b* `c*d` [move forward] \*e\["),
+            [
+                text("a "),
+                Inline::Emphasis(vec![text("b")]),
+                text(" "),
+                Inline::Code("c*d".to_owned()),
+                text(" "),
+                link("move forward"),
+                text(" *e["),
+            ]
+        );
+        assert_eq!(
+            paragraph("*see [jump] or `x`*"),
+            [Inline::Emphasis(vec![
+                text("see "),
+                link("jump"),
+                text(" or "),
+                Inline::Code("x".to_owned()),
+            ])]
+        );
+    }
+
+    #[test]
+    fn malformed_markup() {
+        assert_eq!(
+            error("a `b"),
+            MarkupError::Unterminated {
+                delimiter: '`',
+                excerpt: "b".to_owned()
+            }
+        );
+        assert_eq!(
+            error("a *b"),
+            MarkupError::Unterminated {
+                delimiter: '*',
+                excerpt: "b".to_owned()
+            }
+        );
+        assert_eq!(
+            error("a [b"),
+            MarkupError::Unterminated {
+                delimiter: '[',
+                excerpt: "b".to_owned()
+            }
+        );
+        assert_eq!(error("a ** b"), MarkupError::Empty { delimiter: '*' });
+        assert_eq!(error("a [] b"), MarkupError::Empty { delimiter: '[' });
+        assert_eq!(
+            error("[Jump]"),
+            MarkupError::InvalidLink {
+                target: "Jump".to_owned(),
+                error: IdentifierError::InvalidCharacter('J'),
+            }
+        );
+        assert_eq!(error(r"a\"), MarkupError::TrailingBackslash);
+    }
+
+    #[test]
+    fn links_are_collected_from_all_blocks() {
+        let document = Document::parse("[jump]\n\n- *[turn left]*\n- [stop]").unwrap();
+        let links = document
+            .links()
+            .into_iter()
+            .map(|target| target.0.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(links, ["jump", "turn left", "stop"]);
+    }
+
+    #[test]
+    fn malformed_text_is_taken_literally() {
+        let text = RichText("a *b\n\nc".into());
+        text.parse().unwrap_err();
+        assert_eq!(text.document().to_plain(&name), "a *b\n\nc");
+    }
+
+    #[test]
+    fn rendering() {
+        let document = Document::parse(r"*a* `b` [move forward] <\_>").unwrap();
+        assert_eq!(document.to_plain(&name), "a b MOVE FORWARD <_>");
+        assert_eq!(
+            document.to_markdown(&name, Some(&anchor)),
+            r"*a* `b` [`MOVE FORWARD`](#move-forward) <\_>"
+        );
+        assert_eq!(
+            document.to_markdown(&name, None),
+            r"*a* `b` `MOVE FORWARD` <\_>"
+        );
+        assert_eq!(
+            document.to_docstring(&name),
+            "*a* ``b`` :func:`MOVE FORWARD` <_>"
+        );
+        assert_eq!(
+            document.to_html(&name, &anchor),
+            "<p><em>a</em> <code>b</code> \
+             <a href=\"#move-forward\"><code>MOVE FORWARD</code></a> &lt;_&gt;</p>"
+        );
+    }
+
+    #[test]
+    fn lists_are_rendered_line_by_line() {
+        let document = Document::parse("intro\n- one\n- two").unwrap();
+        assert_eq!(document.to_plain(&name), "intro\n\n- one\n- two");
+        assert_eq!(
+            document.to_html(&name, &anchor),
+            "<p>intro</p>\n<ul><li>one</li><li>two</li></ul>"
+        );
+    }
+
+    #[test]
+    fn code_containing_backticks() {
+        let document = Document::parse(r"`a\`").unwrap();
+        // the backslash is taken verbatim within code
+        assert_eq!(document.to_markdown(&name, None), r"`a\`");
+        let ticks = Document {
+            blocks: vec![Block::Paragraph(vec![Inline::Code("a`b".to_owned())])],
+        };
+        assert_eq!(ticks.to_markdown(&name, None), "`` a`b ``");
+        assert_eq!(ticks.to_docstring(&name), "``a`b``");
+    }
+}
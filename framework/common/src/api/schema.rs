@@ -0,0 +1,833 @@
+//! A JSON Schema describing the serialized form of an [`ApiDescriptor`](super::ApiDescriptor) and
+//! a checker for it.
+//!
+//! The schema allows editors to offer completion and validation while writing descriptors by hand.
+//! It follows the encodings serde produces, most notably the externally tagged enumerations
+//! [`TypeDescriptor`](super::TypeDescriptor) and [`Value`](super::Value) (e.g. `"Float"` or
+//! `{ "Integer": [0, 10000] }`).
+//!
+//! The checker only supports the keywords used by this schema. It ignores `pattern` as
+//! [`ApiDescriptor::validate`](super::ApiDescriptor::validate) reports malformed identifiers with
+//! more precise messages.
+
+use serde_json::{Map, Value, json};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// The dialect of JSON Schema the exported schema is written in
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A single place where a JSON document does not match a schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON path to the offending element, e.g. `$.functions["jump"].parameters[0]`
+    pub path: String,
+    /// What is wrong with the element
+    pub message: String,
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}: {}", self.path, self.message)
+    }
+}
+
+impl Error for SchemaViolation {}
+
+pub(super) fn schema() -> Value {
+    let identifier = json!({
+        "type": "string",
+        "description": "lowercase words of `a-z` and `0-9` separated by single spaces",
+        "pattern": "^[a-z0-9]+( [a-z0-9]+)*$",
+    });
+    let rich_text = json!({
+        "type": "string",
+        "description": "text with a subset of Markdown: *emphasis*, `code`, lists and [links]",
+    });
+    let localizations = json!({
+        "type": "object",
+        "description": "translations into other languages by language tag (e.g. `de` or `de-CH`)",
+        "propertyNames": { "pattern": "^[a-z]{2,3}(-[A-Z]{2})?$" },
+        "additionalProperties": { "$ref": "#/$defs/localization" },
+    });
+
+    let mut schema = json!({
+        "$schema": DIALECT,
+        "title": "api descriptor",
+        "description": "Describes the functions and events of an api",
+        "$ref": "#/$defs/api",
+        "$defs": {
+            "identifier": identifier,
+            "rich text": rich_text,
+            "localizations": localizations,
+            "api": element("api", json!({
+                "$schema": { "type": "string", "description": "location of this schema" },
+                "version": {
+                    "type": "string",
+                    "description": "semantic version, e.g. `1.2.3`",
+                    "pattern": "^[0-9]+\\.[0-9]+\\.[0-9]+$",
+                },
+                "functions": {
+                    "type": "object",
+                    "description": "all functions by their names",
+                    "additionalProperties": { "$ref": "#/$defs/function" },
+                },
+                "events": {
+                    "type": "object",
+                    "description": "all events by their names",
+                    "additionalProperties": { "$ref": "#/$defs/event" },
+                },
+            }), "functions"),
+            "function": element("function", json!({
+                "parameters": { "type": "array", "items": { "$ref": "#/$defs/parameter" } },
+                "returns": { "anyOf": [{ "type": "null" }, { "$ref": "#/$defs/parameter" }] },
+            }), "parameters"),
+            "event": element("event", json!({
+                "parameters": { "type": "array", "items": { "$ref": "#/$defs/parameter" } },
+            }), "parameters"),
+            "parameter": element("parameter", json!({
+                "type": { "$ref": "#/$defs/type" },
+                "default": { "anyOf": [{ "type": "null" }, { "$ref": "#/$defs/value" }] },
+            }), "type"),
+            "localization": {
+                "title": "localization",
+                "type": "object",
+                "properties": {
+                    "alias": {
+                        "type": "string",
+                        "description": "name in this language; may contain any lowercase letter",
+                    },
+                    "caption": { "$ref": "#/$defs/rich text" },
+                    "description": { "$ref": "#/$defs/rich text" },
+                },
+                "additionalProperties": false,
+            },
+        },
+    });
+    if let Some(definitions) = schema.get_mut("$defs").and_then(Value::as_object_mut) {
+        definitions.extend(type_definitions());
+    }
+    schema
+}
+
+/// The encodings of [`TypeDescriptor`](super::TypeDescriptor) and [`Value`](super::Value)
+fn type_definitions() -> Map<String, Value> {
+    let definitions = json!({
+            "integer range": {
+                "title": "integer range",
+                "description": "lower (inclusive) and upper (exclusive) bound",
+                "anyOf": [
+                    {
+                        "type": "array",
+                        "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+                        "minItems": 2,
+                        "maxItems": 2,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "start": { "type": "integer" },
+                            "end": { "type": "integer" },
+                        },
+                        "required": ["start", "end"],
+                        "additionalProperties": false,
+                    },
+                ],
+            },
+            "type": {
+                "title": "type",
+                "oneOf": [
+                    { "const": "Float", "description": "a floating point number" },
+                    { "const": "Boolean", "description": "`true` or `false`" },
+                    { "const": "String", "description": "a text" },
+                    variant("Integer", &json!({ "$ref": "#/$defs/integer range" })),
+                    variant("List", &json!({ "$ref": "#/$defs/type" })),
+                    variant("Tuple", &json!({
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/type" },
+                    })),
+                    variant("Record", &json!({
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/type" },
+                    })),
+                    variant("Enumeration", &json!({
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/identifier" },
+                    })),
+                    variant("Optional", &json!({ "$ref": "#/$defs/type" })),
+                ],
+            },
+            "value": {
+                "title": "value",
+                "oneOf": [
+                    { "const": "Unit" },
+                    variant("Integer", &json!({ "type": "integer" })),
+                    variant("Float", &json!({ "type": "number" })),
+                    variant("Boolean", &json!({ "type": "boolean" })),
+                    variant("String", &json!({ "type": "string" })),
+                    variant("List", &json!({
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/value" },
+                    })),
+                    variant("Tuple", &json!({
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/value" },
+                    })),
+                    variant("Record", &json!({
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/value" },
+                    })),
+                    variant("Enumeration", &json!({ "$ref": "#/$defs/identifier" })),
+                    variant("Optional", &json!({
+                        "anyOf": [{ "type": "null" }, { "$ref": "#/$defs/value" }],
+                    })),
+                ],
+            },
+    });
+    match definitions {
+        Value::Object(definitions) => definitions,
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Array(_) => {
+            Map::new()
+        }
+    }
+}
+
+/// An api, function, event or parameter with the properties all of them share
+fn element(title: &str, mut properties: Value, required: &str) -> Value {
+    if let Some(properties) = properties.as_object_mut() {
+        properties.insert("name".to_owned(), json!({ "$ref": "#/$defs/identifier" }));
+        properties.insert("caption".to_owned(), json!({ "$ref": "#/$defs/rich text" }));
+        properties.insert(
+            "description".to_owned(),
+            json!({ "$ref": "#/$defs/rich text" }),
+        );
+        properties.insert(
+            "localizations".to_owned(),
+            json!({ "$ref": "#/$defs/localizations" }),
+        );
+    }
+    json!({
+        "title": title,
+        "type": "object",
+        "properties": properties,
+        "required": ["name", "caption", "description", required],
+        "additionalProperties": false,
+    })
+}
+
+/// An externally tagged enumeration variant carrying data
+fn variant(name: &str, content: &Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { name: content },
+        "required": [name],
+        "additionalProperties": false,
+    })
+}
+
+/// Checks a JSON document against a schema.
+///
+/// Only the keywords used by [`ApiDescriptor::json_schema`](super::ApiDescriptor::json_schema)
+/// are supported: `$ref` (within the same schema), `type`, `const`, `enum`, `oneOf`, `anyOf`,
+/// `properties`, `required`, `additionalProperties`, `propertyNames`, `prefixItems`, `items`,
+/// `minItems`, `maxItems` and `minLength`. Instead of listing the failures of every alternative of
+/// a `oneOf` or `anyOf`, the checker reports those of the alternative that matches best.
+#[must_use]
+pub fn check_schema(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let checker = Checker { root: schema };
+    let mut failures = Vec::new();
+    checker.check(schema, instance, "$", &mut failures);
+    failures
+        .into_iter()
+        .map(|failure| failure.violation)
+        .collect()
+}
+
+struct Failure {
+    violation: SchemaViolation,
+    /// The instance itself has the wrong type or value (rather than one of its members)
+    mismatch: bool,
+}
+
+struct Checker<'schema> {
+    root: &'schema Value,
+}
+
+impl Checker<'_> {
+    fn fail(failures: &mut Vec<Failure>, path: &str, message: String, mismatch: bool) {
+        failures.push(Failure {
+            violation: SchemaViolation {
+                path: path.to_owned(),
+                message,
+            },
+            mismatch,
+        });
+    }
+
+    /// Follows a reference to a definition of the schema.
+    fn resolve<'value>(&'value self, schema: &'value Value) -> &'value Value {
+        match schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+        {
+            Some(pointer) => self.root.pointer(pointer).unwrap_or(&Value::Bool(true)),
+            None => schema,
+        }
+    }
+
+    fn check(&self, definition: &Value, instance: &Value, path: &str, failures: &mut Vec<Failure>) {
+        if *definition == Value::Bool(false) {
+            Self::fail(failures, path, "is not allowed here".to_owned(), true);
+            return;
+        }
+        // `true` and anything that is not a schema accept every instance
+        let Value::Object(ref schema) = *definition else {
+            return;
+        };
+
+        if schema.contains_key("$ref") {
+            self.check(self.resolve(definition), instance, path, failures);
+        }
+
+        if let Some(expected) = schema.get("type")
+            && !matches_type(expected, instance)
+        {
+            let message = format!(
+                "expected {} but found {}",
+                type_names(expected),
+                describe(instance)
+            );
+            Self::fail(failures, path, message, true);
+            return;
+        }
+
+        if let Some(expected) = schema.get("const")
+            && expected != instance
+        {
+            let message = format!("expected {expected} but found {}", describe(instance));
+            Self::fail(failures, path, message, true);
+            return;
+        }
+
+        if let Some(Value::Array(allowed)) = schema.get("enum")
+            && !allowed.contains(instance)
+        {
+            let allowed = allowed.iter().map(Value::to_string).collect::<Vec<_>>();
+            let message = format!(
+                "expected one of {} but found {instance}",
+                allowed.join(", ")
+            );
+            Self::fail(failures, path, message, true);
+            return;
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(alternatives)) = schema.get(keyword) {
+                self.alternatives(alternatives, instance, path, failures);
+            }
+        }
+
+        match *instance {
+            Value::Object(ref members) => self.object(schema, members, path, failures),
+            Value::Array(ref elements) => self.array(schema, elements, path, failures),
+            Value::String(ref string) => {
+                if let Some(minimum) = schema.get("minLength").and_then(Value::as_u64)
+                    && (string.chars().count() as u64) < minimum
+                {
+                    let message = format!("expected at least {minimum} characters");
+                    Self::fail(failures, path, message, false);
+                }
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+
+    /// Reports the failures of the alternative that comes closest if none of them matches.
+    fn alternatives(
+        &self,
+        alternatives: &[Value],
+        instance: &Value,
+        path: &str,
+        failures: &mut Vec<Failure>,
+    ) {
+        let results = alternatives
+            .iter()
+            .map(|alternative| {
+                let mut branch = Vec::new();
+                self.check(alternative, instance, path, &mut branch);
+                branch
+            })
+            .collect::<Vec<_>>();
+        if results.iter().any(Vec::is_empty) {
+            return;
+        }
+
+        // alternatives rejecting the instance as a whole are most likely not the intended ones
+        let candidates = results
+            .into_iter()
+            .filter(|branch| {
+                !branch
+                    .iter()
+                    .all(|failure| failure.mismatch && failure.violation.path == path)
+            })
+            .collect::<Vec<_>>();
+        let deepest = candidates
+            .iter()
+            .filter(|branch| {
+                branch
+                    .iter()
+                    .any(|failure| failure.violation.path.len() > path.len())
+            })
+            .collect::<Vec<_>>();
+        if let [branch] = candidates.as_slice() {
+            failures.extend(branch.iter().map(Failure::copy));
+        } else if let [branch] = deepest.as_slice() {
+            failures.extend(branch.iter().map(Failure::copy));
+        } else {
+            let message = self.expected_alternatives(alternatives, instance);
+            Self::fail(failures, path, message, candidates.is_empty());
+        }
+    }
+
+    /// Summarizes all alternatives and suggests the closest one.
+    fn expected_alternatives(&self, alternatives: &[Value], instance: &Value) -> String {
+        let mut constants = Vec::new();
+        let mut tags = Vec::new();
+        let mut others = Vec::new();
+        for alternative in alternatives {
+            let alternative = self.resolve(alternative);
+            if let Some(constant) = alternative.get("const") {
+                constants.push(constant.to_string());
+            } else if let Some(Value::Array(required)) = alternative.get("required")
+                && let [Value::String(tag)] = required.as_slice()
+                && alternative.get("additionalProperties") == Some(&Value::Bool(false))
+            {
+                tags.push(tag.clone());
+            } else if let Some(title) = alternative.get("title").and_then(Value::as_str) {
+                others.push(format!("a {title}"));
+            } else if let Some(expected) = alternative.get("type") {
+                others.push(type_names(expected));
+            }
+        }
+
+        let mut expected = constants;
+        expected.extend(others);
+        if !tags.is_empty() {
+            let tags = tags
+                .iter()
+                .map(|tag| format!("{tag:?}"))
+                .collect::<Vec<_>>();
+            expected.push(format!(
+                "an object with one of the keys {}",
+                tags.join(", ")
+            ));
+        }
+        let expected = match expected.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+            None => "something else".to_owned(),
+        };
+        let mut message = format!("expected {expected} but found {}", describe(instance));
+
+        // the most common mistake is a misspelled variant
+        let written = match *instance {
+            Value::String(ref string) => Some(string),
+            Value::Object(ref members) if members.len() == 1 => members.keys().next(),
+            _ => None,
+        };
+        let candidates = alternatives.iter().filter_map(|alternative| {
+            let alternative = self.resolve(alternative);
+            match alternative.get("const") {
+                Some(Value::String(constant)) => Some(constant.as_str()),
+                _ => alternative
+                    .get("required")
+                    .and_then(|required| required.get(0))
+                    .and_then(Value::as_str),
+            }
+        });
+        if let Some(written) = written
+            && let Some(suggestion) = closest(written, candidates)
+        {
+            message = format!("{message}; did you mean {suggestion:?}?");
+        }
+        message
+    }
+
+    fn object(
+        &self,
+        schema: &Map<String, Value>,
+        members: &Map<String, Value>,
+        path: &str,
+        failures: &mut Vec<Failure>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !members.contains_key(name) {
+                    Self::fail(failures, path, format!("missing property {name:?}"), false);
+                }
+            }
+        }
+
+        for (name, member) in members {
+            // entries of maps are written like in the messages of `ApiDescriptor::validate`
+            let entry_path = format!("{path}[{name:?}]");
+            if let Some(names) = schema.get("propertyNames") {
+                self.check(names, &Value::String(name.clone()), &entry_path, failures);
+            }
+            if let Some(property) = properties.and_then(|properties| properties.get(name)) {
+                self.check(property, member, &format!("{path}.{name}"), failures);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(&Value::Bool(false)) => {
+                    let known = properties
+                        .into_iter()
+                        .flat_map(|properties| properties.keys().map(String::as_str));
+                    let message = closest(name, known).map_or_else(
+                        || format!("unknown property {name:?}"),
+                        |suggestion| {
+                            format!("unknown property {name:?}; did you mean {suggestion:?}?")
+                        },
+                    );
+                    Self::fail(failures, path, message, false);
+                }
+                Some(additional) => self.check(additional, member, &entry_path, failures),
+                None => {}
+            }
+        }
+    }
+
+    fn array(
+        &self,
+        schema: &Map<String, Value>,
+        elements: &[Value],
+        path: &str,
+        failures: &mut Vec<Failure>,
+    ) {
+        let prefix = schema
+            .get("prefixItems")
+            .and_then(Value::as_array)
+            .map_or(&[][..], Vec::as_slice);
+        for (index, element) in elements.iter().enumerate() {
+            let element_path = format!("{path}[{index}]");
+            if let Some(item) = prefix.get(index) {
+                self.check(item, element, &element_path, failures);
+            } else if let Some(items) = schema.get("items") {
+                self.check(items, element, &element_path, failures);
+            }
+        }
+
+        let length = elements.len() as u64;
+        let minimum = schema.get("minItems").and_then(Value::as_u64);
+        let maximum = schema.get("maxItems").and_then(Value::as_u64);
+        let message = match (minimum, maximum) {
+            (Some(minimum), Some(maximum)) if minimum == maximum && length != minimum => {
+                Some(format!("expected {minimum} elements but found {length}"))
+            }
+            (Some(minimum), _) if length < minimum => Some(format!(
+                "expected at least {minimum} elements but found {length}"
+            )),
+            (_, Some(maximum)) if length > maximum => Some(format!(
+                "expected at most {maximum} elements but found {length}"
+            )),
+            _ => None,
+        };
+        if let Some(message) = message {
+            Self::fail(failures, path, message, false);
+        }
+    }
+}
+
+impl Failure {
+    fn copy(&self) -> Self {
+        Self {
+            violation: self.violation.clone(),
+            mismatch: self.mismatch,
+        }
+    }
+}
+
+fn matches_type(expected: &Value, instance: &Value) -> bool {
+    match *expected {
+        Value::String(ref name) => matches_type_name(name, instance),
+        Value::Array(ref names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| matches_type_name(name, instance)),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::Object(_) => true,
+    }
+}
+
+fn matches_type_name(name: &str, instance: &Value) -> bool {
+    match (name, instance) {
+        ("null", &Value::Null)
+        | ("boolean", &Value::Bool(_))
+        | ("number", &Value::Number(_))
+        | ("string", &Value::String(_))
+        | ("array", &Value::Array(_))
+        | ("object", &Value::Object(_)) => true,
+        // integers are only accepted without a fractional part, just like serde does
+        ("integer", Value::Number(number)) => number.is_i64() || number.is_u64(),
+        _ => false,
+    }
+}
+
+/// Names the expected types in plain words.
+fn type_names(expected: &Value) -> String {
+    let names = match *expected {
+        Value::String(ref name) => vec![name.as_str()],
+        Value::Array(ref names) => names.iter().filter_map(Value::as_str).collect(),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::Object(_) => Vec::new(),
+    };
+    let names = names.into_iter().map(article).collect::<Vec<_>>();
+    names.join(" or ")
+}
+
+fn article(type_name: &str) -> String {
+    match type_name {
+        "null" => "null".to_owned(),
+        "integer" | "object" | "array" => format!("an {type_name}"),
+        _ => format!("a {type_name}"),
+    }
+}
+
+/// Describes a value in plain words for diagnostics.
+fn describe(instance: &Value) -> String {
+    match *instance {
+        Value::Null => "null".to_owned(),
+        Value::Bool(boolean) => format!("the boolean {boolean}"),
+        Value::Number(ref number) => format!("the number {number}"),
+        Value::String(ref string) => format!("the string {string:?}"),
+        Value::Array(ref elements) => format!("an array of {} elements", elements.len()),
+        Value::Object(ref members) if members.len() == 1 => {
+            let key = members.keys().next().map_or("", String::as_str);
+            format!("an object with the key {key:?}")
+        }
+        Value::Object(_) => "an object".to_owned(),
+    }
+}
+
+/// Finds the candidate most likely meant by a misspelled name.
+fn closest<'candidate>(
+    written: &str,
+    candidates: impl IntoIterator<Item = &'candidate str>,
+) -> Option<&'candidate str> {
+    let written = written.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            (
+                edit_distance(&written, &candidate.to_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|&(distance, candidate)| distance <= (candidate.chars().count() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions and swaps of adjacent characters needed to
+/// turn one string into the other
+fn edit_distance(first: &str, second: &str) -> usize {
+    let first = first.chars().collect::<Vec<_>>();
+    let second = second.chars().collect::<Vec<_>>();
+    let width = second.len() + 1;
+    // distances[row * width + column] between the first `row` and `column` characters
+    let mut distances = vec![0; (first.len() + 1) * width];
+    for row in 0..=first.len() {
+        for column in 0..=second.len() {
+            let distance = if row == 0 || column == 0 {
+                row.max(column)
+            } else {
+                let at = |above: usize, left: usize| distances.get(above * width + left).copied();
+                let changed = usize::from(first.get(row - 1) != second.get(column - 1));
+                let mut distance = [
+                    at(row - 1, column - 1).map(|distance| distance + changed),
+                    at(row - 1, column).map(|distance| distance + 1),
+                    at(row, column - 1).map(|distance| distance + 1),
+                ]
+                .into_iter()
+                .flatten()
+                .min()
+                .unwrap_or_default();
+                if row > 1
+                    && column > 1
+                    && first.get(row - 1) == second.get(column - 2)
+                    && first.get(row - 2) == second.get(column - 1)
+                    && let Some(swapped) = at(row - 2, column - 2)
+                {
+                    distance = distance.min(swapped + 1);
+                }
+                distance
+            };
+            if let Some(cell) = distances.get_mut(row * width + column) {
+                *cell = distance;
+            }
+        }
+    }
+    distances.last().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::indexing_slicing,
+    reason = "the descriptors are modified by indexing into them"
+)]
+mod tests {
+    use super::{SchemaViolation, check_schema, edit_distance, schema};
+    use serde_json::{Value, json};
+
+    /// An api with a single function taking a parameter of the given type
+    fn api(parameter_type: &Value) -> Value {
+        json!({
+            "name": "robot",
+            "caption": "",
+            "description": "",
+            "version": "1.0.0",
+            "functions": {
+                "jump": {
+                    "name": "jump",
+                    "caption": "",
+                    "description": "",
+                    "parameters": [{
+                        "name": "height",
+                        "caption": "",
+                        "description": "",
+                        "type": parameter_type,
+                    }],
+                },
+            },
+        })
+    }
+
+    fn violation(path: &str, message: &str) -> SchemaViolation {
+        SchemaViolation {
+            path: path.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    #[test]
+    fn committed_schema_is_up_to_date() {
+        let committed: Value = serde_json::from_str(include_str!("../../api.schema.json")).unwrap();
+        assert_eq!(
+            committed,
+            schema(),
+            "run `cargo api schema framework/common/api.schema.json`"
+        );
+    }
+
+    #[test]
+    fn descriptors_match_the_schema() {
+        for json in [
+            include_str!("../../../../applications/robot/control.api.json"),
+            include_str!("../../../../engines/robot/api.json"),
+        ] {
+            let descriptor: Value = serde_json::from_str(json).unwrap();
+            assert_eq!(check_schema(&schema(), &descriptor), []);
+        }
+        assert_eq!(
+            check_schema(&schema(), &api(&json!({ "Integer": [0, 10] }))),
+            []
+        );
+        assert_eq!(
+            check_schema(
+                &schema(),
+                &api(&json!({ "Integer": { "start": 0, "end": 10 } }))
+            ),
+            []
+        );
+    }
+
+    /// The message for a parameter type matching none of the variants
+    fn unknown_type(found: &str) -> String {
+        format!(
+            "expected \"Float\", \"Boolean\", \"String\" or an object with one of the keys \
+             \"Integer\", \"List\", \"Tuple\", \"Record\", \"Enumeration\", \"Optional\" \
+             but found {found}"
+        )
+    }
+
+    #[test]
+    fn misspelled_variants_are_suggested() {
+        let path = r#"$.functions["jump"].parameters[0].type"#;
+        assert_eq!(
+            check_schema(&schema(), &api(&json!("Bolean"))),
+            [violation(
+                path,
+                &unknown_type(r#"the string "Bolean"; did you mean "Boolean"?"#)
+            )]
+        );
+        assert_eq!(
+            check_schema(&schema(), &api(&json!({ "Lsit": "Float" }))),
+            [violation(
+                path,
+                &unknown_type(r#"an object with the key "Lsit"; did you mean "List"?"#)
+            )]
+        );
+        assert_eq!(
+            check_schema(&schema(), &api(&json!({ "List": "Floot" }))),
+            [violation(
+                &format!("{path}.List"),
+                &unknown_type(r#"the string "Floot"; did you mean "Float"?"#)
+            )]
+        );
+        assert_eq!(
+            check_schema(&schema(), &api(&json!("Text"))),
+            [violation(path, &unknown_type(r#"the string "Text""#))]
+        );
+        assert_eq!(
+            check_schema(&schema(), &api(&json!(7))),
+            [violation(path, &unknown_type("the number 7"))]
+        );
+    }
+
+    #[test]
+    fn failures_of_the_closest_alternative_are_reported() {
+        assert_eq!(
+            check_schema(&schema(), &api(&json!({ "Integer": [0] }))),
+            [violation(
+                r#"$.functions["jump"].parameters[0].type.Integer"#,
+                "expected 2 elements but found 1"
+            )]
+        );
+    }
+
+    #[test]
+    fn properties_are_checked() {
+        let mut descriptor = api(&json!("Float"));
+        let function = descriptor["functions"]["jump"].as_object_mut().unwrap();
+        function.remove("caption");
+        function.insert("descripton".to_owned(), json!(""));
+        descriptor["functions"]["Jump"] = Value::Null;
+        assert_eq!(
+            check_schema(&schema(), &descriptor),
+            [
+                violation(
+                    r#"$.functions["Jump"]"#,
+                    "expected an object but found null"
+                ),
+                violation(r#"$.functions["jump"]"#, r#"missing property "caption""#),
+                violation(
+                    r#"$.functions["jump"]"#,
+                    r#"unknown property "descripton"; did you mean "description"?"#
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("boolean", "boolean"), 0);
+        assert_eq!(edit_distance("bolean", "boolean"), 1);
+        assert_eq!(edit_distance("lsit", "list"), 1);
+        assert_eq!(edit_distance("float", "flat"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
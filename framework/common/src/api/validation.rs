@@ -0,0 +1,787 @@
+//! Checks an [`ApiDescriptor`] for problems that cannot be caught by deserialization alone.
+//!
+//! Each problem will be reported with a JSON path pointing to the offending element,
+//! e.g. `$.functions["move forward"].parameters[0].default`.
+
+use super::{
+    ApiDescriptor, EventDescriptor, FunctionDescriptor, Identifier, IdentifierError, Locale,
+    Localization, Localized, MarkupError, ParameterDescriptor, RichText, SchemaViolation, Texts,
+    TypeDescriptor, Value,
+};
+use indexmap::IndexMap as HashMap;
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Display},
+    ops::Range,
+};
+
+/// Names function parameters must not take in any locale.
+///
+/// The Python bindings accept each of them as an additional keyword argument of every function
+/// (e.g. `move_forward(500, timeout=2.0)`).
+pub const RESERVED_PARAMETER_NAMES: &[&str] = &["timeout"];
+
+/// A single problem found within an [`ApiDescriptor`].
+#[derive(Clone, Debug)]
+pub struct ValidationError {
+    /// JSON path to the offending element
+    pub path: String,
+    /// What is wrong with the element
+    pub kind: ValidationErrorKind,
+}
+
+/// All kinds of problems [`ApiDescriptor::validate`] is able to detect.
+#[derive(Clone, Debug)]
+pub enum ValidationErrorKind {
+    /// The identifier violates the rules for technical names
+    InvalidIdentifier {
+        identifier: Identifier,
+        error: IdentifierError,
+    },
+    /// A function or event has been stored under a different key than its own name
+    NameMismatch { key: Identifier, name: Identifier },
+    /// The same parameter name appears more than once within a function or event
+    DuplicateParameter(Identifier),
+    /// A function parameter or its alias takes a name the bindings reserve for themselves (see
+    /// [`RESERVED_PARAMETER_NAMES`])
+    ReservedParameterName(Identifier),
+    /// The default value is not a member of the parameter's type
+    DefaultTypeMismatch { default: Value, typ: TypeDescriptor },
+    /// The integer range does not contain any value
+    EmptyIntegerRange(Range<i64>),
+    /// The integer range exceeds the limits given by [`TypeDescriptor::INTEGER_BITS`]
+    IntegerRangeOutOfBounds(Range<i64>),
+    /// An enumeration needs at least one variant
+    EmptyEnumeration,
+    /// The same variant appears more than once within an enumeration
+    DuplicateVariant(Identifier),
+    /// The language tag is malformed
+    InvalidLocale(Locale),
+    /// The alias violates the rules for aliases (see [`Identifier::check_alias`])
+    InvalidAlias {
+        alias: Identifier,
+        error: IdentifierError,
+    },
+    /// Two functions, events or parameters share the same name within a locale
+    DuplicateAlias { locale: Locale, alias: Identifier },
+    /// The caption or description is not well-formed
+    InvalidMarkup(MarkupError),
+    /// The caption or description links to a function that does not exist
+    UnknownReference(Identifier),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}: {}", self.path, self.kind)
+    }
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidIdentifier { identifier, error } => {
+                write!(formatter, "invalid identifier {:?}: {error}", identifier.0)
+            }
+            Self::NameMismatch { key, name } => {
+                write!(formatter, "listed as {:?} but named {:?}", key.0, name.0)
+            }
+            Self::DuplicateParameter(name) => {
+                write!(formatter, "duplicate parameter name {:?}", name.0)
+            }
+            Self::ReservedParameterName(name) => {
+                write!(formatter, "parameter name {:?} is reserved", name.0)
+            }
+            Self::DefaultTypeMismatch { default, typ } => {
+                write!(
+                    formatter,
+                    "default value {default:?} does not match {typ:?}"
+                )
+            }
+            Self::EmptyIntegerRange(range) => {
+                write!(formatter, "integer range {range:?} is empty")
+            }
+            Self::IntegerRangeOutOfBounds(range) => write!(
+                formatter,
+                "integer range {range:?} does not fit into {bits} bits",
+                bits = TypeDescriptor::INTEGER_BITS
+            ),
+            Self::EmptyEnumeration => write!(formatter, "enumeration has no variants"),
+            Self::DuplicateVariant(variant) => {
+                write!(formatter, "duplicate enumeration variant {:?}", variant.0)
+            }
+            Self::InvalidLocale(locale) => write!(formatter, "invalid locale {:?}", locale.0),
+            Self::InvalidAlias { alias, error } => {
+                write!(formatter, "invalid alias {:?}: {error}", alias.0)
+            }
+            Self::DuplicateAlias { locale, alias } => {
+                write!(
+                    formatter,
+                    "name {:?} is not unique in locale {locale}",
+                    alias.0
+                )
+            }
+            Self::InvalidMarkup(error) => write!(formatter, "invalid markup: {error}"),
+            Self::UnknownReference(target) => {
+                write!(formatter, "reference to unknown function {:?}", target.0)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Reasons why an [`ApiDescriptor`] could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The input is not a well-formed descriptor
+    Syntax(serde_json::Error),
+    /// The input is well-formed JSON but does not match the schema of descriptors
+    Schema(Vec<SchemaViolation>),
+    /// The input could be parsed but contains semantic errors
+    Invalid(Vec<ValidationError>),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => write!(formatter, "malformed api descriptor: {error}"),
+            Self::Schema(violations) => {
+                write!(formatter, "malformed api descriptor:")?;
+                for violation in violations {
+                    write!(formatter, "\n\t{violation}")?;
+                }
+                Ok(())
+            }
+            Self::Invalid(errors) => {
+                write!(formatter, "invalid api descriptor:")?;
+                for error in errors {
+                    write!(formatter, "\n\t{error}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Syntax(error) => Some(error),
+            Self::Schema(_) | Self::Invalid(_) => None,
+        }
+    }
+}
+
+pub(super) fn validate(api: &ApiDescriptor) -> Vec<ValidationError> {
+    let mut validator = Validator::default();
+
+    validator.identifier("$.name", &api.name);
+    validator.localizations("$", &api.localizations);
+
+    for (key, function) in &api.functions {
+        let path = format!("$.functions[{:?}]", key.0);
+        validator.function(&path, key, function);
+    }
+
+    for (key, event) in &api.events {
+        let path = format!("$.events[{:?}]", key.0);
+        validator.event(&path, key, event);
+    }
+
+    // aliases must not collide with other aliases or with names that have not been translated
+    for locale in api.locales() {
+        let mut functions = Vec::new();
+        for (key, function) in &api.functions {
+            let path = format!("$.functions[{:?}]", key.0);
+            validator.unique_parameter_names(&path, locale, &function.parameters);
+            functions.push((path, function.localized(Some(locale))));
+        }
+        validator.unique_names(locale, functions);
+
+        let mut events = Vec::new();
+        for (key, event) in &api.events {
+            let path = format!("$.events[{:?}]", key.0);
+            validator.unique_parameter_names(&path, locale, &event.parameters);
+            events.push((path, event.localized(Some(locale))));
+        }
+        validator.unique_names(locale, events);
+    }
+
+    // links within texts must point to functions of this api
+    for (path, text) in rich_texts(api) {
+        validator.rich_text(&path, text, api);
+    }
+
+    validator.errors
+}
+
+/// Collects all captions and descriptions including their translations.
+fn rich_texts(api: &ApiDescriptor) -> Vec<(String, &RichText)> {
+    fn element<'api>(
+        texts: &mut Vec<(String, &'api RichText)>,
+        path: &str,
+        element: &'api impl Localized,
+    ) {
+        let canonical = element.texts();
+        texts.push((format!("{path}.caption"), canonical.caption));
+        texts.push((format!("{path}.description"), canonical.description));
+        for (locale, localization) in element.localizations() {
+            let path = format!("{path}.localizations[{:?}]", locale.0);
+            if let Some(ref caption) = localization.caption {
+                texts.push((format!("{path}.caption"), caption));
+            }
+            if let Some(ref description) = localization.description {
+                texts.push((format!("{path}.description"), description));
+            }
+        }
+    }
+
+    let mut texts = Vec::new();
+    element(&mut texts, "$", api);
+    for (key, function) in &api.functions {
+        let path = format!("$.functions[{:?}]", key.0);
+        element(&mut texts, &path, function);
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            element(
+                &mut texts,
+                &format!("{path}.parameters[{index}]"),
+                parameter,
+            );
+        }
+        if let Some(ref returns) = function.returns {
+            element(&mut texts, &format!("{path}.returns"), returns);
+        }
+    }
+    for (key, event) in &api.events {
+        let path = format!("$.events[{:?}]", key.0);
+        element(&mut texts, &path, event);
+        for (index, parameter) in event.parameters.iter().enumerate() {
+            element(
+                &mut texts,
+                &format!("{path}.parameters[{index}]"),
+                parameter,
+            );
+        }
+    }
+    texts
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    fn report(&mut self, path: impl Into<String>, kind: ValidationErrorKind) {
+        self.errors.push(ValidationError {
+            path: path.into(),
+            kind,
+        });
+    }
+
+    fn identifier(&mut self, path: &str, identifier: &Identifier) {
+        if let Err(error) = identifier.validate() {
+            self.report(
+                path,
+                ValidationErrorKind::InvalidIdentifier {
+                    identifier: identifier.clone(),
+                    error,
+                },
+            );
+        }
+    }
+
+    fn localizations(&mut self, path: &str, localizations: &HashMap<Locale, Localization>) {
+        for (locale, localization) in localizations {
+            let path = format!("{path}.localizations[{:?}]", locale.0);
+            if !locale.is_valid() {
+                self.report(&path, ValidationErrorKind::InvalidLocale(locale.clone()));
+            }
+            if let Some(ref alias) = localization.alias
+                && let Err(error) = Identifier::check_alias(alias.as_ref())
+            {
+                self.report(
+                    format!("{path}.alias"),
+                    ValidationErrorKind::InvalidAlias {
+                        alias: alias.clone(),
+                        error,
+                    },
+                );
+            }
+        }
+    }
+
+    fn rich_text(&mut self, path: &str, text: &RichText, api: &ApiDescriptor) {
+        match text.parse() {
+            Ok(document) => {
+                for target in document.links() {
+                    if !api.functions.contains_key(target) {
+                        self.report(path, ValidationErrorKind::UnknownReference(target.clone()));
+                    }
+                }
+            }
+            Err(error) => self.report(path, ValidationErrorKind::InvalidMarkup(error)),
+        }
+    }
+
+    fn unique_names(&mut self, locale: &Locale, elements: Vec<(String, Texts<'_>)>) {
+        let mut names = HashSet::new();
+        for (path, texts) in elements {
+            if !names.insert(texts.name) {
+                self.report(
+                    path,
+                    ValidationErrorKind::DuplicateAlias {
+                        locale: locale.clone(),
+                        alias: texts.name.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn unique_parameter_names(
+        &mut self,
+        path: &str,
+        locale: &Locale,
+        parameters: &[ParameterDescriptor],
+    ) {
+        let parameters = parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| {
+                (
+                    format!("{path}.parameters[{index}]"),
+                    parameter.localized(Some(locale)),
+                )
+            })
+            .collect();
+        self.unique_names(locale, parameters);
+    }
+
+    fn function(&mut self, path: &str, key: &Identifier, function: &FunctionDescriptor) {
+        let FunctionDescriptor {
+            ref name,
+            caption: _,
+            description: _,
+            ref parameters,
+            ref returns,
+            ref localizations,
+        } = *function;
+
+        self.name(path, key, name);
+        self.localizations(path, localizations);
+        self.parameters(path, parameters);
+        for (index, parameter) in parameters.iter().enumerate() {
+            self.unreserved_parameter_names(&format!("{path}.parameters[{index}]"), parameter);
+        }
+
+        if let Some(returns) = returns {
+            self.parameter(&format!("{path}.returns"), returns);
+        }
+    }
+
+    fn event(&mut self, path: &str, key: &Identifier, event: &EventDescriptor) {
+        let EventDescriptor {
+            ref name,
+            caption: _,
+            description: _,
+            ref parameters,
+            ref localizations,
+        } = *event;
+
+        self.name(path, key, name);
+        self.localizations(path, localizations);
+        self.parameters(path, parameters);
+    }
+
+    fn name(&mut self, path: &str, key: &Identifier, name: &Identifier) {
+        self.identifier(path, key);
+        self.identifier(&format!("{path}.name"), name);
+        if key != name {
+            self.report(
+                format!("{path}.name"),
+                ValidationErrorKind::NameMismatch {
+                    key: key.clone(),
+                    name: name.clone(),
+                },
+            );
+        }
+    }
+
+    fn parameters(&mut self, path: &str, parameters: &[ParameterDescriptor]) {
+        let mut parameter_names = HashSet::new();
+        for (index, parameter) in parameters.iter().enumerate() {
+            let path = format!("{path}.parameters[{index}]");
+            if !parameter_names.insert(&parameter.name) {
+                self.report(
+                    format!("{path}.name"),
+                    ValidationErrorKind::DuplicateParameter(parameter.name.clone()),
+                );
+            }
+            self.parameter(&path, parameter);
+        }
+    }
+
+    fn unreserved_parameter_names(&mut self, path: &str, parameter: &ParameterDescriptor) {
+        let is_reserved = |name: &Identifier| RESERVED_PARAMETER_NAMES.contains(&name.as_ref());
+        if is_reserved(&parameter.name) {
+            self.report(
+                format!("{path}.name"),
+                ValidationErrorKind::ReservedParameterName(parameter.name.clone()),
+            );
+        }
+        for (locale, localization) in &parameter.localizations {
+            if let Some(ref alias) = localization.alias
+                && is_reserved(alias)
+            {
+                self.report(
+                    format!("{path}.localizations[{:?}].alias", locale.0),
+                    ValidationErrorKind::ReservedParameterName(alias.clone()),
+                );
+            }
+        }
+    }
+
+    fn parameter(&mut self, path: &str, parameter: &ParameterDescriptor) {
+        let ParameterDescriptor {
+            ref name,
+            caption: _,
+            description: _,
+            ref typ,
+            ref default,
+            ref localizations,
+        } = *parameter;
+
+        self.identifier(&format!("{path}.name"), name);
+        self.localizations(path, localizations);
+        self.typ(&format!("{path}.type"), typ);
+
+        if let Some(default) = default
+            && !typ.accepts(default)
+        {
+            self.report(
+                format!("{path}.default"),
+                ValidationErrorKind::DefaultTypeMismatch {
+                    default: default.clone(),
+                    typ: typ.clone(),
+                },
+            );
+        }
+    }
+
+    fn typ(&mut self, path: &str, typ: &TypeDescriptor) {
+        match typ {
+            TypeDescriptor::Integer(range) => {
+                if range.is_empty() {
+                    self.report(path, ValidationErrorKind::EmptyIntegerRange(range.clone()));
+                }
+                if !fits_integer_bits(range) {
+                    self.report(
+                        path,
+                        ValidationErrorKind::IntegerRangeOutOfBounds(range.clone()),
+                    );
+                }
+            }
+            TypeDescriptor::Float | TypeDescriptor::Boolean | TypeDescriptor::String => {}
+            TypeDescriptor::List(element_type) => {
+                self.typ(&format!("{path}.List"), element_type);
+            }
+            TypeDescriptor::Tuple(element_types) => {
+                for (index, element_type) in element_types.iter().enumerate() {
+                    self.typ(&format!("{path}.Tuple[{index}]"), element_type);
+                }
+            }
+            TypeDescriptor::Record(field_types) => {
+                for (name, field_type) in field_types {
+                    let path = format!("{path}.Record[{:?}]", name.0);
+                    self.identifier(&path, name);
+                    self.typ(&path, field_type);
+                }
+            }
+            TypeDescriptor::Enumeration(variants) => {
+                if variants.is_empty() {
+                    self.report(path, ValidationErrorKind::EmptyEnumeration);
+                }
+                let mut known_variants = HashSet::new();
+                for (index, variant) in variants.iter().enumerate() {
+                    let path = format!("{path}.Enumeration[{index}]");
+                    self.identifier(&path, variant);
+                    if !known_variants.insert(variant) {
+                        self.report(path, ValidationErrorKind::DuplicateVariant(variant.clone()));
+                    }
+                }
+            }
+            TypeDescriptor::Optional(inner_type) => {
+                self.typ(&format!("{path}.Optional"), inner_type);
+            }
+        }
+    }
+}
+
+/// Both bounds need to fit into either `i48` or `u48`.
+///
+/// The upper bound is exclusive and may therefore exceed the largest value by one.
+fn fits_integer_bits(range: &Range<i64>) -> bool {
+    let fits_signed = range.start >= TypeDescriptor::MIN_INTEGER
+        && range.end <= TypeDescriptor::MAX_SIGNED_INTEGER + 1;
+    let fits_unsigned = range.start >= 0
+        && u64::try_from(range.end)
+            .is_ok_and(|end| end <= TypeDescriptor::MAX_UNSIGNED_INTEGER + 1);
+    fits_signed || fits_unsigned
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::indexing_slicing,
+    reason = "the expected errors are looked up by position"
+)]
+mod tests {
+    use super::{ValidationError, ValidationErrorKind, fits_integer_bits};
+    use crate::api::{ApiDescriptor, IdentifierError, TypeDescriptor};
+    use serde_json::{Value, json};
+
+    fn api(functions: &Value) -> ApiDescriptor {
+        serde_json::from_value(json!({
+            "name": "test",
+            "caption": "a test",
+            "description": "a test",
+            "functions": functions,
+        }))
+        .unwrap()
+    }
+
+    fn function(name: &str, parameters: &Value) -> Value {
+        json!({
+            "name": name,
+            "caption": name,
+            "description": name,
+            "parameters": parameters,
+        })
+    }
+
+    fn parameter(name: &str, typ: &Value) -> Value {
+        json!({ "name": name, "caption": name, "description": name, "type": typ })
+    }
+
+    /// Returns the only error found for the function `f`.
+    fn single_error(parameters: &Value) -> ValidationError {
+        let api = api(&json!({ "f": function("f", parameters) }));
+        let mut errors = api.validate().unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        errors.remove(0)
+    }
+
+    #[test]
+    fn robot_api_is_valid() {
+        let json = include_bytes!("../../../../applications/robot/control.api.json");
+        let api: ApiDescriptor = serde_json::from_slice(json).unwrap();
+        api.validate().unwrap();
+    }
+
+    #[test]
+    fn invalid_api_name() {
+        let mut api = api(&json!({}));
+        api.name.0 = "Test".into();
+        let errors = api.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "$.name");
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::InvalidIdentifier {
+                error: IdentifierError::InvalidCharacter('T'),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn name_mismatch() {
+        let api = api(&json!({ "f": function("g", &json!([])) }));
+        let errors = api.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, r#"$.functions["f"].name"#);
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::NameMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn duplicate_parameter() {
+        let error = single_error(&json!([
+            parameter("x", &json!("Float")),
+            parameter("x", &json!("Boolean")),
+        ]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[1].name"#);
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::DuplicateParameter(_)
+        ));
+    }
+
+    #[test]
+    fn reserved_parameter_name() {
+        let error = single_error(&json!([parameter("timeout", &json!("Float"))]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[0].name"#);
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::ReservedParameterName(_)
+        ));
+    }
+
+    #[test]
+    fn reserved_parameter_alias() {
+        let mut wait = parameter("wait", &json!("Float"));
+        wait["localizations"] = json!({ "en": { "alias": "timeout" } });
+        let error = single_error(&json!([wait]));
+        assert_eq!(
+            error.path,
+            r#"$.functions["f"].parameters[0].localizations["en"].alias"#
+        );
+    }
+
+    #[test]
+    fn default_type_mismatch() {
+        let mut x = parameter("x", &json!({ "Integer": { "start": 0, "end": 10 } }));
+        x["default"] = json!({ "Integer": 10 });
+        let error = single_error(&json!([x]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[0].default"#);
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::DefaultTypeMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn empty_integer_range() {
+        let typ = json!({ "Integer": { "start": 5, "end": 5 } });
+        let error = single_error(&json!([parameter("x", &typ)]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[0].type"#);
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::EmptyIntegerRange(_)
+        ));
+    }
+
+    #[test]
+    fn integer_range_out_of_bounds() {
+        let typ = json!({ "List": { "Integer": { "start": 0, "end": i64::MAX } } });
+        let error = single_error(&json!([parameter("x", &typ)]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[0].type.List"#);
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::IntegerRangeOutOfBounds(_)
+        ));
+    }
+
+    #[test]
+    fn full_width_integer_ranges_fit() {
+        let signed = TypeDescriptor::MIN_INTEGER..TypeDescriptor::MAX_SIGNED_INTEGER + 1;
+        assert!(fits_integer_bits(&signed));
+        let unsigned = 0..i64::try_from(TypeDescriptor::MAX_UNSIGNED_INTEGER).unwrap() + 1;
+        assert!(fits_integer_bits(&unsigned));
+
+        assert!(!fits_integer_bits(&(TypeDescriptor::MIN_INTEGER - 1..0)));
+        assert!(!fits_integer_bits(&(0..unsigned.end + 1)));
+        assert!(!fits_integer_bits(&(-1..signed.end + 1)));
+    }
+
+    #[test]
+    fn empty_enumeration() {
+        let error = single_error(&json!([parameter("x", &json!({ "Enumeration": [] }))]));
+        assert_eq!(error.path, r#"$.functions["f"].parameters[0].type"#);
+        assert!(matches!(error.kind, ValidationErrorKind::EmptyEnumeration));
+    }
+
+    #[test]
+    fn invalid_and_duplicate_variants() {
+        let typ = json!({ "Optional": { "Enumeration": ["north", "north", "South"] } });
+        let api = api(&json!({ "f": function("f", &json!([parameter("x", &typ)])) }));
+        let errors = api.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        let path = r#"$.functions["f"].parameters[0].type.Optional.Enumeration"#;
+        assert_eq!(errors[0].path, format!("{path}[1]"));
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::DuplicateVariant(_)
+        ));
+        assert_eq!(errors[1].path, format!("{path}[2]"));
+        assert!(matches!(
+            errors[1].kind,
+            ValidationErrorKind::InvalidIdentifier { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_record_field() {
+        let typ = json!({ "Record": { "x": "Float", "y ": "Float" } });
+        let error = single_error(&json!([parameter("p", &typ)]));
+        assert_eq!(
+            error.path,
+            r#"$.functions["f"].parameters[0].type.Record["y "]"#
+        );
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::InvalidIdentifier {
+                error: IdentifierError::TrailingSpace,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_locale_and_alias() {
+        let mut localized = function("f", &json!([]));
+        localized["localizations"] = json!({ "DE": {}, "de": { "alias": "Gehe" } });
+        let errors = api(&json!({ "f": localized })).validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, r#"$.functions["f"].localizations["DE"]"#);
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::InvalidLocale(_)
+        ));
+        assert_eq!(
+            errors[1].path,
+            r#"$.functions["f"].localizations["de"].alias"#
+        );
+        assert!(matches!(
+            errors[1].kind,
+            ValidationErrorKind::InvalidAlias { .. }
+        ));
+    }
+
+    #[test]
+    fn duplicate_alias() {
+        let mut localized = function("g", &json!([]));
+        localized["localizations"] = json!({ "de": { "alias": "f" } });
+        let errors = api(&json!({ "f": function("f", &json!([])), "g": localized }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, r#"$.functions["g"]"#);
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::DuplicateAlias { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_markup_and_unknown_reference() {
+        let mut described = function("f", &json!([]));
+        described["caption"] = json!("calls [g]");
+        described["description"] = json!("*unterminated");
+        let errors = api(&json!({ "f": described })).validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, r#"$.functions["f"].caption"#);
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::UnknownReference(_)
+        ));
+        assert_eq!(errors[1].path, r#"$.functions["f"].description"#);
+        assert!(matches!(
+            errors[1].kind,
+            ValidationErrorKind::InvalidMarkup(_)
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+//! Identifies a revision of an [`ApiDescriptor`] so that both ends of a channel can make sure
+//! they are talking about the same api.
+
+use super::{ApiDescriptor, RichText};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A semantic version number of an api.
+///
+/// Within the same major version (or the same minor version while the major version is still `0`)
+/// newer revisions must remain compatible with older ones.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct ApiVersion {
+    /// incremented on every breaking change
+    pub major: u32,
+    /// incremented on every compatible change that adds functionality
+    pub minor: u32,
+    /// incremented on every change that neither adds nor removes functionality
+    pub patch: u32,
+}
+
+impl ApiVersion {
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Returns whether a client built for the `required` version can use an api of this version.
+    #[must_use]
+    pub fn satisfies(self, required: Self) -> bool {
+        if self.major != required.major {
+            return false;
+        }
+        if self.major == 0 {
+            // there are no compatibility guarantees before the first stable release
+            return self.minor == required.minor && self.patch >= required.patch;
+        }
+        (self.minor, self.patch) >= (required.minor, required.patch)
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = ApiVersionError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let error = || ApiVersionError(version.to_owned());
+        let mut parts = version.split('.').map(str::parse);
+
+        let (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(error());
+        };
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+impl TryFrom<String> for ApiVersion {
+    type Error = ApiVersionError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ApiVersion> for String {
+    fn from(value: ApiVersion) -> Self {
+        value.to_string()
+    }
+}
+
+/// The given text is not a version number of the form `major.minor.patch`.
+#[derive(Clone, Debug)]
+pub struct ApiVersionError(pub String);
+
+impl Display for ApiVersionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "invalid version {:?}, expected `major.minor.patch`",
+            self.0
+        )
+    }
+}
+
+impl Error for ApiVersionError {}
+
+/// Calculates a hash over everything that affects the communication between client and server.
+///
+/// Captions and descriptions are ignored, so fixing a typo within the documentation won't render
+/// existing clients incompatible.
+pub(super) fn fingerprint(api: &ApiDescriptor) -> u64 {
+    let mut api = api.clone();
+    strip_documentation(&mut api);
+
+    // all map keys are strings, so this cannot fail
+    let json = serde_json::to_vec(&api).unwrap();
+
+    // 64 bit FNV-1a is good enough to detect accidental changes and is stable across platforms
+    json.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn strip_documentation(api: &mut ApiDescriptor) {
+    let empty = || RichText(String::new());
+
+    api.caption = empty();
+    api.description = empty();
+    api.localizations.clear();
+
+    let function_parameters = api.functions.values_mut().flat_map(|function| {
+        function.caption = empty();
+        function.description = empty();
+        function.localizations.clear();
+        function.parameters.iter_mut().chain(&mut function.returns)
+    });
+    let event_parameters = api.events.values_mut().flat_map(|event| {
+        event.caption = empty();
+        event.description = empty();
+        event.localizations.clear();
+        &mut event.parameters
+    });
+
+    for parameter in function_parameters.chain(event_parameters) {
+        parameter.caption = empty();
+        parameter.description = empty();
+        parameter.localizations.clear();
+    }
+}
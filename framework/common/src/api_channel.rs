@@ -1,53 +1,463 @@
+mod json_lines;
 mod native;
+mod queue;
+mod recording;
+mod restricted;
+mod tcp;
 
 // TODO maybe disable this for WASM or move into own platform specific crate?
 // TODO maybe the entire channel stuff should not be in the common crate as there's too much implementation in them
+pub use json_lines::JsonLinesApiServerEndpoint;
 pub use native::{NativeApiClientEndpoint, NativeApiServerEndpoint};
+pub use queue::{
+    BoundedQueue, DEFAULT_QUEUE_CAPACITY, OverflowPolicy, OverflowPolicyError, QueueConfig,
+    QueueMetrics, QueueReceiver, QueueSender, SendError, bounded_channel, busy_answer,
+};
+pub use recording::{
+    RecordEntry, RecordedMessage, RecordingApiServerEndpoint, ReplayApiServerEndpoint,
+    read_recording,
+};
+pub use restricted::RestrictedApiServerEndpoint;
+pub use tcp::{MAX_MESSAGE_SIZE, TcpApiClientEndpoint, TcpApiServerEndpoint};
 
 use crate::{
-    api::{ApiDescriptor, Identifier, Value},
+    api::{ApiDescriptor, ConversionError, Identifier, TypeDescriptor, Value},
     message::{
-        ClientToServerMessage, ErrorResponseMessage, RequestId, RequestMessage, ResponseMessage,
-        ServerToClientMessage,
+        BatchMessage, BatchResponseMessage, ClientToServerMessage, ErrorResponseMessage,
+        EventMessage, HandshakeError, HandshakeMessage, HandshakeResponseMessage, RequestError,
+        RequestId, RequestMessage, RequestStatus, ResponseMessage, ServerToClientMessage,
+        StatusMessage, SubscribeMessage, UnsubscribeMessage,
     },
 };
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+    time::Duration,
+};
 
 /// Handles transmission of commands to [`ApiServerEndpoint`]s and provides methods for polling responses.
+///
+/// All methods sending or polling messages fail with [`ChannelError::Disconnected`] once the server
+/// has gone away.
 pub trait ApiClientEndpoint {
-    fn send_to_server(&self, message: ClientToServerMessage);
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_to_server(&self, message: ClientToServerMessage) -> Result<(), ChannelError>;
 
     #[must_use]
     fn api(&self) -> &ApiDescriptor;
 
-    #[must_use]
-    fn send_command(&self, command: Identifier, arguments: Vec<Value>) -> RequestId {
-        let request = RequestMessage::new(command, arguments);
+    /// Tells the server which revision of the api this client has been built for.
+    ///
+    /// The server will answer with a [`HandshakeResponseMessage`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn handshake(&self) -> Result<(), ChannelError> {
+        let api = self.api();
+        let handshake = HandshakeMessage {
+            api: api.name.clone(),
+            version: api.version,
+            fingerprint: api.fingerprint(),
+        };
+        self.send_to_server(handshake.into())
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the request could not be delivered.
+    fn send_command(
+        &self,
+        command: Identifier,
+        arguments: Vec<Value>,
+    ) -> Result<RequestId, ChannelError> {
+        self.send_command_with_timeout(command, arguments, None)
+    }
+
+    /// Sends a request the server shall give up on if it cannot answer it within `timeout`.
+    ///
+    /// The server will answer with [`RequestError::DeadlineExceeded`] in that case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request could not be delivered.
+    fn send_command_with_timeout(
+        &self,
+        command: Identifier,
+        arguments: Vec<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<RequestId, ChannelError> {
+        let request = RequestMessage::new(command, arguments, timeout);
         let id = request.id;
-        self.send_to_server(request.into());
-        id
+        self.send_to_server(request.into())?;
+        Ok(id)
+    }
+
+    /// Sends several requests the server shall perform one after another.
+    ///
+    /// The server will answer with a single [`BatchResponseMessage`] once all requests have been
+    /// performed or the first one failed. Requests can be created using [`RequestMessage::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch could not be delivered.
+    fn send_batch(&self, requests: Vec<RequestMessage>) -> Result<RequestId, ChannelError> {
+        let batch = BatchMessage::new(requests);
+        let id = batch.id;
+        self.send_to_server(batch.into())?;
+        Ok(id)
+    }
+
+    /// Asks the server to abort a pending request.
+    ///
+    /// The server will answer with [`ServerToClientMessage::Cancelled`] unless the response has
+    /// already been sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn cancel(&self, id: RequestId) -> Result<(), ChannelError> {
+        self.send_to_server(ClientToServerMessage::Cancel(id))
+    }
+
+    /// Asks the server whether a request is still pending.
+    ///
+    /// The server will answer with a [`StatusMessage`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn query(&self, id: RequestId) -> Result<(), ChannelError> {
+        self.send_to_server(ClientToServerMessage::Query(id))
+    }
+
+    /// Asks the server to send [`EventMessage`]s for the given event from now on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn subscribe(&self, event: Identifier) -> Result<(), ChannelError> {
+        self.send_to_server(SubscribeMessage { event }.into())
+    }
+
+    /// Asks the server to stop sending [`EventMessage`]s for the given event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn unsubscribe(&self, event: Identifier) -> Result<(), ChannelError> {
+        self.send_to_server(UnsubscribeMessage { event }.into())
     }
 
+    /// Ends the session; no further messages should be sent afterwards.
+    ///
+    /// The server will cancel all pending requests of this client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered, e.g. because the server has gone
+    /// away already.
+    fn goodbye(&self) -> Result<(), ChannelError> {
+        self.send_to_server(ClientToServerMessage::Goodbye)
+    }
+
+    /// Returns the next message from the server, if any has arrived yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has gone away or sent a malformed message.
+    fn poll_response(&self) -> Result<Option<ServerToClientMessage>, ChannelError>;
+
+    /// Returns statistics about the queue buffering messages from the server, if there's one.
     #[must_use]
-    fn poll_response(&self) -> Option<ServerToClientMessage>;
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        None
+    }
 }
 
 /// Provides methods for polling on requests from a [`ApiClientEndpoint`]s and sending back responses.
+///
+/// All methods sending or polling messages fail with [`ChannelError::Disconnected`] once the client
+/// has gone away.
 pub trait ApiServerEndpoint {
-    fn send_to_client(&self, message: ServerToClientMessage);
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError>;
 
-    fn send_error(&mut self, id: RequestId, message: String) {
-        let response = ErrorResponseMessage { id, message };
-        self.send_to_client(response.into());
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_error(&self, id: RequestId, error: RequestError) -> Result<(), ChannelError> {
+        let response = ErrorResponseMessage { id, error };
+        self.send_to_client(response.into())
     }
 
-    fn send_response(&self, id: RequestId, result: Value) {
+    /// Checks whether a request calls a function of this api with fitting arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found, which should be sent back using [`Self::send_error`].
+    fn check_request(&self, request: &RequestMessage) -> Result<(), RequestError> {
+        let Some(function) = self.api().functions.get(&request.command) else {
+            return Err(RequestError::UnknownFunction {
+                function: request.command.clone(),
+            });
+        };
+        if function.parameters.len() != request.arguments.len() {
+            return Err(RequestError::WrongArgumentCount {
+                function: request.command.clone(),
+                expected: function.parameters.len(),
+                actual: request.arguments.len(),
+            });
+        }
+
+        let mismatch = function
+            .parameters
+            .iter()
+            .zip(&request.arguments)
+            .find(|&(parameter, argument)| !parameter.typ.accepts(argument));
+        let Some((parameter, argument)) = mismatch else {
+            return Ok(());
+        };
+        Err(match (&parameter.typ, argument) {
+            (TypeDescriptor::Integer(range), &Value::Integer(value)) => RequestError::OutOfRange {
+                parameter: parameter.name.clone(),
+                range: range.clone(),
+                value,
+            },
+            _ => RequestError::TypeMismatch {
+                parameter: parameter.name.clone(),
+                expected: Box::new(parameter.typ.clone()),
+                value: Box::new(argument.clone()),
+            },
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_response(&self, id: RequestId, result: Value) -> Result<(), ChannelError> {
         let response = ResponseMessage { id, result };
-        self.send_to_client(response.into());
+        self.send_to_client(response.into())
     }
 
-    #[must_use]
-    fn poll_request(&self) -> Option<ClientToServerMessage>;
+    /// Sends the collected outcomes of a batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_batch_response(
+        &self,
+        id: RequestId,
+        outcomes: Vec<Result<Value, RequestError>>,
+    ) -> Result<(), ChannelError> {
+        self.send_to_client(BatchResponseMessage { id, outcomes }.into())
+    }
+
+    /// Tells the client that a request has been aborted and will not receive a response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_cancelled(&self, id: RequestId) -> Result<(), ChannelError> {
+        self.send_to_client(ServerToClientMessage::Cancelled(id))
+    }
+
+    /// Answers a query about the progress of a request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_status(&self, id: RequestId, status: RequestStatus) -> Result<(), ChannelError> {
+        self.send_to_client(StatusMessage { id, status }.into())
+    }
+
+    /// Checks whether the client's revision of the api is compatible to the one of this server
+    /// and sends back the verdict.
+    ///
+    /// On success, the inner result tells whether the client has been accepted; the reason for
+    /// rejecting the client has been sent to the client as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the verdict could not be delivered.
+    fn answer_handshake(
+        &self,
+        handshake: &HandshakeMessage,
+    ) -> Result<Result<(), HandshakeError>, ChannelError> {
+        let api = self.api();
+        let fingerprint = api.fingerprint();
+
+        let result = if handshake.api != api.name {
+            Err(HandshakeError::ApiMismatch {
+                server: api.name.clone(),
+                client: handshake.api.clone(),
+            })
+        } else if !api.version.satisfies(handshake.version) {
+            Err(HandshakeError::IncompatibleVersion {
+                server: api.version,
+                client: handshake.version,
+            })
+        } else if api.version == handshake.version && fingerprint != handshake.fingerprint {
+            Err(HandshakeError::FingerprintMismatch {
+                version: api.version,
+                server: fingerprint,
+                client: handshake.fingerprint,
+            })
+        } else {
+            Ok(())
+        };
+
+        let response = HandshakeResponseMessage {
+            version: api.version,
+            fingerprint,
+            result: result.clone(),
+        };
+        self.send_to_client(response.into())?;
+        Ok(result)
+    }
+
+    /// Notifies the client about an event.
+    ///
+    /// The caller is responsible for only sending events the client has subscribed to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered.
+    fn send_event(&self, event: Identifier, arguments: Vec<Value>) -> Result<(), ChannelError> {
+        let message = EventMessage { event, arguments };
+        self.send_to_client(message.into())
+    }
+
+    /// Ends the session; no further messages should be sent afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be delivered, e.g. because the client has gone
+    /// away already.
+    fn goodbye(&self) -> Result<(), ChannelError> {
+        self.send_to_client(ServerToClientMessage::Goodbye)
+    }
+
+    /// Returns the next message from the client, if any has arrived yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client has gone away or sent a malformed message.
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError>;
 
     #[must_use]
     fn api(&self) -> &ApiDescriptor;
+
+    /// Returns statistics about the queue buffering messages from the client, if there's one.
+    #[must_use]
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        None
+    }
+}
+
+/// Reasons why a message could not be exchanged with the other endpoint.
+#[derive(Clone, Debug)]
+pub enum ChannelError {
+    /// The other endpoint has gone away, so the session is over
+    Disconnected,
+    /// The other endpoint cannot keep up, so the message has been rejected; the session goes on
+    Busy,
+    /// A message could not be encoded or a received one could not be decoded
+    Malformed(String),
+    /// The underlying transport failed for any other reason
+    Transport(String),
+}
+
+impl Display for ChannelError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => write!(formatter, "the other endpoint has disconnected"),
+            Self::Busy => write!(formatter, "the other endpoint is busy, try again later"),
+            Self::Malformed(reason) => write!(formatter, "malformed message: {reason}"),
+            Self::Transport(reason) => write!(formatter, "transport failed: {reason}"),
+        }
+    }
+}
+
+impl Error for ChannelError {}
+
+impl From<io::Error> for ChannelError {
+    fn from(value: io::Error) -> Self {
+        match value.kind() {
+            io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::UnexpectedEof => Self::Disconnected,
+            io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => {
+                Self::Malformed(value.to_string())
+            }
+            _ => Self::Transport(value.to_string()),
+        }
+    }
+}
+
+/// Reasons why a call to an api function did not produce a result.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The server rejected this client during the handshake
+    Handshake(HandshakeError),
+    /// The server failed to process the request
+    Rejected(RequestError),
+    /// The request has been cancelled before it could be completed
+    Cancelled,
+    /// The server did not respond in time, so the request has been cancelled
+    TimedOut,
+    /// The server responded with a value not matching the return type of the function
+    InvalidResponse(ConversionError),
+    /// The connection to the server has been lost
+    Channel(ChannelError),
+}
+
+impl Display for ApiError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Handshake(error) => write!(formatter, "incompatible api: {error}"),
+            Self::Rejected(error) => write!(formatter, "request failed: {error}"),
+            Self::Cancelled => write!(formatter, "request has been cancelled"),
+            Self::TimedOut => write!(formatter, "request has not been answered in time"),
+            Self::InvalidResponse(error) => write!(formatter, "invalid response: {error}"),
+            Self::Channel(error) => write!(formatter, "connection lost: {error}"),
+        }
+    }
+}
+
+impl Error for ApiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Handshake(error) => Some(error),
+            Self::Rejected(error) => Some(error),
+            Self::Cancelled | Self::TimedOut => None,
+            Self::InvalidResponse(error) => Some(error),
+            Self::Channel(error) => Some(error),
+        }
+    }
+}
+
+impl From<HandshakeError> for ApiError {
+    fn from(value: HandshakeError) -> Self {
+        Self::Handshake(value)
+    }
+}
+
+impl From<ConversionError> for ApiError {
+    fn from(value: ConversionError) -> Self {
+        Self::InvalidResponse(value)
+    }
+}
+
+impl From<ChannelError> for ApiError {
+    fn from(value: ChannelError) -> Self {
+        Self::Channel(value)
+    }
 }
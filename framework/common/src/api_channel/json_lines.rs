@@ -0,0 +1,217 @@
+//! Connects an api server to a controller written in any language via text streams.
+//!
+//! Each line sent to the server is a [`ClientToServerMessage`] and each line sent back is a
+//! [`ServerToClientMessage`], both serialized as JSON by `serde`. Values are tagged with their type:
+//!
+//! ```text
+//! → {"Handshake": {"api": "robot control", "version": "0.2.0", "fingerprint": 1952905696866385078}}
+//! ← {"HandshakeResponse": {"version": "0.2.0", "fingerprint": 1952905696866385078, "result": {"Ok": null}}}
+//! → {"Request": {"id": 1, "command": "move forward", "arguments": [{"Integer": 500}]}}
+//! ← {"Response": {"id": 1, "result": {"Boolean": true}}}
+//! ```
+//!
+//! The first line has to be a `Handshake` naming the api descriptor the controller has been written
+//! for. Until the server has accepted it, every request is answered with
+//! [`RequestError::HandshakeRequired`]; a handshake with a different fingerprint is rejected and
+//! leaves the controller unable to call anything. The fingerprint is a hash over the whole
+//! descriptor, so controllers not written in Rust take it from the line printed by
+//! `cargo api handshake <api.json>` and send that line as it is.
+//!
+//! Request ids are chosen by the client; any number except `0` will do.
+//!
+//! The streams are either the standard input and output of this process (if it has been started by
+//! the controller) or those of a child process running the controller.
+//!
+//! Requests wait in a bounded queue until polled. With [`OverflowPolicy::Block`] the reader stops
+//! reading while the queue is full, so the controller will be blocked once the pipe is full as
+//! well. Requests and batches rejected or dropped by any other policy are answered with
+//! [`RequestError::Busy`] the next time the server polls for requests.
+//!
+//! [`OverflowPolicy::Block`]: super::OverflowPolicy::Block
+//! [`RequestError::Busy`]: crate::message::RequestError::Busy
+//! [`RequestError::HandshakeRequired`]: crate::message::RequestError::HandshakeRequired
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use tracing::warn;
+
+use crate::{
+    api::ApiDescriptor,
+    message::{ClientToServerMessage, ServerToClientMessage},
+};
+
+use super::{
+    ApiServerEndpoint, ChannelError, QueueConfig, QueueMetrics, QueueReceiver, SendError,
+    bounded_channel, queue::BusyAnswers,
+};
+
+/// Provides methods for polling on requests from a controller talking JSON lines and sending back
+/// responses.
+///
+/// Once the controller has closed its output, polling fails with [`ChannelError::Disconnected`].
+pub struct JsonLinesApiServerEndpoint {
+    api: ApiDescriptor,
+    /// Used to send responses to the controller
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// Used to poll for requests which have been read from the controller
+    receiver: QueueReceiver<ClientToServerMessage>,
+    /// Answers to requests lost to the full queue, waiting to be sent to the controller
+    busy_answers: BusyAnswers,
+    /// The controller, if it has been started by this endpoint
+    child: Option<Child>,
+}
+
+impl JsonLinesApiServerEndpoint {
+    /// Reads requests from `reader` and writes responses to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background thread reading the requests could not be started.
+    pub fn new(
+        api: ApiDescriptor,
+        reader: impl BufRead + Send + 'static,
+        writer: impl Write + Send + 'static,
+    ) -> io::Result<Self> {
+        Self::with_queue(api, reader, writer, QueueConfig::default())
+    }
+
+    /// Reads requests from `reader` into a queue configured by `queue` and writes responses to
+    /// `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background thread reading the requests could not be started.
+    pub fn with_queue(
+        api: ApiDescriptor,
+        reader: impl BufRead + Send + 'static,
+        writer: impl Write + Send + 'static,
+        queue: QueueConfig,
+    ) -> io::Result<Self> {
+        let busy_answers = BusyAnswers::default();
+        Ok(Self {
+            api,
+            writer: Mutex::new(Box::new(writer)),
+            receiver: spawn_reader(reader, queue, busy_answers.clone())?,
+            busy_answers,
+            child: None,
+        })
+    }
+
+    /// Talks to the process that started this one via standard input and output.
+    ///
+    /// Nothing else must be written to the standard output (e.g. log messages).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background thread reading the requests could not be started.
+    pub fn stdio(api: ApiDescriptor, queue: QueueConfig) -> io::Result<Self> {
+        Self::with_queue(api, BufReader::new(io::stdin()), io::stdout(), queue)
+    }
+
+    /// Starts the controller as child process and talks to it via its standard input and output.
+    ///
+    /// The standard error of the controller will be inherited. The controller will be killed once
+    /// this endpoint is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller could not be started.
+    pub fn spawn(
+        api: ApiDescriptor,
+        command: &mut Command,
+        queue: QueueConfig,
+    ) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            unreachable!("standard input and output have been piped");
+        };
+
+        let mut endpoint = Self::with_queue(api, BufReader::new(stdout), stdin, queue)?;
+        endpoint.child = Some(child);
+        Ok(endpoint)
+    }
+}
+
+impl ApiServerEndpoint for JsonLinesApiServerEndpoint {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        serde_json::to_writer(&mut *writer, &message).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        while let Some(answer) = self.busy_answers.next() {
+            self.send_to_client(answer)?;
+        }
+        self.receiver.try_recv()
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        &self.api
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(self.receiver.metrics())
+    }
+}
+
+impl Drop for JsonLinesApiServerEndpoint {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            // the controller might have exited already
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+/// Forwards all messages read from the controller to the returned receiver.
+///
+/// Malformed lines will be skipped; the receiver disconnects once the input has been closed.
+/// Messages lost to the full queue are handed to `busy_answers`.
+fn spawn_reader(
+    reader: impl BufRead + Send + 'static,
+    queue: QueueConfig,
+    busy_answers: BusyAnswers,
+) -> io::Result<QueueReceiver<ClientToServerMessage>> {
+    let (sender, receiver) = bounded_channel(queue);
+    thread::Builder::new()
+        .name("api json lines reader".to_owned())
+        .spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(&line) {
+                    Ok(message) => match sender.send(message) {
+                        Ok(None) => {}
+                        Ok(Some(lost))
+                        | Err(SendError {
+                            error: ChannelError::Busy,
+                            message: lost,
+                        }) => {
+                            warn!("dropping a message as the queue is full");
+                            busy_answers.add(&lost);
+                        }
+                        // the endpoint has been dropped
+                        Err(_) => break,
+                    },
+                    Err(error) => warn!("ignoring malformed message {line:?}: {error}"),
+                }
+            }
+        })?;
+    Ok(receiver)
+}
@@ -1,17 +1,12 @@
-#![expect(
-    clippy::panic,
-    clippy::unwrap_used,
-    reason = "TODO fix after experimentation phase"
-)]
-
-use std::sync::mpsc::{Receiver, Sender, TryRecvError};
-
 use crate::{
     api::ApiDescriptor,
     message::{ClientToServerMessage, ServerToClientMessage},
 };
 
-use super::{ApiClientEndpoint, ApiServerEndpoint};
+use super::{
+    ApiClientEndpoint, ApiServerEndpoint, ChannelError, QueueMetrics, QueueReceiver, QueueSender,
+    queue::BusyAnswers,
+};
 
 // this doesn't work in practice as both ends need to live in different threads, but neither end is `Send`
 // /// creates a connected pair of endpoints
@@ -33,67 +28,80 @@ use super::{ApiClientEndpoint, ApiServerEndpoint};
 // }
 
 /// Handles transmission of commands to [`ApiServerEndpoint`]s and provides methods for polling responses.
+///
+/// Both directions are buffered by queues created with [`super::bounded_channel`]; sending fails
+/// with [`ChannelError::Busy`] if the server's queue is full and rejects further requests. Requests
+/// dropped from the server's queue to make room are answered as busy by this endpoint.
 pub struct NativeApiClientEndpoint {
     api: ApiDescriptor,
     /// Used to send requests to the connected [`ApiServerEndpoint`]
-    sender: Sender<ClientToServerMessage>,
+    sender: QueueSender<ClientToServerMessage>,
     /// Used poll for responses from the the connected [`ApiServerEndpoint`]
-    receiver: Receiver<ServerToClientMessage>,
+    receiver: QueueReceiver<ServerToClientMessage>,
+    /// Answers to requests dropped from the server's queue
+    busy_answers: BusyAnswers,
 }
 
 impl NativeApiClientEndpoint {
     #[must_use]
     pub fn new(
         api: ApiDescriptor,
-        sender: Sender<ClientToServerMessage>,
-        receiver: Receiver<ServerToClientMessage>,
+        sender: QueueSender<ClientToServerMessage>,
+        receiver: QueueReceiver<ServerToClientMessage>,
     ) -> Self {
         Self {
             api,
             sender,
             receiver,
+            busy_answers: BusyAnswers::default(),
         }
     }
 }
 
 impl ApiClientEndpoint for NativeApiClientEndpoint {
-    fn send_to_server(&self, message: ClientToServerMessage) {
-        self.sender.send(message).unwrap_or_else(|_| {
-            panic!(
-                "failed to send message to disconnected api server endpoint: `{}`",
-                self.api.name
-            )
-        });
+    fn send_to_server(&self, message: ClientToServerMessage) -> Result<(), ChannelError> {
+        if let Some(dropped) = self.sender.send(message)? {
+            self.busy_answers.add(&dropped);
+        }
+        Ok(())
     }
 
     fn api(&self) -> &ApiDescriptor {
         &self.api
     }
 
-    fn poll_response(&self) -> Option<ServerToClientMessage> {
-        match self.receiver.try_recv() {
-            Ok(message) => Some(message),
-            Err(TryRecvError::Empty) => None,
-            Err(error @ TryRecvError::Disconnected) => panic!("{error}"),
+    fn poll_response(&self) -> Result<Option<ServerToClientMessage>, ChannelError> {
+        if let Some(answer) = self.busy_answers.next() {
+            return Ok(Some(answer));
         }
+        self.receiver.try_recv()
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(self.receiver.metrics())
     }
 }
 
 /// Provides methods for polling on requests from a [`ApiClientEndpoint`]s and sending back responses.
+///
+/// Both directions are buffered by queues created with [`super::bounded_channel`]. Answers to the
+/// client's messages are never lost to a full queue as their number is limited by the requests
+/// accepted before; only events are subject to the queue's overflow policy, so sending one fails
+/// with [`ChannelError::Busy`] if the client's queue is full and rejects it.
 pub struct NativeApiServerEndpoint {
     api: ApiDescriptor,
     /// Used poll for requests from the the connected [`ApiClientEndpoint`]
-    receiver: Receiver<ClientToServerMessage>,
+    receiver: QueueReceiver<ClientToServerMessage>,
     /// Used to send responses to the connected [`ApiClientEndpoint`]
-    sender: Sender<ServerToClientMessage>,
+    sender: QueueSender<ServerToClientMessage>,
 }
 
 impl NativeApiServerEndpoint {
     #[must_use]
     pub fn new(
         api: ApiDescriptor,
-        receiver: Receiver<ClientToServerMessage>,
-        sender: Sender<ServerToClientMessage>,
+        receiver: QueueReceiver<ClientToServerMessage>,
+        sender: QueueSender<ServerToClientMessage>,
     ) -> Self {
         Self {
             api,
@@ -104,19 +112,28 @@ impl NativeApiServerEndpoint {
 }
 
 impl ApiServerEndpoint for NativeApiServerEndpoint {
-    fn send_to_client(&self, message: ServerToClientMessage) {
-        self.sender.send(message).unwrap();
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        if !matches!(message, ServerToClientMessage::Event(_)) {
+            return self.sender.force(message);
+        }
+        match self.sender.send(message)? {
+            // an event made room by dropping an answer, which must not get lost
+            Some(dropped) if !matches!(dropped, ServerToClientMessage::Event(_)) => {
+                self.sender.force(dropped)
+            }
+            Some(_) | None => Ok(()),
+        }
     }
 
-    fn poll_request(&self) -> Option<ClientToServerMessage> {
-        match self.receiver.try_recv() {
-            Ok(message) => Some(message),
-            Err(TryRecvError::Empty) => None,
-            Err(error @ TryRecvError::Disconnected) => panic!("{error}"),
-        }
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        self.receiver.try_recv()
     }
 
     fn api(&self) -> &ApiDescriptor {
         &self.api
     }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(self.receiver.metrics())
+    }
 }
@@ -0,0 +1,586 @@
+//! Bounded queues buffering messages between two endpoints.
+//!
+//! Each queue holds at most [`QueueConfig::capacity`] messages. Once it is full, the
+//! [`OverflowPolicy`] decides what happens to the next message, so a client flooding the server
+//! with requests can neither exhaust the memory nor take down the engine.
+//!
+//! Requests and batches lost to a full queue must still be answered, or the client would wait
+//! forever; see [`busy_answer`]. Answers on their way back are limited by the number of requests
+//! accepted before, so they may exceed the capacity (see [`QueueSender::force`]) instead of getting
+//! lost.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use tracing::{debug, warn};
+
+use crate::message::{
+    BatchResponseMessage, ClientToServerMessage, ErrorResponseMessage, RequestError,
+    ServerToClientMessage,
+};
+
+use super::ChannelError;
+
+/// The number of messages a queue holds unless configured otherwise
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// Decides what happens to a message sent while the queue is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The sender waits until the receiver made room
+    #[default]
+    Block,
+    /// The message is rejected and the sender gets [`ChannelError::Busy`]
+    Reject,
+    /// The oldest message still waiting is discarded to make room
+    DropOldest,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = OverflowPolicyError;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "block" => Ok(Self::Block),
+            "reject" => Ok(Self::Reject),
+            "drop-oldest" => Ok(Self::DropOldest),
+            _ => Err(OverflowPolicyError(policy.to_owned())),
+        }
+    }
+}
+
+/// The given text does not name an [`OverflowPolicy`].
+#[derive(Clone, Debug)]
+pub struct OverflowPolicyError(pub String);
+
+impl Display for OverflowPolicyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "unknown overflow policy {:?}, expected `block`, `reject` or `drop-oldest`",
+            self.0
+        )
+    }
+}
+
+impl Error for OverflowPolicyError {}
+
+/// Limits the number of messages waiting in a queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// The maximum number of messages waiting to be received; at least one message always fits
+    pub capacity: usize,
+    /// What happens to messages sent while the queue is full
+    pub policy: OverflowPolicy,
+}
+
+impl QueueConfig {
+    #[must_use]
+    pub const fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self { capacity, policy }
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUEUE_CAPACITY, OverflowPolicy::default())
+    }
+}
+
+/// Statistics about the messages that passed a queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueMetrics {
+    /// The number of messages currently waiting to be received
+    pub depth: usize,
+    /// The highest number of messages that have been waiting at the same time
+    pub peak_depth: usize,
+    /// The number of messages accepted by the queue
+    pub accepted: u64,
+    /// The number of messages rejected because the queue was full
+    pub rejected: u64,
+    /// The number of messages discarded to make room for newer ones
+    pub dropped: u64,
+}
+
+/// A queue holding a limited number of messages.
+///
+/// This does not block by itself; [`OverflowPolicy::Block`] only tells the owner to wait until
+/// there's room for the message.
+#[derive(Debug)]
+pub struct BoundedQueue<Message> {
+    messages: VecDeque<Message>,
+    config: QueueConfig,
+    metrics: QueueMetrics,
+}
+
+impl<Message> BoundedQueue<Message> {
+    #[must_use]
+    pub const fn new(config: QueueConfig) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            config,
+            metrics: QueueMetrics {
+                depth: 0,
+                peak_depth: 0,
+                accepted: 0,
+                rejected: 0,
+                dropped: 0,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn config(&self) -> QueueConfig {
+        self.config
+    }
+
+    #[must_use]
+    pub fn metrics(&self) -> QueueMetrics {
+        self.metrics
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.messages.len() >= self.config.capacity.max(1)
+    }
+
+    /// Appends a message, applying the overflow policy if the queue is full.
+    ///
+    /// On success, returns the message that has been discarded to make room, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns the message if the queue is full and the policy is not
+    /// [`OverflowPolicy::DropOldest`]. Only rejections by [`OverflowPolicy::Reject`] are counted.
+    pub fn push(&mut self, message: Message) -> Result<Option<Message>, Message> {
+        let mut dropped = None;
+        if self.is_full() {
+            match self.config.policy {
+                OverflowPolicy::Block => return Err(message),
+                OverflowPolicy::Reject => {
+                    self.metrics.rejected += 1;
+                    return Err(message);
+                }
+                OverflowPolicy::DropOldest => {
+                    dropped = self.messages.pop_front();
+                    self.metrics.dropped += 1;
+                }
+            }
+        }
+
+        self.force(message);
+        Ok(dropped)
+    }
+
+    /// Appends a message even if the queue is full, ignoring the overflow policy.
+    ///
+    /// Only meant for messages that must not get lost and whose number is limited otherwise.
+    pub fn force(&mut self, message: Message) {
+        self.messages.push_back(message);
+        self.metrics.accepted += 1;
+        self.metrics.depth = self.messages.len();
+        self.metrics.peak_depth = self.metrics.peak_depth.max(self.metrics.depth);
+    }
+
+    /// Removes the oldest message.
+    pub fn pop(&mut self) -> Option<Message> {
+        let message = self.messages.pop_front();
+        self.metrics.depth = self.messages.len();
+        message
+    }
+}
+
+/// Creates a connected pair of queue ends passing messages from one thread to another.
+#[must_use]
+pub fn bounded_channel<Message>(
+    config: QueueConfig,
+) -> (QueueSender<Message>, QueueReceiver<Message>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: BoundedQueue::new(config),
+            senders: 1,
+            receiving: true,
+        }),
+        not_full: Condvar::new(),
+    });
+    let sender = QueueSender {
+        shared: Arc::clone(&shared),
+    };
+    (sender, QueueReceiver { shared })
+}
+
+struct Shared<Message> {
+    state: Mutex<State<Message>>,
+    /// Signalled whenever a message has been received or the receiver has gone away
+    not_full: Condvar,
+}
+
+struct State<Message> {
+    queue: BoundedQueue<Message>,
+    /// The number of senders still alive
+    senders: usize,
+    /// Whether the receiver is still alive
+    receiving: bool,
+}
+
+impl<Message> Shared<Message> {
+    fn lock(&self) -> MutexGuard<'_, State<Message>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The sending end of a [`bounded_channel`]
+pub struct QueueSender<Message> {
+    shared: Arc<Shared<Message>>,
+}
+
+impl<Message> QueueSender<Message> {
+    /// Appends a message to the queue, applying the overflow policy if it is full.
+    ///
+    /// On success, returns the message that has been discarded to make room, if any; the caller
+    /// is responsible for telling its sender.
+    ///
+    /// # Errors
+    ///
+    /// Returns the message along with [`ChannelError::Busy`] if it has been rejected or
+    /// [`ChannelError::Disconnected`] once the receiver has gone away.
+    pub fn send(&self, mut message: Message) -> Result<Option<Message>, SendError<Message>> {
+        let mut state = self.shared.lock();
+        loop {
+            if !state.receiving {
+                return Err(SendError {
+                    error: ChannelError::Disconnected,
+                    message,
+                });
+            }
+            match state.queue.push(message) {
+                Ok(dropped) => {
+                    if dropped.is_some() {
+                        debug!("queue is full; dropped the oldest message");
+                    }
+                    return Ok(dropped);
+                }
+                Err(rejected) if state.queue.config().policy == OverflowPolicy::Reject => {
+                    debug!("queue is full; rejected the message");
+                    return Err(SendError {
+                        error: ChannelError::Busy,
+                        message: rejected,
+                    });
+                }
+                Err(unsent) => {
+                    message = unsent;
+                    state = self
+                        .shared
+                        .not_full
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+    }
+
+    /// Appends a message even if the queue is full, ignoring the overflow policy.
+    ///
+    /// Only meant for messages that must not get lost and whose number is limited otherwise, like
+    /// the answers to requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChannelError::Disconnected`] once the receiver has gone away.
+    pub fn force(&self, message: Message) -> Result<(), ChannelError> {
+        let mut state = self.shared.lock();
+        if !state.receiving {
+            return Err(ChannelError::Disconnected);
+        }
+        state.queue.force(message);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn metrics(&self) -> QueueMetrics {
+        self.shared.lock().queue.metrics()
+    }
+}
+
+impl<Message> Clone for QueueSender<Message> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<Message> Drop for QueueSender<Message> {
+    fn drop(&mut self) {
+        self.shared.lock().senders -= 1;
+    }
+}
+
+/// A message that could not be sent by a [`QueueSender`]
+#[derive(Debug)]
+pub struct SendError<Message> {
+    /// Why the message has not been sent
+    pub error: ChannelError,
+    /// The message that has not been sent
+    pub message: Message,
+}
+
+impl<Message> From<SendError<Message>> for ChannelError {
+    fn from(error: SendError<Message>) -> Self {
+        error.error
+    }
+}
+
+/// The receiving end of a [`bounded_channel`]
+pub struct QueueReceiver<Message> {
+    shared: Arc<Shared<Message>>,
+}
+
+impl<Message> QueueReceiver<Message> {
+    /// Returns the oldest message, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChannelError::Disconnected`] once the queue is empty and all senders have gone
+    /// away.
+    pub fn try_recv(&self) -> Result<Option<Message>, ChannelError> {
+        let mut state = self.shared.lock();
+        match state.queue.pop() {
+            Some(message) => {
+                self.shared.not_full.notify_one();
+                Ok(Some(message))
+            }
+            None if state.senders == 0 => Err(ChannelError::Disconnected),
+            None => Ok(None),
+        }
+    }
+
+    #[must_use]
+    pub fn metrics(&self) -> QueueMetrics {
+        self.shared.lock().queue.metrics()
+    }
+}
+
+impl<Message> Drop for QueueReceiver<Message> {
+    fn drop(&mut self) {
+        self.shared.lock().receiving = false;
+        self.shared.not_full.notify_all();
+    }
+}
+
+/// Returns the answer telling the client that a message has been rejected or dropped for being
+/// sent while the queue was full.
+///
+/// Only requests and batches expect an answer; all other messages are lost silently.
+#[must_use]
+pub fn busy_answer(message: &ClientToServerMessage) -> Option<ServerToClientMessage> {
+    let answer = match message {
+        ClientToServerMessage::Request(request) => ErrorResponseMessage {
+            id: request.id,
+            error: RequestError::Busy,
+        }
+        .into(),
+        ClientToServerMessage::Batch(batch) => BatchResponseMessage {
+            id: batch.id,
+            outcomes: vec![Err(RequestError::Busy)],
+        }
+        .into(),
+        ClientToServerMessage::Handshake(_)
+        | ClientToServerMessage::Cancel(_)
+        | ClientToServerMessage::Query(_)
+        | ClientToServerMessage::Subscribe(_)
+        | ClientToServerMessage::Unsubscribe(_)
+        | ClientToServerMessage::Goodbye => {
+            warn!("dropping message sent while the queue was full: {message:?}");
+            return None;
+        }
+    };
+    Some(answer)
+}
+
+/// Answers to requests lost to a full queue, waiting to be sent back by the endpoint owning the
+/// queue
+#[derive(Clone, Default)]
+pub(crate) struct BusyAnswers(Arc<Mutex<VecDeque<ServerToClientMessage>>>);
+
+impl BusyAnswers {
+    /// Remembers to tell the client that its message has been lost to a full queue.
+    pub(crate) fn add(&self, message: &ClientToServerMessage) {
+        if let Some(answer) = busy_answer(message) {
+            self.lock().push_back(answer);
+        }
+    }
+
+    /// Returns the oldest answer not yet sent, if any.
+    pub(crate) fn next(&self) -> Option<ServerToClientMessage> {
+        self.lock().pop_front()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<ServerToClientMessage>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::{
+        BoundedQueue, OverflowPolicy, QueueConfig, QueueMetrics, SendError, bounded_channel,
+        busy_answer,
+    };
+    use crate::{
+        api::{Identifier, Value},
+        api_channel::ChannelError,
+        message::{
+            BatchMessage, BatchResponseMessage, ClientToServerMessage, ErrorResponseMessage,
+            RequestError, RequestMessage, ServerToClientMessage,
+        },
+    };
+
+    fn full_queue(policy: OverflowPolicy) -> BoundedQueue<u8> {
+        let mut queue = BoundedQueue::new(QueueConfig::new(2, policy));
+        assert_eq!(queue.push(1), Ok(None));
+        assert_eq!(queue.push(2), Ok(None));
+        assert!(queue.is_full());
+        queue
+    }
+
+    #[test]
+    fn block_leaves_a_full_queue_alone() {
+        let mut queue = full_queue(OverflowPolicy::Block);
+        assert_eq!(queue.push(3), Err(3));
+        assert_eq!(queue.metrics().rejected, 0);
+        assert_eq!(queue.metrics().dropped, 0);
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.push(3), Ok(None));
+    }
+
+    #[test]
+    fn reject_counts_rejected_messages() {
+        let mut queue = full_queue(OverflowPolicy::Reject);
+        assert_eq!(queue.push(3), Err(3));
+        assert_eq!(queue.metrics().rejected, 1);
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn drop_oldest_returns_the_dropped_message() {
+        let mut queue = full_queue(OverflowPolicy::DropOldest);
+        assert_eq!(queue.push(3), Ok(Some(1)));
+        assert_eq!(queue.metrics().dropped, 1);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+    }
+
+    #[test]
+    fn at_least_one_message_fits() {
+        let mut queue = BoundedQueue::new(QueueConfig::new(0, OverflowPolicy::Reject));
+        assert_eq!(queue.push(1), Ok(None));
+        assert_eq!(queue.push(2), Err(2));
+    }
+
+    #[test]
+    fn forced_messages_exceed_the_capacity() {
+        let mut queue = full_queue(OverflowPolicy::Reject);
+        queue.force(3);
+        assert_eq!(queue.metrics().depth, 3);
+        assert_eq!(queue.metrics().rejected, 0);
+        assert_eq!(queue.pop(), Some(1));
+    }
+
+    #[test]
+    fn metrics_track_the_messages() {
+        let mut queue = full_queue(OverflowPolicy::DropOldest);
+        assert_eq!(queue.push(3), Ok(Some(1)));
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(
+            queue.metrics(),
+            QueueMetrics {
+                depth: 1,
+                peak_depth: 2,
+                accepted: 3,
+                rejected: 0,
+                dropped: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn blocked_sender_waits_for_the_receiver() {
+        let (sender, receiver) = bounded_channel(QueueConfig::new(1, OverflowPolicy::Block));
+        sender.send(1).unwrap();
+        let blocked = thread::spawn(move || sender.send(2).map_err(|error| error.error));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(receiver.metrics().depth, 1);
+
+        assert_eq!(receiver.try_recv().unwrap(), Some(1));
+        assert_eq!(blocked.join().unwrap().unwrap(), None);
+        assert_eq!(receiver.try_recv().unwrap(), Some(2));
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(ChannelError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn rejected_message_is_returned_to_the_sender() {
+        let (sender, receiver) = bounded_channel(QueueConfig::new(1, OverflowPolicy::Reject));
+        sender.send(1).unwrap();
+        let Err(SendError { error, message }) = sender.send(2) else {
+            unreachable!("the queue is full");
+        };
+        assert!(matches!(error, ChannelError::Busy));
+        assert_eq!(message, 2);
+        assert_eq!(sender.metrics(), receiver.metrics());
+        assert_eq!(receiver.metrics().rejected, 1);
+    }
+
+    #[test]
+    fn sending_fails_once_the_receiver_has_gone() {
+        let (sender, receiver) = bounded_channel(QueueConfig::new(1, OverflowPolicy::Block));
+        sender.send(1).unwrap();
+        let blocked = thread::spawn(move || sender.send(2).map_err(|error| error.error));
+        thread::sleep(Duration::from_millis(50));
+        drop(receiver);
+        assert!(matches!(
+            blocked.join().unwrap(),
+            Err(ChannelError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn busy_answers_requests() {
+        let request = RequestMessage::new(Identifier("jump".into()), vec![Value::Integer(1)], None);
+        let request_id = request.id;
+        let Some(ServerToClientMessage::ErrorResponse(ErrorResponseMessage { id, error })) =
+            busy_answer(&request.into())
+        else {
+            unreachable!("requests expect an answer");
+        };
+        assert_eq!(id, request_id);
+        assert_eq!(error, RequestError::Busy);
+        assert!(busy_answer(&ClientToServerMessage::Goodbye).is_none());
+    }
+
+    #[test]
+    fn busy_answers_batches() {
+        let batch = BatchMessage::new(Vec::new());
+        let batch_id = batch.id;
+        let Some(ServerToClientMessage::BatchResponse(BatchResponseMessage { id, outcomes })) =
+            busy_answer(&batch.into())
+        else {
+            unreachable!("batches expect an answer");
+        };
+        assert_eq!(id, batch_id);
+        assert_eq!(outcomes, [Err(RequestError::Busy)]);
+    }
+}
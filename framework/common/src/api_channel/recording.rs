@@ -0,0 +1,289 @@
+//! Records the traffic of an api server endpoint and plays it back later.
+//!
+//! A recording is a text file containing one [`RecordEntry`] per line, serialized as JSON by
+//! `serde`. Each entry carries the tick and the wall-clock time at which the message passed the
+//! endpoint.
+//!
+//! Ticks are counted by the endpoint itself: the runtime drains all pending requests once per
+//! engine tick, so each poll not returning a request completes a tick. Replaying a recording thus
+//! hands each request to the engine at the very same tick as in the original run.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{
+    api::ApiDescriptor,
+    message::{ClientToServerMessage, RequestError, RequestMessage, ServerToClientMessage},
+};
+
+use super::{ApiServerEndpoint, ChannelError, QueueMetrics};
+
+/// A single message that passed a [`RecordingApiServerEndpoint`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordEntry {
+    /// The number of ticks completed before the message passed the endpoint
+    pub tick: u64,
+    /// The wall-clock time at which the message passed the endpoint
+    pub time: SystemTime,
+    /// The message and the direction it went
+    pub message: RecordedMessage,
+}
+
+/// A message together with the direction it went
+#[derive(Debug, Serialize, Deserialize)]
+pub enum RecordedMessage {
+    /// A message polled from the client
+    FromClient(ClientToServerMessage),
+    /// A message sent to the client
+    ToClient(ServerToClientMessage),
+}
+
+/// Serializes just like [`RecordEntry`] without taking ownership of the message
+#[derive(Serialize)]
+struct RecordEntryRef<'message> {
+    tick: u64,
+    time: SystemTime,
+    message: RecordedMessageRef<'message>,
+}
+
+/// Serializes just like [`RecordedMessage`] without taking ownership of the message
+#[derive(Serialize)]
+enum RecordedMessageRef<'message> {
+    FromClient(&'message ClientToServerMessage),
+    ToClient(&'message ServerToClientMessage),
+}
+
+/// Reads all entries of a recording.
+///
+/// # Errors
+///
+/// Returns an error if the recording could not be read or contains a malformed line.
+pub fn read_recording(reader: impl BufRead) -> io::Result<Vec<RecordEntry>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            serde_json::from_str(&line?).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {error}", index + 1),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Wraps any [`ApiServerEndpoint`] and writes all messages passing it to a recording.
+///
+/// Failing to write the recording does not affect the traffic; the problem is logged instead.
+pub struct RecordingApiServerEndpoint<Endpoint: ApiServerEndpoint> {
+    inner: Endpoint,
+    /// Receives the recorded entries
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// The number of ticks completed so far
+    tick: AtomicU64,
+}
+
+impl<Endpoint: ApiServerEndpoint> RecordingApiServerEndpoint<Endpoint> {
+    /// Records all messages passing `inner` to `writer`.
+    pub fn new(inner: Endpoint, writer: impl Write + Send + 'static) -> Self {
+        Self {
+            inner,
+            writer: Mutex::new(Box::new(writer)),
+            tick: AtomicU64::new(0),
+        }
+    }
+
+    /// Records all messages passing `inner` to a newly created file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be created.
+    pub fn create(inner: Endpoint, path: &Path) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::new(inner, BufWriter::new(file)))
+    }
+
+    /// Returns the endpoint being recorded.
+    #[must_use]
+    pub fn inner(&self) -> &Endpoint {
+        &self.inner
+    }
+
+    fn record(&self, message: RecordedMessageRef<'_>) {
+        let entry = RecordEntryRef {
+            tick: self.tick.load(Ordering::Relaxed),
+            time: SystemTime::now(),
+            message,
+        };
+
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = serde_json::to_writer(&mut *writer, &entry)
+            .map_err(io::Error::from)
+            .and_then(|()| writer.write_all(b"\n"))
+            .and_then(|()| writer.flush());
+        if let Err(error) = result {
+            warn!("failed to record message: {error}");
+        }
+    }
+}
+
+impl<Endpoint: ApiServerEndpoint> ApiServerEndpoint for RecordingApiServerEndpoint<Endpoint> {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        self.record(RecordedMessageRef::ToClient(&message));
+        self.inner.send_to_client(message)
+    }
+
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        let Some(message) = self.inner.poll_request()? else {
+            self.tick.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        };
+        self.record(RecordedMessageRef::FromClient(&message));
+        Ok(Some(message))
+    }
+
+    fn check_request(&self, request: &RequestMessage) -> Result<(), RequestError> {
+        self.inner.check_request(request)
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        self.inner.api()
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        self.inner.queue_metrics()
+    }
+}
+
+/// Plays the role of the client by feeding the requests of a recording back into the engine.
+///
+/// Each request is polled at the tick it has been recorded at. The messages sent back are compared
+/// to the recorded ones; each difference is logged and counted as divergence.
+pub struct ReplayApiServerEndpoint {
+    api: ApiDescriptor,
+    state: Mutex<ReplayState>,
+}
+
+struct ReplayState {
+    /// The number of ticks completed so far
+    tick: u64,
+    /// Recorded messages from the client along with their tick, yet to be polled
+    requests: VecDeque<(u64, ClientToServerMessage)>,
+    /// Recorded messages to the client, yet to be compared to the actual ones
+    responses: VecDeque<ServerToClientMessage>,
+    /// The number of messages to the client which did not match the recording
+    divergences: usize,
+}
+
+impl ReplayApiServerEndpoint {
+    /// Replays the given entries of a recording.
+    pub fn new(api: ApiDescriptor, entries: impl IntoIterator<Item = RecordEntry>) -> Self {
+        let mut requests = VecDeque::new();
+        let mut responses = VecDeque::new();
+        for entry in entries {
+            match entry.message {
+                RecordedMessage::FromClient(message) => requests.push_back((entry.tick, message)),
+                RecordedMessage::ToClient(message) => responses.push_back(message),
+            }
+        }
+
+        Self {
+            api,
+            state: Mutex::new(ReplayState {
+                tick: 0,
+                requests,
+                responses,
+                divergences: 0,
+            }),
+        }
+    }
+
+    /// Replays the recording stored in the given file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording could not be read or is malformed.
+    pub fn open(api: ApiDescriptor, path: &Path) -> io::Result<Self> {
+        let entries = read_recording(BufReader::new(File::open(path)?))?;
+        Ok(Self::new(api, entries))
+    }
+
+    /// Returns whether all recorded requests have been polled.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.lock().requests.is_empty()
+    }
+
+    /// Returns the number of messages sent to the client which did not match the recording.
+    ///
+    /// Missing messages are not counted until [`Self::is_finished`] and the engine had time to
+    /// answer the last request.
+    #[must_use]
+    pub fn divergences(&self) -> usize {
+        let state = self.lock();
+        state.divergences + state.responses.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ReplayState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ApiServerEndpoint for ReplayApiServerEndpoint {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        let mut state = self.lock();
+        let tick = state.tick;
+        let Some(expected) = state.responses.pop_front() else {
+            warn!("replay diverged at tick {tick}: unexpected message {message:?}");
+            state.divergences += 1;
+            return Ok(());
+        };
+
+        // compare the serialized form, as messages do not implement `PartialEq`
+        let matches = serde_json::to_value(&expected).ok() == serde_json::to_value(&message).ok();
+        if matches {
+            debug!("replay matched at tick {tick}: {message:?}");
+        } else {
+            warn!("replay diverged at tick {tick}: expected {expected:?} but got {message:?}");
+            state.divergences += 1;
+        }
+        Ok(())
+    }
+
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        let mut state = self.lock();
+        let tick = state.tick;
+        let due = state
+            .requests
+            .front()
+            .is_some_and(|&(request_tick, _)| request_tick <= tick);
+        if !due {
+            state.tick += 1;
+            return Ok(None);
+        }
+
+        let message = state.requests.pop_front().map(|(_, message)| message);
+        if state.requests.is_empty() {
+            info!("replay: all recorded requests have been sent at tick {tick}");
+        }
+        Ok(message)
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        &self.api
+    }
+}
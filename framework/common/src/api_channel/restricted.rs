@@ -0,0 +1,198 @@
+//! Restricts the functions a client may call to a set of [`Capabilities`].
+
+use tracing::debug;
+
+use crate::{
+    api::{ApiDescriptor, Capabilities, Identifier},
+    message::{
+        ClientToServerMessage, ErrorResponseMessage, RequestError, RequestMessage,
+        ServerToClientMessage,
+    },
+};
+
+use super::{ApiServerEndpoint, ChannelError, QueueMetrics};
+
+/// Wraps any [`ApiServerEndpoint`] and rejects all requests calling a function not permitted by
+/// the capabilities.
+///
+/// Rejected requests are answered with [`RequestError::NotPermitted`] right away and never reach
+/// the engine. Batches are passed on as they are; the server checks each of their requests using
+/// [`ApiServerEndpoint::check_request`] once it reaches it, so the requests before a forbidden one
+/// are performed and the error becomes the outcome of the forbidden request itself.
+///
+/// The endpoint still describes the complete api, so clients knowing all functions pass the
+/// handshake as well as clients generated for the restricted api.
+pub struct RestrictedApiServerEndpoint<Endpoint: ApiServerEndpoint> {
+    inner: Endpoint,
+    capabilities: Capabilities,
+}
+
+impl<Endpoint: ApiServerEndpoint> RestrictedApiServerEndpoint<Endpoint> {
+    #[must_use]
+    pub fn new(inner: Endpoint, capabilities: Capabilities) -> Self {
+        Self {
+            inner,
+            capabilities,
+        }
+    }
+
+    #[must_use]
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Returns the endpoint being restricted.
+    #[must_use]
+    pub fn inner(&self) -> &Endpoint {
+        &self.inner
+    }
+
+    /// Returns the error for a function not being permitted.
+    fn check(&self, function: &Identifier) -> Result<(), RequestError> {
+        if self.capabilities.permits(function) {
+            return Ok(());
+        }
+        Err(RequestError::NotPermitted {
+            function: function.clone(),
+            capabilities: self.capabilities.name.clone(),
+        })
+    }
+
+    /// Returns the answer rejecting the message if it calls a function not being permitted.
+    fn reject(&self, message: &ClientToServerMessage) -> Option<ServerToClientMessage> {
+        let ClientToServerMessage::Request(request) = message else {
+            return None;
+        };
+        let error = self.check(&request.command).err()?;
+        Some(
+            ErrorResponseMessage {
+                id: request.id,
+                error,
+            }
+            .into(),
+        )
+    }
+}
+
+impl<Endpoint: ApiServerEndpoint> ApiServerEndpoint for RestrictedApiServerEndpoint<Endpoint> {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        self.inner.send_to_client(message)
+    }
+
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        while let Some(message) = self.inner.poll_request()? {
+            let Some(answer) = self.reject(&message) else {
+                return Ok(Some(message));
+            };
+            debug!("rejecting {message:?} as it is not permitted");
+            self.inner.send_to_client(answer)?;
+        }
+        Ok(None)
+    }
+
+    fn check_request(&self, request: &RequestMessage) -> Result<(), RequestError> {
+        self.check(&request.command)?;
+        self.inner.check_request(request)
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        self.inner.api()
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        self.inner.queue_metrics()
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic, reason = "unexpected messages fail the test")]
+mod tests {
+    use super::RestrictedApiServerEndpoint;
+    use crate::{
+        api::{ApiDescriptor, Capabilities, Identifier, Value},
+        api_channel::{
+            ApiServerEndpoint, NativeApiServerEndpoint, QueueConfig, QueueReceiver, QueueSender,
+            bounded_channel,
+        },
+        message::{
+            BatchMessage, ClientToServerMessage, ErrorResponseMessage, RequestError,
+            RequestMessage, ServerToClientMessage,
+        },
+    };
+
+    struct Connection {
+        to_server: QueueSender<ClientToServerMessage>,
+        to_client: QueueReceiver<ServerToClientMessage>,
+        endpoint: RestrictedApiServerEndpoint<NativeApiServerEndpoint>,
+    }
+
+    /// Connects to an endpoint only permitting to move forward.
+    fn connect() -> Connection {
+        let json = include_bytes!("../../../../applications/robot/control.api.json");
+        let api = ApiDescriptor::load(json).unwrap();
+        let capabilities = Capabilities::new(
+            Identifier("lesson 1".into()),
+            &api,
+            [Identifier("move forward".into())],
+        )
+        .unwrap();
+        let (to_server, from_client) = bounded_channel(QueueConfig::default());
+        let (to_client, from_server) = bounded_channel(QueueConfig::default());
+        let inner = NativeApiServerEndpoint::new(api, from_client, to_client);
+        Connection {
+            to_server,
+            to_client: from_server,
+            endpoint: RestrictedApiServerEndpoint::new(inner, capabilities),
+        }
+    }
+
+    fn request(function: &'static str) -> RequestMessage {
+        RequestMessage::new(Identifier(function.into()), vec![Value::Integer(500)], None)
+    }
+
+    fn not_permitted(function: &'static str) -> RequestError {
+        RequestError::NotPermitted {
+            function: Identifier(function.into()),
+            capabilities: Identifier("lesson 1".into()),
+        }
+    }
+
+    #[test]
+    fn forbidden_requests_are_answered_right_away() {
+        let connection = connect();
+        let forbidden = request("jump");
+        let forbidden_id = forbidden.id;
+        connection.to_server.send(forbidden.into()).unwrap();
+
+        assert!(connection.endpoint.poll_request().unwrap().is_none());
+        let Some(ServerToClientMessage::ErrorResponse(ErrorResponseMessage { id, error })) =
+            connection.to_client.try_recv().unwrap()
+        else {
+            panic!("expected the request to be rejected");
+        };
+        assert_eq!(id, forbidden_id);
+        assert_eq!(error, not_permitted("jump"));
+    }
+
+    #[test]
+    fn batches_are_checked_request_by_request() {
+        let connection = connect();
+        let requests = vec![request("move forward"), request("jump")];
+        connection
+            .to_server
+            .send(BatchMessage::new(requests).into())
+            .unwrap();
+
+        let Some(ClientToServerMessage::Batch(batch)) = connection.endpoint.poll_request().unwrap()
+        else {
+            panic!("expected the batch to be passed on");
+        };
+        assert!(connection.to_client.try_recv().unwrap().is_none());
+        let outcomes = batch
+            .requests
+            .iter()
+            .map(|request| connection.endpoint.check_request(request))
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes, [Ok(()), Err(not_permitted("jump"))]);
+    }
+}
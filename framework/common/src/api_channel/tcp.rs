@@ -0,0 +1,308 @@
+//! Connects api endpoints living in different processes via TCP.
+//!
+//! Each message is encoded by `bincode` (standard configuration) via `serde` – just like on the way
+//! between the browser's workers – and prefixed by its length as a little endian `u32`.
+//!
+//! Incoming messages are read by a background thread per connection, so polling never blocks. They
+//! wait in a bounded queue until polled. With [`OverflowPolicy::Block`] the reader stops reading
+//! while the queue is full, so the peer will be slowed down by TCP's flow control. Requests and
+//! batches rejected or dropped by any other policy are answered with [`RequestError::Busy`] the
+//! next time the server polls for requests; responses lost by the client are logged only.
+//!
+//! [`OverflowPolicy::Block`]: super::OverflowPolicy::Block
+//! [`RequestError::Busy`]: crate::message::RequestError::Busy
+
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    thread,
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+use tracing::warn;
+
+use crate::{
+    api::ApiDescriptor,
+    message::{ClientToServerMessage, ServerToClientMessage},
+};
+
+use super::{
+    ApiClientEndpoint, ApiServerEndpoint, ChannelError, QueueConfig, QueueMetrics, QueueReceiver,
+    SendError, bounded_channel, queue::BusyAnswers,
+};
+
+/// Messages exceeding this size are considered to be garbage and terminate the connection.
+pub const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Handles transmission of commands to an [`ApiServerEndpoint`] on the other end of a TCP connection.
+pub struct TcpApiClientEndpoint {
+    api: ApiDescriptor,
+    /// Used to send requests to the connected [`ApiServerEndpoint`]
+    stream: TcpStream,
+    /// Used to poll for responses which have been read from the connection
+    receiver: QueueReceiver<Result<ServerToClientMessage, ChannelError>>,
+}
+
+impl TcpApiClientEndpoint {
+    /// Connects to an api server listening at the given address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be established.
+    pub fn connect(api: ApiDescriptor, address: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(api, TcpStream::connect(address)?)
+    }
+
+    /// Uses an already established connection to talk to an api server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be prepared for reading in the background.
+    pub fn new(api: ApiDescriptor, stream: TcpStream) -> io::Result<Self> {
+        Self::with_queue(api, stream, QueueConfig::default())
+    }
+
+    /// Uses an already established connection to talk to an api server, buffering responses in a
+    /// queue configured by `queue`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be prepared for reading in the background.
+    pub fn with_queue(
+        api: ApiDescriptor,
+        stream: TcpStream,
+        queue: QueueConfig,
+    ) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let receiver = spawn_reader(stream.try_clone()?, queue, |_lost| {
+            warn!("dropping a message from the server as the queue is full");
+        })?;
+        Ok(Self {
+            api,
+            stream,
+            receiver,
+        })
+    }
+}
+
+impl ApiClientEndpoint for TcpApiClientEndpoint {
+    fn send_to_server(&self, message: ClientToServerMessage) -> Result<(), ChannelError> {
+        Ok(write_message(&self.stream, &message)?)
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        &self.api
+    }
+
+    fn poll_response(&self) -> Result<Option<ServerToClientMessage>, ChannelError> {
+        poll(&self.receiver)
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(self.receiver.metrics())
+    }
+}
+
+impl Drop for TcpApiClientEndpoint {
+    fn drop(&mut self) {
+        // also stops the reader thread; the connection might already be closed by the server
+        self.stream.shutdown(Shutdown::Both).ok();
+    }
+}
+
+/// Provides methods for polling on requests from an [`ApiClientEndpoint`] on the other end of a
+/// TCP connection and sending back responses.
+///
+pub struct TcpApiServerEndpoint {
+    api: ApiDescriptor,
+    /// Used to send responses to the connected [`ApiClientEndpoint`]
+    stream: TcpStream,
+    /// Used to poll for requests which have been read from the connection
+    receiver: QueueReceiver<Result<ClientToServerMessage, ChannelError>>,
+    /// Answers to requests lost to the full queue, waiting to be sent to the client
+    busy_answers: BusyAnswers,
+    /// The address of the connected client
+    peer: SocketAddr,
+}
+
+impl TcpApiServerEndpoint {
+    /// Waits for the next client to connect to the listener.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no connection could be accepted.
+    pub fn accept(api: ApiDescriptor, listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        Self::new(api, stream)
+    }
+
+    /// Serves an already established connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be prepared for reading in the background.
+    pub fn new(api: ApiDescriptor, stream: TcpStream) -> io::Result<Self> {
+        Self::with_queue(api, stream, QueueConfig::default())
+    }
+
+    /// Serves an already established connection, buffering requests in a queue configured by
+    /// `queue`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be prepared for reading in the background.
+    pub fn with_queue(
+        api: ApiDescriptor,
+        stream: TcpStream,
+        queue: QueueConfig,
+    ) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let peer = stream.peer_addr()?;
+        let busy_answers = BusyAnswers::default();
+        let lost_answers = busy_answers.clone();
+        let receiver = spawn_reader(stream.try_clone()?, queue, move |lost| {
+            warn!("dropping a message from {peer} as the queue is full");
+            if let Ok(message) = lost {
+                lost_answers.add(&message);
+            }
+        })?;
+        Ok(Self {
+            api,
+            stream,
+            receiver,
+            busy_answers,
+            peer,
+        })
+    }
+
+    /// Returns the address of the connected client.
+    #[must_use]
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer
+    }
+}
+
+impl ApiServerEndpoint for TcpApiServerEndpoint {
+    fn send_to_client(&self, message: ServerToClientMessage) -> Result<(), ChannelError> {
+        Ok(write_message(&self.stream, &message)?)
+    }
+
+    fn poll_request(&self) -> Result<Option<ClientToServerMessage>, ChannelError> {
+        while let Some(answer) = self.busy_answers.next() {
+            self.send_to_client(answer)?;
+        }
+        poll(&self.receiver)
+    }
+
+    fn api(&self) -> &ApiDescriptor {
+        &self.api
+    }
+
+    fn queue_metrics(&self) -> Option<QueueMetrics> {
+        Some(self.receiver.metrics())
+    }
+}
+
+impl Drop for TcpApiServerEndpoint {
+    fn drop(&mut self) {
+        // also stops the reader thread; the connection might already be closed by the client
+        self.stream.shutdown(Shutdown::Both).ok();
+    }
+}
+
+/// Forwards all messages read from the connection to the returned receiver.
+///
+/// The receiver disconnects once the connection has been closed. Any other problem will be
+/// forwarded as well before the connection is abandoned. Messages lost to the full queue are
+/// handed to `lost`.
+fn spawn_reader<Message>(
+    mut stream: TcpStream,
+    queue: QueueConfig,
+    lost: impl Fn(Result<Message, ChannelError>) + Send + 'static,
+) -> io::Result<QueueReceiver<Result<Message, ChannelError>>>
+where
+    Message: DeserializeOwned + Send + 'static,
+{
+    let (sender, receiver) = bounded_channel(queue);
+    thread::Builder::new()
+        .name("api connection reader".to_owned())
+        .spawn(move || {
+            loop {
+                let message = match read_message(&mut stream) {
+                    Ok(message) => Ok(message),
+                    Err(error) => match ChannelError::from(error) {
+                        ChannelError::Disconnected => break,
+                        error => Err(error),
+                    },
+                };
+                let failed = message.is_err();
+                match sender.send(message) {
+                    Ok(None) => {}
+                    Ok(Some(message))
+                    | Err(SendError {
+                        error: ChannelError::Busy,
+                        message,
+                    }) => lost(message),
+                    // the endpoint has been dropped
+                    Err(_) => break,
+                }
+                if failed {
+                    // the connection is unusable
+                    break;
+                }
+            }
+        })?;
+    Ok(receiver)
+}
+
+/// Returns the next message read from the connection, if any.
+fn poll<Message>(
+    receiver: &QueueReceiver<Result<Message, ChannelError>>,
+) -> Result<Option<Message>, ChannelError> {
+    receiver.try_recv()?.transpose()
+}
+
+/// Writes a single length-prefixed message.
+fn write_message(mut stream: &TcpStream, message: &impl Serialize) -> io::Result<()> {
+    let bytes = bincode::serde::encode_to_vec(message, bincode::config::standard())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    if bytes.len() > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("message of {} bytes exceeds the size limit", bytes.len()),
+        ));
+    }
+    let length = u32::try_from(bytes.len())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let mut frame = Vec::with_capacity(4 + bytes.len());
+    frame.extend_from_slice(&length.to_le_bytes());
+    frame.extend_from_slice(&bytes);
+    stream.write_all(&frame)
+}
+
+/// Blocks until a complete length-prefixed message has been read.
+fn read_message<Message: DeserializeOwned>(stream: &mut TcpStream) -> io::Result<Message> {
+    let mut length = [0; 4];
+    stream.read_exact(&mut length)?;
+    let length = usize::try_from(u32::from_le_bytes(length))
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    if length > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {length} bytes exceeds the size limit"),
+        ));
+    }
+
+    let mut bytes = vec![0; length];
+    stream.read_exact(&mut bytes)?;
+    let (message, read) = bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    if read != length {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message has {} trailing bytes", length - read),
+        ));
+    }
+    Ok(message)
+}
@@ -1,26 +1,60 @@
 //! Contains the types of messages that can be sent between endpoints.
 //! Each endpoint creates a single mpsc channel in order to receive commands or events from a single endpoints.
 
-use crate::api::{Identifier, Value};
+use crate::api::{
+    ApiVersion, ConversionError, Identifier, TypeDescriptor, Value, dispatch::DispatchError,
+};
 use serde::{Deserialize, Serialize};
 use std::{
+    error::Error,
     fmt::Display,
     num::{NonZeroU128, TryFromIntError},
+    ops::Range,
+    time::Duration,
 };
 
 /// Any message that can be sent from a client to a server
-///
-/// This might be extended in the future in order to query or cancel a pending request
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ClientToServerMessage {
+    Handshake(HandshakeMessage),
     Request(RequestMessage),
+    Subscribe(SubscribeMessage),
+    Unsubscribe(UnsubscribeMessage),
+    /// Asks the server to abort a pending request.
+    ///
+    /// The server answers with [`ServerToClientMessage::Cancelled`] instead of a response if the
+    /// request was still pending. Otherwise the response has already been sent and the
+    /// cancellation will be ignored.
+    Cancel(RequestId),
+    /// Asks the server whether a request is still pending.
+    ///
+    /// The server answers with a [`StatusMessage`].
+    Query(RequestId),
+    /// Ends the session; the client will not send any further messages.
+    ///
+    /// All pending requests of the client will be cancelled without notice.
+    Goodbye,
+    /// Asks the server to perform several requests one after another.
+    ///
+    /// The server answers with a single [`BatchResponseMessage`].
+    Batch(BatchMessage),
 }
 
 /// Any message that can be sent from a server to a client
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ServerToClientMessage {
+    HandshakeResponse(HandshakeResponseMessage),
     Response(ResponseMessage),
     ErrorResponse(ErrorResponseMessage),
+    Event(EventMessage),
+    /// The request has been aborted on behalf of the client and will not receive a response.
+    Cancelled(RequestId),
+    /// Answers a [`ClientToServerMessage::Query`].
+    Status(StatusMessage),
+    /// Ends the session; the server will not answer any further requests.
+    Goodbye,
+    /// Answers a [`ClientToServerMessage::Batch`].
+    BatchResponse(BatchResponseMessage),
 }
 
 /// UUID to associate all messages with the initial request
@@ -48,6 +82,80 @@ impl Display for RequestId {
     }
 }
 
+/// Tells the receiver which revision of the api the sender has been built for.
+///
+/// This should be the first message a client sends.
+/// The server will answer with a [`HandshakeResponseMessage`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandshakeMessage {
+    /// The technical name of the api the client expects
+    pub api: Identifier,
+    /// The version of the api the client expects
+    pub version: ApiVersion,
+    /// The fingerprint of the api descriptor the client has been built with
+    pub fingerprint: u64,
+}
+
+/// Tells a client whether the server accepts its revision of the api.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandshakeResponseMessage {
+    /// The version of the api the server provides
+    pub version: ApiVersion,
+    /// The fingerprint of the api descriptor the server has been built with
+    pub fingerprint: u64,
+    /// Either acceptance of the client or the reason for rejecting it
+    pub result: Result<(), HandshakeError>,
+}
+
+/// Reasons for a server to reject a client's [`HandshakeMessage`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum HandshakeError {
+    /// The client expects an entirely different api
+    ApiMismatch {
+        server: Identifier,
+        client: Identifier,
+    },
+    /// The client requires a version the server is not compatible with
+    IncompatibleVersion {
+        server: ApiVersion,
+        client: ApiVersion,
+    },
+    /// Both claim the same version, but the api descriptors differ
+    FingerprintMismatch {
+        version: ApiVersion,
+        server: u64,
+        client: u64,
+    },
+}
+
+impl Display for HandshakeError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ApiMismatch { server, client } => write!(
+                formatter,
+                "the client expects the api `{client}` but the server provides `{server}`"
+            ),
+            Self::IncompatibleVersion { server, client } => write!(
+                formatter,
+                "the client requires version {client} of the api but the server provides \
+                 the incompatible version {server}"
+            ),
+            Self::FingerprintMismatch {
+                version,
+                server,
+                client,
+            } => write!(
+                formatter,
+                "client and server both claim version {version} of the api, but their \
+                 descriptors differ (fingerprints {client:016x} and {server:016x}); \
+                 the version should have been increased"
+            ),
+        }
+    }
+}
+
+impl Error for HandshakeError {}
+
 /// Asks the receiver to perform an operation and return a response containing the result.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RequestMessage {
@@ -57,19 +165,63 @@ pub struct RequestMessage {
     pub command: Identifier,
     /// The list of arguments to be passed to the called function
     pub arguments: Vec<Value>,
+    /// The time the server may take to answer, starting when it receives the request.
+    ///
+    /// Once this deadline has passed, the server cancels the request and answers with
+    /// [`RequestError::DeadlineExceeded`].
+    #[serde(default)]
+    pub timeout: Option<Duration>,
 }
 
 impl RequestMessage {
+    /// Creates a request with a random id.
     #[must_use]
-    pub(crate) fn new(command: Identifier, arguments: Vec<Value>) -> Self {
+    pub fn new(command: Identifier, arguments: Vec<Value>, timeout: Option<Duration>) -> Self {
         Self {
             id: RequestId::new_random(),
             command,
             arguments,
+            timeout,
         }
     }
 }
 
+/// Asks the receiver to perform a sequence of requests in the given order.
+///
+/// Each request is started once the previous one has been answered successfully; the first
+/// failure skips all remaining requests. The answers to the individual requests are not sent on
+/// their own, but collected into a single [`BatchResponseMessage`].
+///
+/// Cancelling the batch by its id cancels the request currently being performed as well.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchMessage {
+    /// UUID used to track corresponding messages
+    pub id: RequestId,
+    /// The requests to be performed, each with an id of its own
+    pub requests: Vec<RequestMessage>,
+}
+
+impl BatchMessage {
+    #[must_use]
+    pub(crate) fn new(requests: Vec<RequestMessage>) -> Self {
+        Self {
+            id: RequestId::new_random(),
+            requests,
+        }
+    }
+}
+
+/// Contains the outcomes of all requests of a batch that have been performed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResponseMessage {
+    /// this shall match the id of the corresponding batch
+    pub id: RequestId,
+    /// The outcome of each request in the order they have been performed.
+    ///
+    /// Only the last one may be an error; requests following a failed one are left out.
+    pub outcomes: Vec<Result<Value, RequestError>>,
+}
+
 /// Contains the result of a requested operation
 // TODO find a better name to reflect a positive result value (e.g. `OkResultMessage` or `OkResponseMessage`)
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,9 +243,185 @@ pub struct ResponseMessage {
 pub struct ErrorResponseMessage {
     /// this shall match the id of the corresponding request
     pub id: RequestId,
-    /// A readable description of what went wrong
-    // TODO maybe this could be an enum of known error causes
-    pub message: String,
+    /// What went wrong
+    pub error: RequestError,
+}
+
+/// Known causes for a request to fail.
+///
+/// The [`Display`] implementation provides a readable description.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RequestError {
+    /// The api does not provide a function with this name
+    UnknownFunction { function: Identifier },
+    /// The number of arguments does not match the number of parameters
+    WrongArgumentCount {
+        function: Identifier,
+        expected: usize,
+        actual: usize,
+    },
+    /// An argument does not have the parameter's type
+    TypeMismatch {
+        parameter: Identifier,
+        expected: Box<TypeDescriptor>,
+        value: Box<Value>,
+    },
+    /// An integer argument lies outside of the parameter's range
+    OutOfRange {
+        parameter: Identifier,
+        range: Range<i64>,
+        value: i64,
+    },
+    /// The server is still processing another request and cannot accept this one
+    Busy,
+    /// The request has been cancelled before it could be completed
+    Cancelled,
+    /// The engine refused to perform the request
+    EngineRejected { reason: String },
+    /// The request has not been answered before its deadline
+    DeadlineExceeded,
+    /// The function exists, but the client's capabilities do not permit calling it
+    NotPermitted {
+        function: Identifier,
+        capabilities: Identifier,
+    },
+    /// The client has not completed a successful handshake yet
+    HandshakeRequired,
+}
+
+impl Display for RequestError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFunction { function } => {
+                write!(formatter, "unknown function `{function}`")
+            }
+            Self::WrongArgumentCount {
+                function,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "function `{function}` takes {expected} arguments but {actual} were given"
+            ),
+            Self::TypeMismatch {
+                parameter,
+                expected,
+                value,
+            } => write!(
+                formatter,
+                "argument `{parameter}` must be a value of {expected:?} but got {value:?}"
+            ),
+            Self::OutOfRange {
+                parameter,
+                range,
+                value,
+            } => write!(
+                formatter,
+                "argument `{parameter}` must be within {range:?} but got {value}"
+            ),
+            Self::Busy => write!(formatter, "the server is busy with another request"),
+            Self::Cancelled => write!(formatter, "request has been cancelled"),
+            Self::EngineRejected { reason } => write!(formatter, "request rejected: {reason}"),
+            Self::DeadlineExceeded => write!(formatter, "request has not been answered in time"),
+            Self::NotPermitted {
+                function,
+                capabilities,
+            } => write!(
+                formatter,
+                "function `{function}` is not available in `{capabilities}`"
+            ),
+            Self::HandshakeRequired => {
+                write!(
+                    formatter,
+                    "the client has not completed a successful handshake"
+                )
+            }
+        }
+    }
+}
+
+impl Error for RequestError {}
+
+impl From<DispatchError> for RequestError {
+    fn from(error: DispatchError) -> Self {
+        match error {
+            DispatchError::UnknownFunction(function) => Self::UnknownFunction { function },
+            DispatchError::WrongArgumentCount {
+                function,
+                expected,
+                actual,
+            } => Self::WrongArgumentCount {
+                function,
+                expected,
+                actual,
+            },
+            DispatchError::InvalidArgument {
+                parameter,
+                error: ConversionError::TypeMismatch { expected, value },
+            } => Self::TypeMismatch {
+                parameter,
+                expected,
+                value,
+            },
+            DispatchError::InvalidArgument {
+                parameter,
+                error: ConversionError::OutOfRange { range, value },
+            } => Self::OutOfRange {
+                parameter,
+                range,
+                value,
+            },
+        }
+    }
+}
+
+/// Tells a client about the progress of one of its requests.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusMessage {
+    /// this shall match the id of the queried request
+    pub id: RequestId,
+    /// Whether the request is still being processed
+    pub status: RequestStatus,
+}
+
+/// The progress of a request as seen by the server
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RequestStatus {
+    /// The request has been received but not been answered yet
+    Pending,
+    /// The request has already been answered or cancelled, or has never been received
+    Unknown,
+}
+
+/// Asks the receiver to send [`EventMessage`]s whenever the given event occurs.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscribeMessage {
+    /// The name of the event as declared by the api
+    pub event: Identifier,
+}
+
+/// Asks the receiver to stop sending [`EventMessage`]s for the given event.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnsubscribeMessage {
+    /// The name of the event as declared by the api
+    pub event: Identifier,
+}
+
+/// Notifies a subscribed client that an event has occurred.
+///
+/// Events are not related to any request and will not be answered.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventMessage {
+    /// The name of the event as declared by the api
+    pub event: Identifier,
+    /// The payload of this event
+    pub arguments: Vec<Value>,
+}
+
+impl From<HandshakeResponseMessage> for ServerToClientMessage {
+    fn from(value: HandshakeResponseMessage) -> Self {
+        Self::HandshakeResponse(value)
+    }
 }
 
 impl From<ErrorResponseMessage> for ServerToClientMessage {
@@ -108,12 +436,54 @@ impl From<ResponseMessage> for ServerToClientMessage {
     }
 }
 
+impl From<EventMessage> for ServerToClientMessage {
+    fn from(value: EventMessage) -> Self {
+        Self::Event(value)
+    }
+}
+
+impl From<StatusMessage> for ServerToClientMessage {
+    fn from(value: StatusMessage) -> Self {
+        Self::Status(value)
+    }
+}
+
+impl From<BatchResponseMessage> for ServerToClientMessage {
+    fn from(value: BatchResponseMessage) -> Self {
+        Self::BatchResponse(value)
+    }
+}
+
+impl From<HandshakeMessage> for ClientToServerMessage {
+    fn from(value: HandshakeMessage) -> Self {
+        Self::Handshake(value)
+    }
+}
+
 impl From<RequestMessage> for ClientToServerMessage {
     fn from(value: RequestMessage) -> Self {
         Self::Request(value)
     }
 }
 
+impl From<SubscribeMessage> for ClientToServerMessage {
+    fn from(value: SubscribeMessage) -> Self {
+        Self::Subscribe(value)
+    }
+}
+
+impl From<UnsubscribeMessage> for ClientToServerMessage {
+    fn from(value: UnsubscribeMessage) -> Self {
+        Self::Unsubscribe(value)
+    }
+}
+
+impl From<BatchMessage> for ClientToServerMessage {
+    fn from(value: BatchMessage) -> Self {
+        Self::Batch(value)
+    }
+}
+
 /// A little helper for the common case that a command's result might have a third `Future` state,
 /// indicating the command has been accepted but the actual result will arrive later.
 ///
@@ -0,0 +1,231 @@
+//! Emits the module accompanying an api trait.
+
+use crate::parse::{Api, Documentation, Function, Parameter, trait_name};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemTrait;
+
+pub(crate) fn api(api: &Api, item: &ItemTrait) -> TokenStream {
+    let trait_ident = &item.ident;
+    let visibility = &item.vis;
+    let module = format_ident!("{}", trait_name(trait_ident).replace(' ', "_"));
+    let module_doc = format!("Reflection and dispatching for [`{trait_ident}`]");
+
+    let descriptor = descriptor(api);
+    let dispatch = dispatch(api, trait_ident);
+
+    quote! {
+        #item
+
+        #[doc = #module_doc]
+        #visibility mod #module {
+            #[allow(
+                unused_imports,
+                clippy::wildcard_imports,
+                reason = "the parameter types are declared in the parent module"
+            )]
+            use super::*;
+
+            #descriptor
+
+            #dispatch
+        }
+    }
+}
+
+fn descriptor(api: &Api) -> TokenStream {
+    let Api {
+        ref name,
+        version: [major, minor, patch],
+        ref documentation,
+        ref functions,
+    } = *api;
+
+    let (caption, description) = rich_texts(documentation);
+    let functions = functions.iter().map(function_descriptor);
+
+    quote! {
+        /// Returns the descriptor of this api.
+        #[must_use]
+        pub fn descriptor() -> ::gam3du_framework_common::api::ApiDescriptor {
+            use ::gam3du_framework_common::api::{ApiDescriptor, ApiVersion, Identifier};
+
+            ApiDescriptor {
+                name: Identifier(::std::borrow::Cow::Borrowed(#name)),
+                version: ApiVersion::new(#major, #minor, #patch),
+                caption: #caption,
+                description: #description,
+                functions: [#(#functions),*].into_iter().collect(),
+                events: Default::default(),
+                localizations: Default::default(),
+            }
+        }
+    }
+}
+
+fn function_descriptor(function: &Function) -> TokenStream {
+    let Function {
+        ident: _,
+        ref name,
+        ref documentation,
+        ref parameters,
+        ref returns,
+    } = *function;
+
+    let (caption, description) = rich_texts(documentation);
+    let parameters = parameters.iter().map(parameter_descriptor);
+    let returns = returns.as_ref().map_or_else(
+        || quote!(None),
+        |typ| {
+            quote! {
+                Some(::gam3du_framework_common::api::ParameterDescriptor {
+                    name: Identifier(::std::borrow::Cow::Borrowed("return")),
+                    caption: ::gam3du_framework_common::api::RichText(String::new()),
+                    description: ::gam3du_framework_common::api::RichText(String::new()),
+                    typ: <#typ as ::gam3du_framework_common::api::ApiValue>::type_descriptor(),
+                    default: None,
+                    localizations: Default::default(),
+                })
+            }
+        },
+    );
+
+    quote! {
+        (
+            Identifier(::std::borrow::Cow::Borrowed(#name)),
+            ::gam3du_framework_common::api::FunctionDescriptor {
+                name: Identifier(::std::borrow::Cow::Borrowed(#name)),
+                caption: #caption,
+                description: #description,
+                parameters: vec![#(#parameters),*],
+                returns: #returns,
+                localizations: Default::default(),
+            },
+        )
+    }
+}
+
+fn parameter_descriptor(parameter: &Parameter) -> TokenStream {
+    let Parameter {
+        ref name,
+        ref documentation,
+        ref typ,
+        ref range,
+        ref default,
+    } = *parameter;
+
+    let (caption, description) = rich_texts(documentation);
+    let typ_descriptor = range.as_ref().map_or_else(
+        || quote!(<#typ as ::gam3du_framework_common::api::ApiValue>::type_descriptor()),
+        |range| quote!(::gam3du_framework_common::api::TypeDescriptor::Integer(#range)),
+    );
+    let default = default.as_ref().map_or_else(
+        || quote!(None),
+        |default| {
+            quote!(Some(<#typ as ::gam3du_framework_common::api::ApiValue>::into_value(#default)))
+        },
+    );
+
+    quote! {
+        ::gam3du_framework_common::api::ParameterDescriptor {
+            name: Identifier(::std::borrow::Cow::Borrowed(#name)),
+            caption: #caption,
+            description: #description,
+            typ: #typ_descriptor,
+            default: #default,
+            localizations: Default::default(),
+        }
+    }
+}
+
+fn dispatch(api: &Api, trait_ident: &syn::Ident) -> TokenStream {
+    let arms = api.functions.iter().map(dispatch_arm);
+
+    quote! {
+        /// Calls the trait method matching the given function name and returns its result.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the function is unknown or the arguments don't match its
+        /// parameters.
+        pub fn dispatch<Target: super::#trait_ident + ?Sized>(
+            target: &mut Target,
+            function: &::gam3du_framework_common::api::Identifier,
+            arguments: Vec<::gam3du_framework_common::api::Value>,
+        ) -> Result<
+            ::gam3du_framework_common::api::Value,
+            ::gam3du_framework_common::api::dispatch::DispatchError,
+        > {
+            use ::gam3du_framework_common::api::{
+                ApiValue, Value,
+                dispatch::{DispatchError, argument, arguments as expect_arguments},
+            };
+
+            match function.as_ref() {
+                #(#arms)*
+                _ => Err(DispatchError::UnknownFunction(function.clone())),
+            }
+        }
+    }
+}
+
+fn dispatch_arm(function: &Function) -> TokenStream {
+    let Function {
+        ref ident,
+        ref name,
+        documentation: _,
+        ref parameters,
+        ref returns,
+    } = *function;
+
+    let count = parameters.len();
+    let variables = (0..count)
+        .map(|index| format_ident!("argument_{index}"))
+        .collect::<Vec<_>>();
+    let conversions = parameters
+        .iter()
+        .zip(&variables)
+        .map(|(parameter, variable)| {
+            let Parameter {
+                name: ref parameter_name,
+                ref typ,
+                ref range,
+                ..
+            } = *parameter;
+            let range = range
+                .as_ref()
+                .map_or_else(|| quote!(None), |range| quote!(Some(#range)));
+            quote! {
+                let #variable = argument::<#typ>(#parameter_name, #range, #variable)?;
+            }
+        });
+
+    let call = quote!(target.#ident(#(#variables),*));
+    let result = if returns.is_some() {
+        quote!(Ok(ApiValue::into_value(#call)))
+    } else {
+        quote! {
+            #call;
+            Ok(Value::Unit)
+        }
+    };
+
+    quote! {
+        #name => {
+            let [#(#variables),*] = expect_arguments::<#count>(#name, arguments)?;
+            #(#conversions)*
+            #result
+        }
+    }
+}
+
+fn rich_texts(documentation: &Documentation) -> (TokenStream, TokenStream) {
+    let Documentation {
+        ref caption,
+        ref description,
+    } = *documentation;
+    (
+        quote!(::gam3du_framework_common::api::RichText(String::from(#caption))),
+        quote!(::gam3du_framework_common::api::RichText(String::from(#description))),
+    )
+}
@@ -0,0 +1,56 @@
+//! Procedural macros deriving api descriptors and their glue code from annotated Rust code.
+
+mod generate;
+mod parse;
+
+use proc_macro::TokenStream;
+use syn::{ItemTrait, parse_macro_input};
+
+/// Turns a trait into the single source of truth for an api.
+///
+/// Next to the unchanged trait, a module named after the trait (in snake case) will be generated
+/// containing:
+/// - `descriptor()` returning the `ApiDescriptor` of the api. Captions and descriptions are taken
+///   from the doc comments of the trait and its methods.
+/// - `dispatch(target, function, arguments)` which converts the arguments of a request into their
+///   native types, calls the corresponding trait method on `target` and converts the result back
+///   into a `Value`.
+///
+/// All parameter and return types need to implement `ApiValue`.
+///
+/// ```ignore
+/// /// Moves a robot across a plane
+/// #[api(name = "robot plugin", version = "0.1.0")]
+/// pub trait EngineApi {
+///     /// Moves the robot one tile forward
+///     ///
+///     /// Returns `false` if the robot was blocked.
+///     fn move_forward(
+///         &mut self,
+///         #[api(caption = "draw a line while moving")] draw: bool,
+///         #[api(caption = "duration in milliseconds", range = 0..10_000, default = 500)]
+///         duration: u64,
+///     ) -> bool;
+/// }
+/// ```
+///
+/// Supported attribute arguments:
+/// - on the trait: `name` (defaults to the trait's name) and `version` (defaults to `0.0.0`)
+/// - on methods: `name` (defaults to the method's name)
+/// - on parameters: `name`, `caption`, `description`, `range` (integers only) and `default`
+///
+/// Technical names are derived from Rust identifiers by replacing underscores with spaces.
+#[proc_macro_attribute]
+pub fn api(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let mut config = parse::ApiConfig::default();
+    let parser = syn::meta::parser(|meta| config.parse(&meta));
+    parse_macro_input!(attribute with parser);
+
+    let mut item = parse_macro_input!(item as ItemTrait);
+
+    parse::api(config, &mut item)
+        .map_or_else(syn::Error::into_compile_error, |api| {
+            generate::api(&api, &item)
+        })
+        .into()
+}
@@ -0,0 +1,293 @@
+//! Collects everything needed to describe an api from an annotated trait.
+//!
+//! All `#[api(…)]` attributes will be removed from the trait while doing so.
+
+use proc_macro2::Span;
+use syn::{
+    Attribute, Expr, ExprLit, FnArg, Ident, ItemTrait, Lit, LitStr, Meta, Pat, ReturnType,
+    TraitItem, TraitItemFn, Type, meta::ParseNestedMeta,
+};
+
+pub(crate) struct Api {
+    pub(crate) name: String,
+    pub(crate) version: [u32; 3],
+    pub(crate) documentation: Documentation,
+    pub(crate) functions: Vec<Function>,
+}
+
+pub(crate) struct Function {
+    pub(crate) ident: Ident,
+    pub(crate) name: String,
+    pub(crate) documentation: Documentation,
+    pub(crate) parameters: Vec<Parameter>,
+    pub(crate) returns: Option<Type>,
+}
+
+pub(crate) struct Parameter {
+    pub(crate) name: String,
+    pub(crate) documentation: Documentation,
+    pub(crate) typ: Type,
+    pub(crate) range: Option<Expr>,
+    pub(crate) default: Option<Expr>,
+}
+
+#[derive(Default)]
+pub(crate) struct Documentation {
+    pub(crate) caption: String,
+    pub(crate) description: String,
+}
+
+#[derive(Default)]
+pub(crate) struct ApiConfig {
+    name: Option<LitStr>,
+    version: Option<LitStr>,
+}
+
+impl ApiConfig {
+    pub(crate) fn parse(&mut self, meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("version") {
+            self.version = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `name` or `version`"))
+        }
+    }
+}
+
+pub(crate) fn api(config: ApiConfig, item: &mut ItemTrait) -> syn::Result<Api> {
+    let name = match config.name {
+        Some(name) => checked_name(&name.value(), name.span())?,
+        None => trait_name(&item.ident),
+    };
+
+    let version = match config.version {
+        Some(version) => parse_version(&version)?,
+        None => [0, 0, 0],
+    };
+
+    let mut functions = Vec::new();
+    for trait_item in &mut item.items {
+        match *trait_item {
+            TraitItem::Fn(ref mut method) => functions.push(function(method)?),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    trait_item,
+                    "only methods are supported within an api trait",
+                ));
+            }
+        }
+    }
+
+    Ok(Api {
+        name,
+        version,
+        documentation: documentation(&item.attrs),
+        functions,
+    })
+}
+
+fn function(method: &mut TraitItemFn) -> syn::Result<Function> {
+    let mut name = None;
+    for attribute in take_api_attributes(&mut method.attrs) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(checked_name(&value.value(), value.span())?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name`"))
+            }
+        })?;
+    }
+
+    let signature = &mut method.sig;
+    if !signature.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &signature.generics,
+            "api methods may not be generic",
+        ));
+    }
+
+    let mut inputs = signature.inputs.iter_mut();
+    match inputs.next() {
+        Some(&mut FnArg::Receiver(ref receiver)) if receiver.reference.is_some() => {}
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &signature.ident,
+                "api methods need to take `&self` or `&mut self`",
+            ));
+        }
+    }
+
+    let parameters = inputs
+        .map(|input| match *input {
+            FnArg::Typed(ref mut typed) => parameter(&mut typed.attrs, &typed.pat, &typed.ty),
+            FnArg::Receiver(_) => unreachable!("only the first argument may be a receiver"),
+        })
+        .collect::<syn::Result<_>>()?;
+
+    let returns = match signature.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ref typ) => Some((**typ).clone()),
+    };
+
+    let ident = signature.ident.clone();
+    Ok(Function {
+        name: match name {
+            Some(name) => name,
+            None => checked_name(&ident.to_string().replace('_', " "), ident.span())?,
+        },
+        ident,
+        documentation: documentation(&method.attrs),
+        parameters,
+        returns,
+    })
+}
+
+fn parameter(attributes: &mut Vec<Attribute>, pat: &Pat, typ: &Type) -> syn::Result<Parameter> {
+    let Pat::Ident(ref pat) = *pat else {
+        return Err(syn::Error::new_spanned(
+            pat,
+            "api parameters need to be plain identifiers",
+        ));
+    };
+
+    let mut name = None;
+    let mut documentation = Documentation::default();
+    let mut range = None;
+    let mut default = None;
+
+    for attribute in take_api_attributes(attributes) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(checked_name(&value.value(), value.span())?);
+            } else if meta.path.is_ident("caption") {
+                documentation.caption = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("description") {
+                documentation.description = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("range") {
+                range = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+            } else {
+                return Err(
+                    meta.error("expected `name`, `caption`, `description`, `range` or `default`")
+                );
+            }
+            Ok(())
+        })?;
+    }
+
+    if documentation.description.is_empty() {
+        documentation.description.clone_from(&documentation.caption);
+    }
+
+    Ok(Parameter {
+        name: match name {
+            Some(name) => name,
+            None => checked_name(&pat.ident.to_string().replace('_', " "), pat.ident.span())?,
+        },
+        documentation,
+        typ: typ.clone(),
+        range,
+        default,
+    })
+}
+
+/// Removes all `#[api(…)]` attributes as those are meant for this macro only.
+fn take_api_attributes(attributes: &mut Vec<Attribute>) -> Vec<Attribute> {
+    let (api_attributes, others) = attributes
+        .drain(..)
+        .partition(|attribute| attribute.path().is_ident("api"));
+    *attributes = others;
+    api_attributes
+}
+
+/// Uses the first paragraph of the doc comments as caption and all of it as description.
+fn documentation(attributes: &[Attribute]) -> Documentation {
+    let lines = attributes
+        .iter()
+        .filter_map(|attribute| match attribute.meta {
+            Meta::NameValue(ref name_value) if name_value.path.is_ident("doc") => {
+                match name_value.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(ref line),
+                        ..
+                    }) => Some(line.value()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>();
+
+    let description = lines.join("\n").trim().to_owned();
+    let caption = description
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Documentation {
+        caption,
+        description,
+    }
+}
+
+/// Splits a trait name like `EngineApi` into `engine api`.
+pub(crate) fn trait_name(ident: &Ident) -> String {
+    let mut name = String::new();
+    for character in ident.to_string().chars() {
+        if character.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.extend(character.to_lowercase());
+    }
+    name
+}
+
+/// Makes sure the name is a valid technical name of the api.
+fn checked_name(name: &str, span: Span) -> syn::Result<String> {
+    let is_valid = !name.is_empty()
+        && !name.starts_with(' ')
+        && !name.ends_with(' ')
+        && !name.contains("  ")
+        && name
+            .chars()
+            .all(|character| matches!(character, 'a'..='z' | '0'..='9' | ' '));
+
+    if is_valid {
+        Ok(name.to_owned())
+    } else {
+        Err(syn::Error::new(
+            span,
+            format!(
+                "{name:?} is not a valid identifier (only `a-z`, `0-9` and single spaces are allowed)"
+            ),
+        ))
+    }
+}
+
+fn parse_version(version: &LitStr) -> syn::Result<[u32; 3]> {
+    let value = version.value();
+    let parts = value
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<Vec<u32>, _>>();
+
+    match parts.as_deref() {
+        Ok(&[major, minor, patch]) => Ok([major, minor, patch]),
+        _ => Err(syn::Error::new(
+            version.span(),
+            "expected a version of the form `major.minor.patch`",
+        )),
+    }
+}
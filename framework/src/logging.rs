@@ -26,8 +26,11 @@ fn target_filter() -> filter::Targets {
 
 #[cfg(not(target_family = "wasm"))]
 pub fn init_logger() {
-    // A layer that logs events to stdout using the human-readable "pretty" format.
-    let logger = tracing_subscriber::fmt::layer().pretty();
+    // A layer that logs events to stderr using the human-readable "pretty" format.
+    // stdout is kept free for controllers talking to the application via standard output.
+    let logger = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_writer(std::io::stderr);
 
     tracing_subscriber::registry()
         .with(logger)
@@ -0,0 +1,165 @@
+// --- api client runtime (shared by all generated api modules) ---
+
+/** A value exchanged with the server, tagged with its type */
+export type Value =
+    | "Unit"
+    | { Integer: number }
+    | { Float: number }
+    | { Boolean: boolean }
+    | { String: string }
+    | { List: Value[] }
+    | { Tuple: Value[] }
+    | { Record: { [field: string]: Value } }
+    | { Enumeration: string }
+    | { Optional: Value | null };
+
+/** Describes the set of valid values for a parameter */
+export type TypeDescriptor =
+    | { Integer: [number, number] | { start: number; end: number } }
+    | "Float"
+    | "Boolean"
+    | "String"
+    | { List: TypeDescriptor }
+    | { Tuple: TypeDescriptor[] }
+    | { Record: { [field: string]: TypeDescriptor } }
+    | { Enumeration: string[] }
+    | { Optional: TypeDescriptor };
+
+export interface ParameterDescriptor {
+    name: string;
+    caption: string;
+    description: string;
+    type: TypeDescriptor;
+    default: Value | null;
+}
+
+export interface FunctionDescriptor {
+    name: string;
+    caption: string;
+    description: string;
+    parameters: ParameterDescriptor[];
+    returns: ParameterDescriptor | null;
+}
+
+export interface EventDescriptor {
+    name: string;
+    caption: string;
+    description: string;
+    parameters: ParameterDescriptor[];
+}
+
+export interface ApiDescriptor {
+    name: string;
+    version: string;
+    caption: string;
+    description: string;
+    functions: { [name: string]: FunctionDescriptor };
+    events: { [name: string]: EventDescriptor };
+}
+
+export type HandshakeError =
+    | { ApiMismatch: { server: string; client: string } }
+    | { IncompatibleVersion: { server: string; client: string } }
+    | { FingerprintMismatch: { version: string; server: bigint; client: bigint } };
+
+/** Known causes for a request to fail */
+export type RequestError =
+    | { UnknownFunction: { function: string } }
+    | { WrongArgumentCount: { function: string; expected: number; actual: number } }
+    | { TypeMismatch: { parameter: string; expected: TypeDescriptor; value: Value } }
+    | { OutOfRange: { parameter: string; range: { start: number; end: number }; value: number } }
+    | "Busy"
+    | "Cancelled"
+    | { EngineRejected: { reason: string } }
+    | "DeadlineExceeded"
+    | { NotPermitted: { function: string; capabilities: string } }
+    | "HandshakeRequired";
+
+/** The progress of a request as seen by the server */
+export type RequestStatus = "Pending" | "Unknown";
+
+/** Asks the server to call a function, either on its own or as part of a batch */
+export type RequestMessage = {
+    id: bigint;
+    command: string;
+    arguments: Value[];
+    timeout: { secs: bigint; nanos: number } | null;
+};
+
+export type ClientToServerMessage =
+    | { Handshake: { api: string; version: string; fingerprint: bigint } }
+    | { Request: RequestMessage }
+    | { Subscribe: { event: string } }
+    | { Unsubscribe: { event: string } }
+    | { Cancel: bigint }
+    | { Query: bigint }
+    | "Goodbye"
+    | { Batch: { id: bigint; requests: RequestMessage[] } };
+
+export type ServerToClientMessage =
+    | {
+          HandshakeResponse: {
+              version: string;
+              fingerprint: bigint;
+              result: { Ok: null } | { Err: HandshakeError };
+          };
+      }
+    | { Response: { id: bigint; result: Value } }
+    | { ErrorResponse: { id: bigint; error: RequestError } }
+    | { Event: { event: string; arguments: Value[] } }
+    | { Cancelled: bigint }
+    | { Status: { id: bigint; status: RequestStatus } }
+    | "Goodbye"
+    | {
+          BatchResponse: {
+              id: bigint;
+              outcomes: ({ Ok: Value } | { Err: RequestError })[];
+          };
+      };
+
+export function encodeClientToServerMessage(message: ClientToServerMessage): Uint8Array;
+export function decodeClientToServerMessage(bytes: ArrayLike<number>): ClientToServerMessage;
+export function encodeServerToClientMessage(message: ServerToClientMessage): Uint8Array;
+export function decodeServerToClientMessage(bytes: ArrayLike<number>): ServerToClientMessage;
+
+/** Converts a native JavaScript value into a tagged value of the given type. */
+export function toValue(type: TypeDescriptor, native: unknown, path?: string): Value;
+/** Converts a tagged value into its native JavaScript representation. */
+export function fromValue(value: Value): unknown;
+
+/** Rejects a call the server could not perform. */
+export class RequestFailedError extends Error {
+    constructor(error: RequestError);
+    /** The name of the variant, e.g. `"Busy"` */
+    readonly kind: string;
+    /** The content of the variant, if any */
+    readonly details: unknown;
+}
+
+/** Talks to an api server through a `MessagePort`. */
+export class ApiClient {
+    constructor(port: MessagePort, api: ApiDescriptor, fingerprint: bigint);
+    /** Settles once the server accepted or rejected this client. */
+    readonly ready: Promise<void>;
+    /** Milliseconds to wait for a response unless a call specifies its own `timeout`; `null` waits indefinitely. */
+    defaultTimeout: number | null;
+    /**
+     * Calls a function by its technical name; arguments are passed in their native representation.
+     *
+     * Aborting the `signal` asks the server to cancel the request, as does exceeding the `timeout`
+     * (in milliseconds).
+     */
+    call(
+        name: string,
+        args: unknown[],
+        options?: { signal?: AbortSignal; timeout?: number | null },
+    ): Promise<unknown>;
+    /** Subscribes to an event; the handler receives the event's payload as native values. */
+    on(event: string, handler: (...args: any[]) => void): void;
+    /** Unsubscribes from an event and removes all of its handlers. */
+    off(event: string): void;
+    /** Says goodbye to the server; pending and future calls will be rejected. */
+    close(): void;
+}
+
+// --- generated api bindings ---
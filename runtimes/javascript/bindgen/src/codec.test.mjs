@@ -0,0 +1,121 @@
+// Checks the codec against the byte vectors the Rust side produces with `bincode`.
+//
+// The fixtures are checked on the Rust side by the tests of `runtime-javascript-bindgen`.
+// Run with `node --test runtimes/javascript/bindgen/src/`.
+
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+import {
+    decodeClientToServerMessage,
+    decodeServerToClientMessage,
+    encodeClientToServerMessage,
+    encodeServerToClientMessage,
+} from "./codec.mjs";
+
+const FIXTURES = JSON.parse(readFileSync(new URL("codec.fixtures.json", import.meta.url)));
+
+const VERSION = "0.2.0";
+const FINGERPRINT = 0x1b1a1d62658ebcb6n;
+
+// A request exercising every kind of value
+const REQUEST = {
+    id: 1n,
+    command: "move forward",
+    arguments: [
+        "Unit",
+        { Integer: -300 },
+        { Float: 1.5 },
+        { Boolean: true },
+        { String: "grüße" },
+        { List: [{ Integer: 1 }, { Integer: 2 }] },
+        { Tuple: [{ Boolean: false }] },
+        { Record: { x: { Integer: 7 } } },
+        { Enumeration: "left" },
+        { Optional: null },
+        { Optional: { Integer: 0 } },
+    ],
+    timeout: { secs: 1n, nanos: 250_000_000 },
+};
+
+const CLIENT_TO_SERVER = {
+    "handshake": { Handshake: { api: "robot control", version: VERSION, fingerprint: FINGERPRINT } },
+    "request": { Request: REQUEST },
+    "subscribe": { Subscribe: { event: "robot stopped" } },
+    "unsubscribe": { Unsubscribe: { event: "robot stopped" } },
+    "cancel": { Cancel: 0x0123456789abcdef0123456789abcdefn },
+    "query": { Query: (1n << 128n) - 1n },
+    "goodbye": "Goodbye",
+    "batch": {
+        Batch: {
+            id: 2n,
+            requests: [{ id: 3n, command: "paint tile", arguments: [], timeout: null }],
+        },
+    },
+};
+
+const SERVER_TO_CLIENT = {
+    "handshake accepted": {
+        HandshakeResponse: { version: VERSION, fingerprint: FINGERPRINT, result: { Ok: null } },
+    },
+    "handshake rejected": {
+        HandshakeResponse: {
+            version: VERSION,
+            fingerprint: FINGERPRINT,
+            result: {
+                Err: { FingerprintMismatch: { version: VERSION, server: FINGERPRINT, client: 1n } },
+            },
+        },
+    },
+    "response": { Response: { id: 1n, result: { Boolean: true } } },
+    "error response": {
+        ErrorResponse: {
+            id: 1n,
+            error: {
+                TypeMismatch: {
+                    parameter: "duration",
+                    expected: { Integer: { start: 0, end: 10_000 } },
+                    value: { String: "soon" },
+                },
+            },
+        },
+    },
+    "event": { Event: { event: "robot stopped", arguments: [] } },
+    "cancelled": { Cancelled: 1n },
+    "status": { Status: { id: 1n, status: "Pending" } },
+    "server goodbye": "Goodbye",
+    "batch response": {
+        BatchResponse: {
+            id: 2n,
+            outcomes: [
+                { Ok: "Unit" },
+                { Err: { NotPermitted: { function: "jump", capabilities: "lesson 1" } } },
+            ],
+        },
+    },
+    "batch refused": { BatchResponse: { id: 2n, outcomes: [{ Err: "HandshakeRequired" }] } },
+};
+
+function fixture(name) {
+    assert.ok(name in FIXTURES, `missing fixture ${JSON.stringify(name)}`);
+    return Uint8Array.from(FIXTURES[name]);
+}
+
+test("client to server messages match the fixtures", () => {
+    for (const [name, message] of Object.entries(CLIENT_TO_SERVER)) {
+        assert.deepEqual(encodeClientToServerMessage(message), fixture(name), name);
+        assert.deepEqual(decodeClientToServerMessage(fixture(name)), message, name);
+    }
+});
+
+test("server to client messages match the fixtures", () => {
+    for (const [name, message] of Object.entries(SERVER_TO_CLIENT)) {
+        assert.deepEqual(encodeServerToClientMessage(message), fixture(name), name);
+        assert.deepEqual(decodeServerToClientMessage(fixture(name)), message, name);
+    }
+});
+
+test("every fixture is checked", () => {
+    const checked = [...Object.keys(CLIENT_TO_SERVER), ...Object.keys(SERVER_TO_CLIENT)];
+    assert.deepEqual(Object.keys(FIXTURES).sort(), checked.sort());
+});
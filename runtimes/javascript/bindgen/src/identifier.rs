@@ -0,0 +1,100 @@
+use gam3du_framework_common::api;
+
+/// Reserved words which cannot be used as names of parameters
+const KEYWORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Name mangling of [`api::Identifier`]s according to the JavaScript naming conventions
+pub trait JsIdentifier {
+    /// `lowerCamelCase` for methods, parameters and fields
+    fn lower_camel(&self) -> String;
+    /// `UpperCamelCase` for classes
+    fn upper_camel(&self) -> String;
+    /// `lowerCamelCase` for keys of objects which need no escaping
+    fn key(&self) -> String;
+}
+
+impl JsIdentifier for api::Identifier {
+    fn lower_camel(&self) -> String {
+        escape(self.key())
+    }
+
+    fn upper_camel(&self) -> String {
+        escape(self.as_ref().split(' ').map(capitalize).collect())
+    }
+
+    fn key(&self) -> String {
+        let mut words = self.as_ref().split(' ');
+        let mut camel = words.next().unwrap_or_default().to_owned();
+        words.for_each(|word| camel.push_str(&capitalize(word)));
+        camel
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+    characters.next().map_or_else(String::new, |first| {
+        let mut capitalized = first.to_ascii_uppercase().to_string();
+        capitalized.push_str(characters.as_str());
+        capitalized
+    })
+}
+
+/// Makes sure the name is a valid JavaScript identifier.
+fn escape(name: String) -> String {
+    if name.starts_with(|character: char| character.is_ascii_digit()) {
+        format!("_{name}")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
@@ -0,0 +1,547 @@
+//! Generates JavaScript modules and TypeScript typings for api descriptors.
+//!
+//! The module contains an encoder and decoder for all messages exchanged with an api server and a
+//! client class offering one method per function of the api. Each method returns a promise which
+//! settles once the server responded to the request.
+//!
+//! The module has no dependencies and is meant to be served next to the page using it.
+
+mod identifier;
+
+use gam3du_framework_common::api::{
+    ApiDescriptor, EventDescriptor, FunctionDescriptor, Identifier, ParameterDescriptor, RichText,
+    TypeDescriptor, Value,
+};
+pub use identifier::JsIdentifier;
+use std::io::{self, Write};
+
+/// Encoder, decoder and generic client shared by all generated modules
+const CODEC: &str = include_str!("codec.mjs");
+
+/// Typings for [`CODEC`]
+const CODEC_TYPES: &str = include_str!("codec.d.ts");
+
+/// Generates an ES module containing the client for the given api.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn generate_module(out: &mut impl Write, api: &ApiDescriptor) -> io::Result<()> {
+    let api_json = serde_json::to_string_pretty(api).map_err(io::Error::other)?;
+
+    writeln!(out, "{CODEC}")?;
+    writeln!(
+        out,
+        "/** The descriptor of the api this module has been generated for */"
+    )?;
+    writeln!(out, "export const API = {api_json};")?;
+    writeln!(out)?;
+    writeln!(out, "/** The fingerprint of {{@link API}} */")?;
+    writeln!(out, "export const FINGERPRINT = {:#x}n;", api.fingerprint())?;
+    writeln!(out)?;
+
+    generate_documentation(out, "", &api.caption, &api.description, &[])?;
+    writeln!(
+        out,
+        "export class {}Client extends ApiClient {{",
+        api.name.upper_camel()
+    )?;
+    writeln!(
+        out,
+        "    /** @param {{MessagePort}} port connected to the api server */"
+    )?;
+    writeln!(out, "    constructor(port) {{")?;
+    writeln!(out, "        super(port, API, FINGERPRINT);")?;
+    writeln!(out, "    }}")?;
+
+    for function in api.functions.values() {
+        writeln!(out)?;
+        generate_function(out, function)?;
+    }
+
+    for event in api.events.values() {
+        writeln!(out)?;
+        generate_event(out, event)?;
+    }
+
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// Generates the TypeScript typings for the module generated by [`generate_module`].
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn generate_typings(out: &mut impl Write, api: &ApiDescriptor) -> io::Result<()> {
+    writeln!(out, "{CODEC_TYPES}")?;
+    writeln!(
+        out,
+        "/** The descriptor of the api this module has been generated for */"
+    )?;
+    writeln!(out, "export const API: ApiDescriptor;")?;
+    writeln!(out)?;
+    writeln!(out, "/** The fingerprint of {{@link API}} */")?;
+    writeln!(out, "export const FINGERPRINT: bigint;")?;
+    writeln!(out)?;
+
+    generate_documentation(out, "", &api.caption, &api.description, &[])?;
+    writeln!(
+        out,
+        "export class {}Client extends ApiClient {{",
+        api.name.upper_camel()
+    )?;
+    writeln!(out, "    constructor(port: MessagePort);")?;
+
+    for function in api.functions.values() {
+        writeln!(out)?;
+        generate_documentation(out, "    ", &function.caption, &function.description, &[])?;
+        write!(out, "    {}(", function.name.lower_camel())?;
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            if index > 0 {
+                write!(out, ", ")?;
+            }
+            // a parameter may only be omitted if all following parameters may be omitted as well
+            let optional = function
+                .parameters
+                .iter()
+                .skip(index)
+                .all(|following| following.default.is_some());
+            write!(
+                out,
+                "{}{}: {}",
+                parameter.name.lower_camel(),
+                if optional { "?" } else { "" },
+                ts_type(&parameter.typ)
+            )?;
+        }
+        writeln!(out, "): Promise<{}>;", return_type(function))?;
+    }
+
+    for event in api.events.values() {
+        let name = event.name.upper_camel();
+        writeln!(out)?;
+        generate_documentation(out, "    ", &event.caption, &event.description, &[])?;
+        writeln!(
+            out,
+            "    on{name}(handler: ({}) => void): void;",
+            parameter_list(&event.parameters)
+        )?;
+        writeln!(out, "    /** Stops receiving `{}` events. */", event.name)?;
+        writeln!(out, "    off{name}(): void;")?;
+    }
+
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+fn generate_function(out: &mut impl Write, function: &FunctionDescriptor) -> io::Result<()> {
+    let mut tags = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = parameter.name.lower_camel();
+            let name = if parameter.default.is_some() {
+                format!("[{name}]")
+            } else {
+                name
+            };
+            format!(
+                "@param {{{}}} {name} {}",
+                ts_type(&parameter.typ),
+                single_line(&parameter.caption)
+            )
+        })
+        .collect::<Vec<_>>();
+    tags.push(format!("@returns {{Promise<{}>}}", return_type(function)));
+    generate_documentation(out, "    ", &function.caption, &function.description, &tags)?;
+
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = parameter.name.lower_camel();
+            match parameter.default {
+                Some(ref default) => format!("{name} = {}", js_literal(default)),
+                None => name,
+            }
+        })
+        .collect::<Vec<_>>();
+    let arguments = function
+        .parameters
+        .iter()
+        .map(|parameter| parameter.name.lower_camel())
+        .collect::<Vec<_>>();
+
+    writeln!(
+        out,
+        "    {}({}) {{",
+        function.name.lower_camel(),
+        parameters.join(", ")
+    )?;
+    writeln!(
+        out,
+        "        return this.call({}, [{}]);",
+        js_string(function.name.as_ref()),
+        arguments.join(", ")
+    )?;
+    writeln!(out, "    }}")?;
+
+    Ok(())
+}
+
+fn generate_event(out: &mut impl Write, event: &EventDescriptor) -> io::Result<()> {
+    let name = event.name.upper_camel();
+    let event_name = js_string(event.name.as_ref());
+
+    let handler = format!(
+        "@param {{({}) => void}} handler receives the payload of each event",
+        parameter_list(&event.parameters)
+    );
+    generate_documentation(out, "    ", &event.caption, &event.description, &[handler])?;
+    writeln!(out, "    on{name}(handler) {{")?;
+    writeln!(out, "        this.on({event_name}, handler);")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /** Stops receiving `{}` events. */", event.name)?;
+    writeln!(out, "    off{name}() {{")?;
+    writeln!(out, "        this.off({event_name});")?;
+    writeln!(out, "    }}")?;
+
+    Ok(())
+}
+
+/// Writes the caption, the description (if it adds anything) and the given tags as `JSDoc`.
+fn generate_documentation(
+    out: &mut impl Write,
+    indentation: &str,
+    caption: &RichText,
+    description: &RichText,
+    tags: &[String],
+) -> io::Result<()> {
+    writeln!(out, "{indentation}/**")?;
+    writeln!(out, "{indentation} * {}", single_line(caption))?;
+    if description.0 != caption.0 && !description.0.is_empty() {
+        writeln!(out, "{indentation} *")?;
+        for line in markdown(description).lines() {
+            if line.is_empty() {
+                writeln!(out, "{indentation} *")?;
+            } else {
+                writeln!(out, "{indentation} * {}", escape_comment(line))?;
+            }
+        }
+    }
+    if !tags.is_empty() {
+        writeln!(out, "{indentation} *")?;
+        for tag in tags {
+            writeln!(out, "{indentation} * {tag}")?;
+        }
+    }
+    writeln!(out, "{indentation} */")?;
+    Ok(())
+}
+
+fn single_line(text: &RichText) -> String {
+    escape_comment(&markdown(text).replace('\n', " "))
+}
+
+/// Renders the markup of a text; links name the methods of the client.
+fn markdown(text: &RichText) -> String {
+    let name = |target: &Identifier| target.lower_camel();
+    text.document().to_markdown(&name, None)
+}
+
+/// Prevents text from terminating the surrounding comment.
+fn escape_comment(text: &str) -> String {
+    text.replace("*/", "*\\/")
+}
+
+/// Renders the parameters of an event handler in TypeScript syntax.
+fn parameter_list(parameters: &[ParameterDescriptor]) -> String {
+    parameters
+        .iter()
+        .map(|parameter| {
+            format!(
+                "{}: {}",
+                parameter.name.lower_camel(),
+                ts_type(&parameter.typ)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn return_type(function: &FunctionDescriptor) -> String {
+    function
+        .returns
+        .as_ref()
+        .map_or_else(|| "void".to_owned(), |returns| ts_type(&returns.typ))
+}
+
+/// Renders the native JavaScript representation of the given type in TypeScript syntax.
+fn ts_type(typ: &TypeDescriptor) -> String {
+    match *typ {
+        TypeDescriptor::Integer(_) | TypeDescriptor::Float => "number".to_owned(),
+        TypeDescriptor::Boolean => "boolean".to_owned(),
+        TypeDescriptor::String => "string".to_owned(),
+        TypeDescriptor::List(ref element) => {
+            let element = ts_type(element);
+            // only unions need to be grouped; tuples and records are already enclosed
+            if element.contains('|') && !element.starts_with(['[', '{']) {
+                format!("({element})[]")
+            } else {
+                format!("{element}[]")
+            }
+        }
+        TypeDescriptor::Tuple(ref elements) => {
+            let elements = elements.iter().map(ts_type).collect::<Vec<_>>();
+            format!("[{}]", elements.join(", "))
+        }
+        TypeDescriptor::Record(ref fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, field)| format!("{}: {}", name.key(), ts_type(field)))
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        TypeDescriptor::Enumeration(ref variants) => {
+            let variants = variants
+                .iter()
+                .map(|variant| js_string(variant.as_ref()))
+                .collect::<Vec<_>>();
+            variants.join(" | ")
+        }
+        TypeDescriptor::Optional(ref inner) => format!("{} | null", ts_type(inner)),
+    }
+}
+
+/// Renders the native JavaScript representation of the given value.
+fn js_literal(value: &Value) -> String {
+    match *value {
+        Value::Unit => "undefined".to_owned(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Float(float) if float.is_nan() => "NaN".to_owned(),
+        Value::Float(float) if float.is_infinite() => {
+            if float.is_sign_positive() {
+                "Infinity".to_owned()
+            } else {
+                "-Infinity".to_owned()
+            }
+        }
+        Value::Float(float) => format!("{float:?}"),
+        Value::Boolean(boolean) => boolean.to_string(),
+        Value::String(ref string) => js_string(string),
+        Value::List(ref elements) | Value::Tuple(ref elements) => {
+            let elements = elements.iter().map(js_literal).collect::<Vec<_>>();
+            format!("[{}]", elements.join(", "))
+        }
+        Value::Record(ref fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, field)| format!("{}: {}", js_string(&name.key()), js_literal(field)))
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        Value::Enumeration(ref variant) => js_string(variant.as_ref()),
+        Value::Optional(None) => "null".to_owned(),
+        Value::Optional(Some(ref inner)) => js_literal(inner),
+    }
+}
+
+/// Renders a JavaScript string literal.
+fn js_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::unwrap_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    reason = "malformed fixtures fail the test"
+)]
+mod tests {
+    //! `codec.test.mjs` checks the JavaScript codec against the same fixtures.
+
+    use gam3du_framework_common::{
+        api::{ApiVersion, Identifier, TypeDescriptor, Value},
+        message::{
+            BatchMessage, BatchResponseMessage, ClientToServerMessage, ErrorResponseMessage,
+            EventMessage, HandshakeError, HandshakeMessage, HandshakeResponseMessage, RequestError,
+            RequestId, RequestMessage, RequestStatus, ResponseMessage, ServerToClientMessage,
+            StatusMessage, SubscribeMessage, UnsubscribeMessage,
+        },
+    };
+    use serde::{Serialize, de::DeserializeOwned};
+    use std::time::Duration;
+
+    const VERSION: ApiVersion = ApiVersion::new(0, 2, 0);
+    const FINGERPRINT: u64 = 0x1b1a_1d62_658e_bcb6;
+
+    fn id(id: u128) -> RequestId {
+        RequestId::try_from(id).unwrap()
+    }
+
+    fn name(name: &'static str) -> Identifier {
+        Identifier(name.into())
+    }
+
+    /// Returns the bytes `codec.fixtures.json` lists for the message of the given name.
+    fn fixture(name: &str) -> Vec<u8> {
+        let fixtures: serde_json::Value =
+            serde_json::from_str(include_str!("codec.fixtures.json")).unwrap();
+        serde_json::from_value(fixtures[name].clone()).unwrap_or_else(|error| {
+            panic!("missing fixture {name:?}: {error}");
+        })
+    }
+
+    /// Checks that a message is encoded into its fixture and that decoding it yields the same bytes.
+    fn assert_fixture<Message: Serialize + DeserializeOwned>(name: &str, message: &Message) {
+        let config = bincode::config::standard();
+        let encoded = bincode::serde::encode_to_vec(message, config).unwrap();
+        assert_eq!(encoded, fixture(name), "{name}");
+
+        let (decoded, read): (Message, _) =
+            bincode::serde::decode_from_slice(&encoded, config).unwrap();
+        assert_eq!(read, encoded.len(), "{name}");
+        let reencoded = bincode::serde::encode_to_vec(&decoded, config).unwrap();
+        assert_eq!(reencoded, encoded, "{name}");
+    }
+
+    /// A request exercising every kind of value
+    fn request() -> RequestMessage {
+        RequestMessage {
+            id: id(1),
+            command: name("move forward"),
+            arguments: vec![
+                Value::Unit,
+                Value::Integer(-300),
+                Value::Float(1.5),
+                Value::Boolean(true),
+                Value::String("grüße".into()),
+                Value::List(vec![Value::Integer(1), Value::Integer(2)]),
+                Value::Tuple(vec![Value::Boolean(false)]),
+                Value::Record([(name("x"), Value::Integer(7))].into_iter().collect()),
+                Value::Enumeration(name("left")),
+                Value::Optional(None),
+                Value::Optional(Some(Box::new(Value::Integer(0)))),
+            ],
+            timeout: Some(Duration::from_millis(1250)),
+        }
+    }
+
+    #[test]
+    fn client_to_server_messages_match_fixtures() {
+        let handshake = HandshakeMessage {
+            api: name("robot control"),
+            version: VERSION,
+            fingerprint: FINGERPRINT,
+        };
+        assert_fixture("handshake", &ClientToServerMessage::Handshake(handshake));
+        assert_fixture("request", &ClientToServerMessage::Request(request()));
+        let subscribe = SubscribeMessage {
+            event: name("robot stopped"),
+        };
+        assert_fixture("subscribe", &ClientToServerMessage::Subscribe(subscribe));
+        let unsubscribe = UnsubscribeMessage {
+            event: name("robot stopped"),
+        };
+        assert_fixture(
+            "unsubscribe",
+            &ClientToServerMessage::Unsubscribe(unsubscribe),
+        );
+        let large_id = id(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        assert_fixture("cancel", &ClientToServerMessage::Cancel(large_id));
+        assert_fixture("query", &ClientToServerMessage::Query(id(u128::MAX)));
+        assert_fixture("goodbye", &ClientToServerMessage::Goodbye);
+        let batch = BatchMessage {
+            id: id(2),
+            requests: vec![RequestMessage {
+                id: id(3),
+                command: name("paint tile"),
+                arguments: Vec::new(),
+                timeout: None,
+            }],
+        };
+        assert_fixture("batch", &ClientToServerMessage::Batch(batch));
+    }
+
+    #[test]
+    fn server_to_client_messages_match_fixtures() {
+        let accepted = HandshakeResponseMessage {
+            version: VERSION,
+            fingerprint: FINGERPRINT,
+            result: Ok(()),
+        };
+        assert_fixture(
+            "handshake accepted",
+            &ServerToClientMessage::HandshakeResponse(accepted),
+        );
+        let rejected = HandshakeResponseMessage {
+            version: VERSION,
+            fingerprint: FINGERPRINT,
+            result: Err(HandshakeError::FingerprintMismatch {
+                version: VERSION,
+                server: FINGERPRINT,
+                client: 1,
+            }),
+        };
+        assert_fixture(
+            "handshake rejected",
+            &ServerToClientMessage::HandshakeResponse(rejected),
+        );
+        let response = ResponseMessage {
+            id: id(1),
+            result: Value::Boolean(true),
+        };
+        assert_fixture("response", &ServerToClientMessage::Response(response));
+        let error = ErrorResponseMessage {
+            id: id(1),
+            error: RequestError::TypeMismatch {
+                parameter: name("duration"),
+                expected: Box::new(TypeDescriptor::Integer(0..10_000)),
+                value: Box::new(Value::String("soon".into())),
+            },
+        };
+        assert_fixture(
+            "error response",
+            &ServerToClientMessage::ErrorResponse(error),
+        );
+        let event = EventMessage {
+            event: name("robot stopped"),
+            arguments: Vec::new(),
+        };
+        assert_fixture("event", &ServerToClientMessage::Event(event));
+        assert_fixture("cancelled", &ServerToClientMessage::Cancelled(id(1)));
+        let status = StatusMessage {
+            id: id(1),
+            status: RequestStatus::Pending,
+        };
+        assert_fixture("status", &ServerToClientMessage::Status(status));
+        assert_fixture("server goodbye", &ServerToClientMessage::Goodbye);
+        let batch = BatchResponseMessage {
+            id: id(2),
+            outcomes: vec![
+                Ok(Value::Unit),
+                Err(RequestError::NotPermitted {
+                    function: name("jump"),
+                    capabilities: name("lesson 1"),
+                }),
+            ],
+        };
+        assert_fixture(
+            "batch response",
+            &ServerToClientMessage::BatchResponse(batch),
+        );
+        let refused = BatchResponseMessage {
+            id: id(2),
+            outcomes: vec![Err(RequestError::HandshakeRequired)],
+        };
+        assert_fixture(
+            "batch refused",
+            &ServerToClientMessage::BatchResponse(refused),
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Names of the Python exception classes raised by api clients when the server rejects a request.
+
+use gam3du_framework_common::message::RequestError;
+
+/// The common base class of all exceptions raised for rejected requests (derived from `RuntimeError`)
+pub const REQUEST_ERROR: &str = "RequestError";
+
+/// One exception class per variant of [`RequestError`], each derived from [`REQUEST_ERROR`]
+pub const EXCEPTION_CLASSES: [&str; 10] = [
+    "UnknownFunctionError",
+    "WrongArgumentCountError",
+    "TypeMismatchError",
+    "OutOfRangeError",
+    "BusyError",
+    "CancelledError",
+    "EngineRejectedError",
+    "DeadlineExceededError",
+    "NotPermittedError",
+    "HandshakeRequiredError",
+];
+
+/// Returns the name of the exception class to be raised for the given error.
+#[must_use]
+pub fn exception_class(error: &RequestError) -> &'static str {
+    match *error {
+        RequestError::UnknownFunction { .. } => "UnknownFunctionError",
+        RequestError::WrongArgumentCount { .. } => "WrongArgumentCountError",
+        RequestError::TypeMismatch { .. } => "TypeMismatchError",
+        RequestError::OutOfRange { .. } => "OutOfRangeError",
+        RequestError::Busy => "BusyError",
+        RequestError::Cancelled => "CancelledError",
+        RequestError::EngineRejected { .. } => "EngineRejectedError",
+        RequestError::DeadlineExceeded => "DeadlineExceededError",
+        RequestError::NotPermitted { .. } => "NotPermittedError",
+        RequestError::HandshakeRequired => "HandshakeRequiredError",
+    }
+}
+
+/// Generates an import of all exception classes from the given module.
+pub(crate) fn import_exception_classes(
+    out: &mut impl std::io::Write,
+    module: &str,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "from {module} import {REQUEST_ERROR}, {}",
+        EXCEPTION_CLASSES.join(", ")
+    )
+}
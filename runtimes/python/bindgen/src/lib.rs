@@ -10,10 +10,14 @@
     reason = "TODO remove and fix before release"
 )]
 
+mod exceptions;
 mod identifier;
 
+use exceptions::import_exception_classes;
+pub use exceptions::{EXCEPTION_CLASSES, REQUEST_ERROR, exception_class};
 use gam3du_framework_common::api::{
-    ApiDescriptor, FunctionDescriptor, Identifier, ParameterDescriptor, TypeDescriptor, Value,
+    ApiDescriptor, Capabilities, EventDescriptor, FunctionDescriptor, Identifier, LinkName, Locale,
+    Localized, ParameterDescriptor, Phrases, RichText, TypeDescriptor, Value,
 };
 pub use identifier::PyIdentifier;
 use std::io::{self, Write};
@@ -23,7 +27,8 @@ pub struct Config {
 }
 
 pub fn generate(out: &mut impl Write, api: &ApiDescriptor, config: &Config) -> io::Result<()> {
-    // TODO add documentation comments for api
+    generate_docstring(out, "", &api.caption, &api.description, &identifier)?;
+    writeln!(out)?;
 
     generate_module(out, api, config)?;
 
@@ -41,26 +46,369 @@ fn generate_module(
     let async_module_name = &format!("{}_api_async", api.name.file());
     if config.sync {
         writeln!(out, "import {async_module_name}")?;
+        import_exception_classes(out, async_module_name)?;
         // writeln!(out, "import asyncio")?;
     } else {
         // writeln!(out, "import asyncio")?;
         writeln!(out, "import {internal_module_name}")?;
+        import_exception_classes(out, internal_module_name)?;
     }
     writeln!(out)?;
+    generate_set_default_timeout(
+        out,
+        &api.name,
+        internal_module_name,
+        async_module_name,
+        config,
+    )?;
     api.functions.values().try_for_each(|function| {
         generate_function(
             out,
+            &api.name,
             function,
             internal_module_name,
             async_module_name,
             config,
         )
     })?;
+    api.events.values().try_for_each(|event| {
+        generate_event(
+            out,
+            &api.name,
+            event,
+            internal_module_name,
+            async_module_name,
+            config,
+        )
+    })?;
+    if !api.events.is_empty() {
+        generate_dispatch_events(
+            out,
+            &api.name,
+            internal_module_name,
+            async_module_name,
+            config,
+        )?;
+    }
+    generate_batch(
+        out,
+        &api.name,
+        internal_module_name,
+        async_module_name,
+        config,
+    )?;
+    Ok(())
+}
+
+/// The name of the module generated by [`generate_localized`] (e.g. `robot_control_api_de`).
+#[must_use]
+pub fn localized_module_name(api: &ApiDescriptor, locale: &Locale) -> String {
+    format!(
+        "{}_api_{}",
+        api.name.file(),
+        locale.as_ref().to_lowercase().replace('-', "_")
+    )
+}
+
+/// Generates a module offering the api in the language of the given locale.
+///
+/// Functions, events and parameters are named by their aliases (or their canonical names if they
+/// have not been translated) and forward to the canonical module. Integer ranges and enumeration
+/// variants are checked before forwarding, so students get error messages in their language.
+pub fn generate_localized(
+    out: &mut impl Write,
+    api: &ApiDescriptor,
+    locale: &Locale,
+) -> io::Result<()> {
+    // links refer to the aliases of the functions
+    let names = |target: &Identifier| {
+        api.functions.get(target).map_or_else(
+            || identifier(target),
+            |function| identifier(function.localized(Some(locale)).name),
+        )
+    };
+    let api_texts = api.localized(Some(locale));
+    generate_docstring(out, "", api_texts.caption, api_texts.description, &names)?;
+    writeln!(out)?;
+
+    let canonical_module_name = format!("{}_api", api.name.file());
+    writeln!(out, "import {canonical_module_name}")?;
+    import_exception_classes(out, &canonical_module_name)?;
+    writeln!(out)?;
+
+    for function in api.functions.values() {
+        generate_localized_function(out, function, &canonical_module_name, locale, &names)?;
+    }
+
+    for event in api.events.values() {
+        let texts = event.localized(Some(locale));
+        writeln!(out, "def on_{}(handler):", identifier(texts.name))?;
+        generate_docstring(out, "\t", texts.caption, texts.description, &names)?;
+        writeln!(
+            out,
+            "\t{canonical_module_name}.on_{}(handler)",
+            identifier(&event.name)
+        )?;
+        writeln!(out)?;
+    }
+    if !api.events.is_empty() {
+        writeln!(out, "from {canonical_module_name} import dispatch_events")?;
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "from {canonical_module_name} import set_default_timeout"
+    )?;
+    writeln!(out, "from {canonical_module_name} import batch")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// The name of the module generated by [`generate_restricted`] (e.g. `robot_control_api_lesson_1`).
+#[must_use]
+pub fn restricted_module_name(api: &ApiDescriptor, capabilities: &Capabilities) -> String {
+    format!("{}_api_{}", api.name.file(), capabilities.name.module())
+}
+
+/// Generates a module offering only the functions permitted by the capabilities.
+///
+/// The functions are imported from the canonical module, so students cannot stumble upon the
+/// others by code completion. Events and helpers like `batch` are offered as well.
+pub fn generate_restricted(
+    out: &mut impl Write,
+    api: &ApiDescriptor,
+    capabilities: &Capabilities,
+) -> io::Result<()> {
+    let mut docstring = Docstring::new(&api.caption, &api.description, &identifier);
+    docstring.paragraph(&format!(
+        "Only the functions available in ``{}`` are offered.",
+        capabilities.name
+    ));
+    docstring.generate(out, "")?;
+    writeln!(out)?;
+
+    let canonical_module_name = format!("{}_api", api.name.file());
+    import_exception_classes(out, &canonical_module_name)?;
+    writeln!(out)?;
+
+    for function in capabilities.restrict(api).functions.values() {
+        writeln!(
+            out,
+            "from {canonical_module_name} import {}",
+            identifier(&function.name)
+        )?;
+    }
+    for event in api.events.values() {
+        writeln!(
+            out,
+            "from {canonical_module_name} import on_{}",
+            identifier(&event.name)
+        )?;
+    }
+    if !api.events.is_empty() {
+        writeln!(out, "from {canonical_module_name} import dispatch_events")?;
+    }
+    writeln!(
+        out,
+        "from {canonical_module_name} import set_default_timeout"
+    )?;
+    writeln!(out, "from {canonical_module_name} import batch")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_localized_function(
+    out: &mut impl Write,
+    function: &FunctionDescriptor,
+    canonical_module_name: &str,
+    locale: &Locale,
+    names: LinkName<'_>,
+) -> io::Result<()> {
+    let locale = Some(locale);
+    let phrases = Phrases::for_locale(locale);
+    let parameter_names = function
+        .parameters
+        .iter()
+        .map(|parameter| identifier(parameter.localized(locale).name))
+        .collect::<Vec<_>>();
+
+    write!(out, "def {}(", identifier(function.localized(locale).name))?;
+    for (index, (parameter, name)) in function.parameters.iter().zip(&parameter_names).enumerate() {
+        if index > 0 {
+            write!(out, ", ")?;
+        }
+        write!(out, "{name}: {}", typ(&parameter.typ))?;
+        if let Some(ref default) = parameter.default
+            && !matches!(*default, Value::Unit)
+        {
+            write!(out, " = {}", value(default))?;
+        }
+    }
+    generate_timeout_parameter(out, !function.parameters.is_empty())?;
+    write!(out, ")")?;
+    if let Some(ref returns) = function.returns {
+        write!(out, " -> {}", typ(&returns.typ))?;
+    }
+    writeln!(out, ":")?;
+    generate_function_docstring(out, function, locale, names, true)?;
+
+    for (parameter, name) in function.parameters.iter().zip(&parameter_names) {
+        generate_check(out, &parameter.typ, name, phrases)?;
+    }
+
+    let arguments = parameter_names
+        .iter()
+        .map(String::as_str)
+        .chain(["timeout=timeout"])
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "\treturn {canonical_module_name}.{}({})",
+        identifier(&function.name),
+        arguments.join(", ")
+    )?;
+    writeln!(out)?;
+
     Ok(())
 }
 
+/// Checks the constraints of a parameter its type hint cannot express.
+fn generate_check(
+    out: &mut impl Write,
+    typ: &TypeDescriptor,
+    name: &str,
+    phrases: &Phrases,
+) -> io::Result<()> {
+    match *typ {
+        TypeDescriptor::Integer(ref range) => {
+            writeln!(out, "\tif not {} <= {name} < {}:", range.start, range.end)?;
+            // the upper bound is exclusive but students expect to see the largest value
+            let message = phrases
+                .out_of_range
+                .replace("{parameter}", name)
+                .replace("{start}", &range.start.to_string())
+                .replace("{end}", &range.end.saturating_sub(1).to_string());
+            generate_raise(out, &message, name)
+        }
+        TypeDescriptor::Enumeration(ref variants) => {
+            let variants = variants
+                .iter()
+                .map(|variant| value(&Value::Enumeration(variant.clone())))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(out, "\tif {name} not in ({variants},):")?;
+            let message = phrases
+                .unknown_variant
+                .replace("{parameter}", name)
+                .replace("{variants}", &variants);
+            generate_raise(out, &message, name)
+        }
+        TypeDescriptor::Float
+        | TypeDescriptor::Boolean
+        | TypeDescriptor::String
+        | TypeDescriptor::List(_)
+        | TypeDescriptor::Tuple(_)
+        | TypeDescriptor::Record(_)
+        | TypeDescriptor::Optional(_) => Ok(()),
+    }
+}
+
+/// Raises a `ValueError` with the given message, replacing `{value}` by the offending value.
+fn generate_raise(out: &mut impl Write, message: &str, name: &str) -> io::Result<()> {
+    let (prefix, suffix) = message.split_once("{value}").unwrap_or((message, ""));
+    write!(out, "\t\traise ValueError({prefix:?} + repr({name})")?;
+    if !suffix.is_empty() {
+        write!(out, " + {suffix:?}")?;
+    }
+    writeln!(out, ")")
+}
+
+/// Generates a module wrapping a native module that exposes an api through a single generic
+/// `call(function, *arguments)` function.
+///
+/// All other members of the native module will be re-exported unchanged.
+pub fn generate_native_wrappers(
+    out: &mut impl Write,
+    api: &ApiDescriptor,
+    internal_module_name: &str,
+) -> io::Result<()> {
+    writeln!(out, "import {internal_module_name}")?;
+    writeln!(out, "from {internal_module_name} import *")?;
+    writeln!(out)?;
+
+    for function in api.functions.values() {
+        generate_native_signature(out, function)?;
+
+        write!(
+            out,
+            "\treturn {internal_module_name}.call(\"{}\"",
+            function.name
+        )?;
+        for parameter in &function.parameters {
+            write!(out, ", ")?;
+            generate_parameter(out, parameter, true)?;
+        }
+        writeln!(out, ")")?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Generates a stub of the module created by [`generate_native_wrappers`] for IDE support.
+///
+/// The members of the native module need to be covered by a separate stub.
+pub fn generate_native_stub(
+    out: &mut impl Write,
+    api: &ApiDescriptor,
+    internal_module_name: &str,
+) -> io::Result<()> {
+    writeln!(out, "from {internal_module_name} import *")?;
+    writeln!(out)?;
+
+    for function in api.functions.values() {
+        generate_native_signature(out, function)?;
+        writeln!(out, "\t...")?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn generate_native_signature(
+    out: &mut impl Write,
+    function: &FunctionDescriptor,
+) -> io::Result<()> {
+    let FunctionDescriptor {
+        ref name,
+        caption: _,
+        description: _,
+        ref parameters,
+        ref returns,
+        localizations: _,
+    } = *function;
+
+    write!(out, "def {name}(", name = identifier(name))?;
+    for (index, parameter) in parameters.iter().enumerate() {
+        if index > 0 {
+            write!(out, ", ")?;
+        }
+        generate_parameter(out, parameter, false)?;
+    }
+    write!(out, ")")?;
+    if let Some(ref returns) = *returns {
+        write!(out, " -> {typ}", typ = typ(&returns.typ))?;
+    }
+    writeln!(out, ":")?;
+    // the native module does not offer a timeout
+    generate_function_docstring(out, function, None, &identifier, false)
+}
+
 pub fn generate_function(
     out: &mut impl Write,
+    api_name: &Identifier,
     function: &FunctionDescriptor,
     internal_module_name: &str,
     async_module_name: &str,
@@ -72,10 +420,9 @@ pub fn generate_function(
         description: _,
         ref parameters,
         ref returns,
+        localizations: _,
     } = *function;
 
-    // TODO add documentation comments for function and parameters
-
     if !config.sync {
         // write!(out, "async ")?;
     }
@@ -87,6 +434,7 @@ pub fn generate_function(
         }
         generate_parameter(out, parameter, false)?;
     }
+    generate_timeout_parameter(out, !parameters.is_empty())?;
 
     write!(out, ")")?;
 
@@ -94,6 +442,7 @@ pub fn generate_function(
         write!(out, " -> {typ}", typ = typ(&returns.typ))?;
     }
     writeln!(out, ":")?;
+    generate_function_docstring(out, function, None, &identifier, true)?;
 
     if config.sync {
         write!(
@@ -109,23 +458,34 @@ pub fn generate_function(
             first = false;
             generate_parameter(out, parameter, true)?;
         }
-        writeln!(out, ")")?;
+        if !first {
+            write!(out, ", ")?;
+        }
+        writeln!(out, "timeout=timeout)")?;
         // writeln!(out, "\treturn asyncio.run(future)")?;
         writeln!(out, "\treturn future")?;
     } else {
-        write!(out, "\thandle = {internal_module_name}.message(\"{name}\"")?;
+        write!(
+            out,
+            "\thandle = {internal_module_name}.message(\"{api_name}\", \"{name}\""
+        )?;
         for parameter in parameters {
             write!(out, ", ")?;
             generate_parameter(out, parameter, true)?;
         }
-        writeln!(out, ")")?;
+        writeln!(out, ", timeout=timeout)")?;
         writeln!(
             out,
-            "\twhile True:
-		result = {internal_module_name}.poll(handle)
-		if result.is_done():
-			return result.get_value()
-		# await asyncio.sleep(0.01)
+            "\ttry:
+		while True:
+			result = {internal_module_name}.poll(handle)
+			if result.is_done():
+				return result.get_value()
+			# await asyncio.sleep(0.01)
+	except KeyboardInterrupt:
+		# the script has been stopped while waiting, so the server shall stop as well
+		{internal_module_name}.cancel(handle)
+		raise
 "
         )?;
     }
@@ -135,6 +495,321 @@ pub fn generate_function(
     Ok(())
 }
 
+/// Generates a function registering a handler for the given event.
+///
+/// The handler will be called with the event's parameters whenever the event occurs
+/// while the script is waiting for a response or calls `dispatch_events()`.
+pub fn generate_event(
+    out: &mut impl Write,
+    api_name: &Identifier,
+    event: &EventDescriptor,
+    internal_module_name: &str,
+    async_module_name: &str,
+    config: &Config,
+) -> io::Result<()> {
+    let EventDescriptor {
+        ref name,
+        ref caption,
+        ref description,
+        ref parameters,
+        localizations: _,
+    } = *event;
+
+    writeln!(out, "def on_{name}(handler):", name = identifier(name))?;
+    let mut docstring = Docstring::new(caption, description, &identifier);
+    docstring.section(
+        "Args",
+        &[(
+            "handler".to_owned(),
+            if parameters.is_empty() {
+                "called without arguments whenever the event occurs".to_owned()
+            } else {
+                let names = parameters
+                    .iter()
+                    .map(|parameter| identifier(&parameter.name))
+                    .collect::<Vec<_>>();
+                format!(
+                    "called with `{}` whenever the event occurs",
+                    names.join("`, `")
+                )
+            },
+        )],
+    );
+    docstring.generate(out, "\t")?;
+    if config.sync {
+        writeln!(
+            out,
+            "\t{async_module_name}.on_{name}(handler)",
+            name = identifier(name)
+        )?;
+    } else {
+        writeln!(
+            out,
+            "\t{internal_module_name}.subscribe(\"{api_name}\", \"{name}\", handler)"
+        )?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_dispatch_events(
+    out: &mut impl Write,
+    api_name: &Identifier,
+    internal_module_name: &str,
+    async_module_name: &str,
+    config: &Config,
+) -> io::Result<()> {
+    writeln!(out, "def dispatch_events():")?;
+    if config.sync {
+        writeln!(out, "\t{async_module_name}.dispatch_events()")?;
+    } else {
+        writeln!(
+            out,
+            "\t{internal_module_name}.dispatch_events(\"{api_name}\")"
+        )?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Generates a function setting the timeout of all requests which do not specify their own.
+fn generate_set_default_timeout(
+    out: &mut impl Write,
+    api_name: &Identifier,
+    internal_module_name: &str,
+    async_module_name: &str,
+    config: &Config,
+) -> io::Result<()> {
+    writeln!(out, "def set_default_timeout(timeout: float | None):")?;
+    writeln!(
+        out,
+        "\t\"\"\"Sets the number of seconds to wait for a response unless a function is given its own `timeout`
+
+\t`None` waits indefinitely, which is the initial setting.
+\t\"\"\""
+    )?;
+    if config.sync {
+        writeln!(out, "\t{async_module_name}.set_default_timeout(timeout)")?;
+    } else {
+        writeln!(
+            out,
+            "\t{internal_module_name}.set_default_timeout(\"{api_name}\", timeout)"
+        )?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Generates a context manager collecting all calls made within a `with` block into a single batch.
+///
+/// The batch is sent once the block has been left without raising an exception.
+fn generate_batch(
+    out: &mut impl Write,
+    api_name: &Identifier,
+    internal_module_name: &str,
+    async_module_name: &str,
+    config: &Config,
+) -> io::Result<()> {
+    if config.sync {
+        writeln!(out, "from {async_module_name} import batch")?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "class batch:
+\t\"\"\"Collects all calls made within a `with` block and sends them to the server at once
+
+\tThe server performs the calls one after another and stops at the first failure. Within the
+\tblock, calls return `None` right away. Once the block has been left, `results` lists the
+\toutcome of each call performed; a failed call is the last entry and its exception is raised.
+
+\tArgs:
+\t\ttimeout: seconds to wait for the whole batch; `None` waits indefinitely
+\t\"\"\"
+
+\tdef __init__(self, *, timeout: float | None = None):
+\t\tself.timeout = timeout
+\t\tself.results = None
+
+\tdef __enter__(self):
+\t\t{internal_module_name}.begin_batch(\"{api_name}\")
+\t\treturn self
+
+\tdef __exit__(self, exception_type, exception, traceback):
+\t\tif exception is not None:
+\t\t\t# nothing has been sent yet, so the calls are dropped along with the block
+\t\t\t{internal_module_name}.discard_batch(\"{api_name}\")
+\t\t\treturn False
+\t\thandle = {internal_module_name}.send_batch(\"{api_name}\", self.timeout)
+\t\ttry:
+\t\t\twhile True:
+\t\t\t\tresult = {internal_module_name}.poll(handle)
+\t\t\t\tif result.is_done():
+\t\t\t\t\tself.results = result.get_value()
+\t\t\t\t\tbreak
+\t\texcept KeyboardInterrupt:
+\t\t\t# the script has been stopped while waiting, so the server shall stop as well
+\t\t\t{internal_module_name}.cancel(handle)
+\t\t\traise
+\t\tif self.results and isinstance(self.results[-1], BaseException):
+\t\t\traise self.results[-1]
+\t\treturn False
+"
+    )?;
+
+    Ok(())
+}
+
+/// Writes the docstring of a function describing its parameters and return value.
+///
+/// `timeout` tells whether the function accepts the keyword-only parameter added by
+/// [`generate_timeout_parameter`].
+fn generate_function_docstring(
+    out: &mut impl Write,
+    function: &FunctionDescriptor,
+    locale: Option<&Locale>,
+    names: LinkName<'_>,
+    timeout: bool,
+) -> io::Result<()> {
+    let phrases = Phrases::for_locale(locale);
+    let texts = function.localized(locale);
+    let mut docstring = Docstring::new(texts.caption, texts.description, names);
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let text = parameter_text(parameter, locale, names);
+            let text = match parameter.default {
+                Some(ref default) if !matches!(*default, Value::Unit) => {
+                    format!("{text} ({}: `{}`)", phrases.default, value(default))
+                }
+                _ => text,
+            };
+            (identifier(parameter.localized(locale).name), text)
+        })
+        .collect::<Vec<_>>();
+    let timeout = timeout.then(|| ("timeout".to_owned(), phrases.timeout.to_owned()));
+    docstring.section(
+        "Args",
+        &parameters.into_iter().chain(timeout).collect::<Vec<_>>(),
+    );
+    if let Some(ref returns) = function.returns {
+        let text = parameter_text(returns, locale, names);
+        let text = if text.is_empty() {
+            format!("`{}`", typ(&returns.typ))
+        } else {
+            format!("`{}`: {text}", typ(&returns.typ))
+        };
+        docstring.paragraph(&format!("Returns:\n\t{text}"));
+    }
+    docstring.generate(out, "\t")
+}
+
+/// Adds the keyword-only parameter limiting the time to wait for the response.
+///
+/// Validated descriptors cannot contain a parameter of the same name (see
+/// [`RESERVED_PARAMETER_NAMES`]).
+///
+/// [`RESERVED_PARAMETER_NAMES`]: gam3du_framework_common::api::RESERVED_PARAMETER_NAMES
+fn generate_timeout_parameter(out: &mut impl Write, separator: bool) -> io::Result<()> {
+    if separator {
+        write!(out, ", ")?;
+    }
+    write!(out, "*, timeout: float | None = None")
+}
+
+/// The most detailed explanation of a parameter.
+fn parameter_text(
+    parameter: &ParameterDescriptor,
+    locale: Option<&Locale>,
+    names: LinkName<'_>,
+) -> String {
+    let texts = parameter.localized(locale);
+    let text = if texts.description.0.is_empty() {
+        texts.caption
+    } else {
+        texts.description
+    };
+    text.document().to_docstring(names).replace('\n', " ")
+}
+
+/// Writes a module docstring explaining the api.
+fn generate_docstring(
+    out: &mut impl Write,
+    indentation: &str,
+    caption: &RichText,
+    description: &RichText,
+    names: LinkName<'_>,
+) -> io::Result<()> {
+    Docstring::new(caption, description, names).generate(out, indentation)
+}
+
+/// A Python docstring following the Google style guide
+struct Docstring {
+    paragraphs: Vec<String>,
+}
+
+impl Docstring {
+    /// Starts with the caption as summary line followed by the description (if it adds anything).
+    ///
+    /// Links to other functions are named by `names`.
+    fn new(caption: &RichText, description: &RichText, names: LinkName<'_>) -> Self {
+        let mut paragraphs = vec![caption.document().to_docstring(names).replace('\n', " ")];
+        if description.0 != caption.0 && !description.0.is_empty() {
+            paragraphs.push(description.document().to_docstring(names));
+        }
+        Self { paragraphs }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.paragraphs.push(text.to_owned());
+    }
+
+    /// Adds a section like `Args:` listing the given entries; empty sections will be omitted.
+    fn section(&mut self, title: &str, entries: &[(String, String)]) {
+        if entries.is_empty() {
+            return;
+        }
+        let mut lines = vec![format!("{title}:")];
+        lines.extend(
+            entries
+                .iter()
+                .map(|(name, text)| format!("\t{name}: {text}")),
+        );
+        self.paragraphs.push(lines.join("\n"));
+    }
+
+    fn generate(&self, out: &mut impl Write, indentation: &str) -> io::Result<()> {
+        let text = self
+            .paragraphs
+            .join("\n\n")
+            .replace('\\', "\\\\")
+            .replace("\"\"\"", "\\\"\"\"");
+        if !text.contains('\n') {
+            return writeln!(out, "{indentation}\"\"\"{text}\"\"\"");
+        }
+        let mut lines = text.lines();
+        writeln!(
+            out,
+            "{indentation}\"\"\"{}",
+            lines.next().unwrap_or_default()
+        )?;
+        for line in lines {
+            if line.is_empty() {
+                writeln!(out)?;
+            } else {
+                writeln!(out, "{indentation}{line}")?;
+            }
+        }
+        writeln!(out, "{indentation}\"\"\"")
+    }
+}
+
 pub fn generate_parameter(
     out: &mut impl Write,
     parameter: &ParameterDescriptor,
@@ -146,6 +821,7 @@ pub fn generate_parameter(
         description: _,
         ref typ,
         default: _,
+        localizations: _,
     } = *parameter;
 
     write!(out, "{name}", name = identifier(name))?;
@@ -158,29 +834,46 @@ pub fn generate_parameter(
     let typ = self::typ(typ);
     write!(out, ": {typ}")?;
 
-    if let Some(ref default) = parameter.default {
-        match *default {
-            Value::Unit => {}
-            Value::Integer(default) => write!(out, " = {default}")?,
-            Value::Float(default) => write!(out, " = {default}")?,
-            Value::Boolean(true) => write!(out, " = True")?,
-            Value::Boolean(false) => write!(out, " = False")?,
-            Value::String(ref default) => write!(out, " = {default:?}")?,
-            Value::List(ref default) => match **default {
-                Value::Unit => {}
-                Value::Integer(default) => write!(out, " = {default}")?,
-                Value::Float(default) => write!(out, " = {default}")?,
-                Value::Boolean(true) => write!(out, " = True")?,
-                Value::Boolean(false) => write!(out, " = False")?,
-                Value::String(ref default) => write!(out, " = {default:?}")?,
-                Value::List(_) => unreachable!("3D lists are not supported"),
-            },
-        }
+    if let Some(ref default) = parameter.default
+        && !matches!(*default, Value::Unit)
+    {
+        write!(out, " = {}", value(default))?;
     }
 
     Ok(())
 }
 
+/// Renders a value as Python literal.
+///
+/// Record fields and enumeration variants are represented by their mangled identifiers.
+#[must_use]
+pub fn value(value: &Value) -> String {
+    match *value {
+        Value::Unit | Value::Optional(None) => "None".into(),
+        Value::Integer(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+        Value::Boolean(true) => "True".into(),
+        Value::Boolean(false) => "False".into(),
+        Value::String(ref value) => format!("{value:?}"),
+        Value::List(ref elements) => format!("[{}]", values(elements)),
+        Value::Tuple(ref elements) if elements.len() == 1 => format!("({},)", values(elements)),
+        Value::Tuple(ref elements) => format!("({})", values(elements)),
+        Value::Record(ref fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, field)| format!("\"{}\": {}", name.parameter(), self::value(field)))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Enumeration(ref variant) => format!("\"{}\"", variant.parameter()),
+        Value::Optional(Some(ref inner)) => self::value(inner),
+    }
+}
+
+fn values(values: &[Value]) -> String {
+    values.iter().map(value).collect::<Vec<_>>().join(", ")
+}
+
 #[must_use]
 pub fn identifier(identifier: &Identifier) -> String {
     // TODO add safeguards against reserved keywords
@@ -193,7 +886,15 @@ pub fn typ(descriptor: &TypeDescriptor) -> String {
         TypeDescriptor::Integer(_) => "int".into(),
         TypeDescriptor::Float => "float".into(),
         TypeDescriptor::Boolean => "bool".into(),
-        TypeDescriptor::String => "str".into(),
+        // enumeration variants are represented by their mangled names
+        TypeDescriptor::String | TypeDescriptor::Enumeration(_) => "str".into(),
         TypeDescriptor::List(ref element_type) => format!("list[{}]", typ(element_type)),
+        TypeDescriptor::Tuple(ref element_types) if element_types.is_empty() => "tuple[()]".into(),
+        TypeDescriptor::Tuple(ref element_types) => {
+            let element_types = element_types.iter().map(typ).collect::<Vec<_>>();
+            format!("tuple[{}]", element_types.join(", "))
+        }
+        TypeDescriptor::Record(_) => "dict".into(),
+        TypeDescriptor::Optional(ref inner_type) => format!("{} | None", typ(inner_type)),
     }
 }
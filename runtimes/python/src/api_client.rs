@@ -1,15 +1,30 @@
-use crate::api_client::py_api_client::{MaybeFulfilled, RequestHandle};
+use crate::{
+    api_client::py_api_client::{MaybeFulfilled, RequestHandle},
+    convert::{py_to_value, value_to_py},
+};
 use gam3du_framework_common::{
-    api::{ApiDescriptor, Identifier, TypeDescriptor, Value},
-    api_channel::ApiClientEndpoint,
-    message::{ErrorResponseMessage, ResponseMessage, ServerToClientMessage},
+    api::{ApiDescriptor, FunctionDescriptor, Identifier, Value},
+    api_channel::{ApiClientEndpoint, ChannelError},
+    message::{
+        BatchResponseMessage, ErrorResponseMessage, EventMessage, HandshakeResponseMessage,
+        RequestError, RequestId, RequestMessage, ResponseMessage, ServerToClientMessage,
+    },
 };
+use runtime_python_bindgen::{exception_class, PyIdentifier, EXCEPTION_CLASSES, REQUEST_ERROR};
 use rustpython_vm::{builtins::PyBaseExceptionRef, convert::IntoObject};
 use rustpython_vm::{
-    builtins::PyStr, convert::ToPyObject, function::PosArgs, pyclass, pymodule, PyObject,
-    PyObjectRef, PyPayload, PyRef, PyResult, TryFromBorrowedObject, VirtualMachine,
+    builtins::{PyStr, PyType},
+    function::{FuncArgs, KwArgs, PosArgs},
+    pyclass, pymodule, PyObject, PyObjectRef, PyPayload, PyRef, PyResult, TryFromBorrowedObject,
+    TryFromObject, VirtualMachine,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    time::Duration,
 };
-use tracing::{debug, error, trace};
+use tracing::{debug, error, info, trace, warn};
+use web_time::Instant;
 
 pub(crate) fn insert_api_client(
     vm: &VirtualMachine,
@@ -21,9 +36,65 @@ pub(crate) fn insert_api_client(
         .import(api_module, 0)
         .expect("Expect robot api must be imported");
 
+    let handshake = api.handshake();
+    let private_api = PrivateApi::wrap(api);
+    if let Err(error) = handshake {
+        warn!("cannot greet the api server: {error}");
+        private_api.disconnect(error);
+    }
     module
-        .set_attr("_private_api", PrivateApi::wrap(api).into_py(vm), vm)
+        .set_attr("_private_api", private_api.into_py(vm), vm)
         .expect("Set private api client");
+
+    insert_exception_classes(vm);
+}
+
+/// Adds the exception classes for rejected requests to the `api_client` module.
+///
+/// All api clients share the same classes, so they will only be created once.
+fn insert_exception_classes(vm: &VirtualMachine) {
+    let module = vm
+        .import("api_client", 0)
+        .expect("Expect api client module must be present");
+    if module.get_attr(REQUEST_ERROR, vm).is_ok() {
+        return;
+    }
+
+    let base = vm.ctx.new_exception_type(
+        "api_client",
+        REQUEST_ERROR,
+        Some(vec![vm.ctx.exceptions.runtime_error.to_owned()]),
+    );
+    module
+        .set_attr(REQUEST_ERROR, base.clone(), vm)
+        .expect("Set exception class");
+    for name in EXCEPTION_CLASSES {
+        let class = vm
+            .ctx
+            .new_exception_type("api_client", name, Some(vec![base.clone()]));
+        module
+            .set_attr(name, class, vm)
+            .expect("Set exception class");
+    }
+}
+
+/// Creates an instance of the exception class matching the reason for a rejected request.
+fn request_exception(vm: &VirtualMachine, error: &RequestError) -> PyBaseExceptionRef {
+    let class = vm
+        .import("api_client", 0)
+        .and_then(|module| module.get_attr(exception_class(error), vm))
+        .ok()
+        .and_then(|class| class.downcast::<PyType>().ok())
+        .unwrap_or_else(|| vm.ctx.exceptions.runtime_error.to_owned());
+    vm.new_exception_msg(class, error.to_string())
+}
+
+/// Creates a `ConnectionError` telling why the session with the server has ended.
+fn channel_exception(vm: &VirtualMachine, error: &ChannelError) -> PyBaseExceptionRef {
+    vm.new_exception_msg(
+        vm.ctx.exceptions.connection_error.to_owned(),
+        error.to_string(),
+    )
 }
 
 fn get_api_client(vm: &VirtualMachine, api_module: &str) -> PyRef<PrivateApi> {
@@ -52,16 +123,97 @@ fn get_api_client(vm: &VirtualMachine, api_module: &str) -> PyRef<PrivateApi> {
 #[pyclass(name = "PrivateApi", module = false)]
 struct PrivateApi {
     api: Box<dyn ApiClientEndpoint>,
+    /// Python callables to be invoked whenever the server sends an event
+    handlers: RefCell<HashMap<Identifier, Vec<PyObjectRef>>>,
+    /// All requests sent by this client which have not been polled to completion yet
+    requests: RefCell<HashMap<RequestId, RequestState>>,
+    /// The time to wait for a response unless specified otherwise for a single request
+    default_timeout: Cell<Option<Duration>>,
+    /// The reason the session with the server has ended, if it has
+    disconnected: RefCell<Option<ChannelError>>,
+    /// Requests collected for a batch which has not been sent yet
+    batch: RefCell<Option<Vec<RequestMessage>>>,
+}
+
+/// The progress of a single request as seen by the client
+enum RequestState {
+    /// The server did not answer yet; the request will be cancelled after the deadline passed
+    Pending { deadline: Option<Instant> },
+    /// The answer has arrived but has not been polled yet
+    Answered(ServerToClientMessage),
+    /// Nobody is waiting for this request anymore; its answer will be dropped
+    Cancelled,
+    /// The request is part of a batch; its outcome will be part of the batch's answer
+    Batched,
 }
 
 impl PrivateApi {
     fn wrap(api: Box<dyn ApiClientEndpoint>) -> Self {
-        Self { api }
+        Self {
+            api,
+            handlers: RefCell::default(),
+            requests: RefCell::default(),
+            default_timeout: Cell::default(),
+            disconnected: RefCell::default(),
+            batch: RefCell::default(),
+        }
     }
 
     fn into_py(self, vm: &VirtualMachine) -> PyObjectRef {
         vm.new_pyobj(self)
     }
+
+    /// Ends the session; only the first reason will be kept.
+    fn disconnect(&self, error: ChannelError) {
+        let mut disconnected = self.disconnected.borrow_mut();
+        if disconnected.is_none() {
+            info!(
+                "the session with api server `{}` has ended: {error}",
+                self.api.api().name
+            );
+            *disconnected = Some(error);
+        }
+    }
+
+    fn is_disconnected(&self) -> bool {
+        self.disconnected.borrow().is_some()
+    }
+
+    /// Raises a `ConnectionError` if the session has ended.
+    fn check_connected(&self, vm: &VirtualMachine) -> PyResult<()> {
+        match &*self.disconnected.borrow() {
+            Some(error) => Err(channel_exception(vm, error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Ends the session if a message could not be exchanged and raises a `ConnectionError`.
+    ///
+    /// A message rejected because the server cannot keep up raises a `BusyError` instead; the
+    /// session goes on.
+    fn check_channel<T>(
+        &self,
+        result: Result<T, ChannelError>,
+        vm: &VirtualMachine,
+    ) -> PyResult<T> {
+        result.map_err(|error| {
+            if matches!(error, ChannelError::Busy) {
+                return request_exception(vm, &RequestError::Busy);
+            }
+            let exception = channel_exception(vm, &error);
+            self.disconnect(error);
+            exception
+        })
+    }
+}
+
+impl Drop for PrivateApi {
+    fn drop(&mut self) {
+        if !self.is_disconnected() {
+            // the server might have gone away already
+            self.api.goodbye().ok();
+        }
+    }
 }
 
 impl std::fmt::Debug for PrivateApi {
@@ -90,19 +242,53 @@ pub(crate) mod py_api_client {
     use super::{FunctionNameConverter, PyResult, VirtualMachine};
     use gam3du_framework_common::message::RequestId;
     use rustpython_vm::{
-        builtins::PyBaseExceptionRef, function::PosArgs, pyclass, PyObjectRef, PyPayload,
-        TryFromObject,
+        builtins::PyBaseExceptionRef,
+        function::{KwArgs, PosArgs},
+        pyclass, PyObjectRef, PyPayload, TryFromObject,
     };
 
     #[pyfunction]
     fn message(
+        api_name: String,
         name: FunctionNameConverter,
         args: PosArgs,
-        // kwargs: KwArgs,
+        kwargs: KwArgs,
         vm: &VirtualMachine,
     ) -> PyResult<RequestHandle> {
         // just forward to a location outside of this macro so that the IDE can assist us
-        super::message(name, args, vm)
+        super::message(api_name, name, args, kwargs, vm)
+    }
+
+    #[pyfunction]
+    fn set_default_timeout(
+        api_name: String,
+        timeout: Option<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::set_default_timeout(api_name, timeout, vm)
+    }
+
+    #[pyfunction]
+    fn begin_batch(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::begin_batch(api_name, vm)
+    }
+
+    #[pyfunction]
+    fn send_batch(
+        api_name: String,
+        timeout: Option<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<RequestHandle> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::send_batch(api_name, timeout, vm)
+    }
+
+    #[pyfunction]
+    fn discard_batch(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::discard_batch(api_name, vm)
     }
 
     #[pyfunction]
@@ -114,9 +300,40 @@ pub(crate) mod py_api_client {
         super::poll(request, vm)
     }
 
+    #[pyfunction]
+    fn cancel(request: RequestHandle, vm: &VirtualMachine) {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::cancel(request, vm);
+    }
+
+    #[pyfunction]
+    fn subscribe(
+        api_name: String,
+        event: String,
+        handler: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::subscribe(api_name, event, handler, vm)
+    }
+
+    #[pyfunction]
+    fn unsubscribe(api_name: String, event: String, vm: &VirtualMachine) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::unsubscribe(api_name, event, vm)
+    }
+
+    #[pyfunction]
+    fn dispatch_events(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::dispatch_events(api_name, vm)
+    }
+
     #[pyclass(name, module = "py_api_client", no_attr)]
-    #[derive(Copy, Clone, PyPayload)]
+    #[derive(Clone, PyPayload)]
     pub(super) struct RequestHandle {
+        /// name of the internal module of the api client which sent the request
+        api_module: String,
         id: RequestId,
     }
 
@@ -128,19 +345,27 @@ pub(crate) mod py_api_client {
         reason = "required as separation between macro and non-macro code"
     )]
     impl RequestHandle {
-        pub(super) fn new(request_id: RequestId) -> Self {
-            Self { id: request_id }
+        pub(super) fn new(api_module: String, request_id: RequestId) -> Self {
+            Self {
+                api_module,
+                id: request_id,
+            }
         }
 
         pub(super) fn inner(&self) -> RequestId {
             self.id
         }
+
+        pub(super) fn api_module(&self) -> &str {
+            &self.api_module
+        }
     }
 
     impl std::fmt::Debug for RequestHandle {
         fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             formatter
                 .debug_struct("RequestHandle")
+                .field("api_module", &self.api_module)
                 .field("request_id", &self.id)
                 .finish()
         }
@@ -149,7 +374,7 @@ pub(crate) mod py_api_client {
     impl TryFromObject for RequestHandle {
         fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
             obj.payload()
-                .copied()
+                .cloned()
                 .ok_or_else(|| vm.new_value_error("invalid pending request".to_owned()))
         }
     }
@@ -205,96 +430,418 @@ pub(crate) mod py_api_client {
     }
 }
 
+/// Returns the name of the internal module the client of the given api has been registered as.
+fn api_module_name(api_name: String, vm: &VirtualMachine) -> PyResult<String> {
+    let api_name = Identifier::try_from(api_name)
+        .map_err(|error| vm.new_value_error(format!("invalid api name: {error}")))?;
+    Ok(format!("{}_api_internal", api_name.module()))
+}
+
+/// Processes all messages received so far.
+///
+/// Events are dispatched to their handlers right away while answers are kept with their requests
+/// until they get polled. This way, the answers may arrive in any order.
+///
+/// A vanished server ends the session just like a server saying goodbye; this is not an error yet,
+/// as all answers received before are still available.
+fn receive(private_api: &PrivateApi, vm: &VirtualMachine) -> PyResult<()> {
+    while !private_api.is_disconnected() {
+        let message = match private_api.api.poll_response() {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(error) => {
+                private_api.disconnect(error);
+                break;
+            }
+        };
+
+        let id = match message {
+            ServerToClientMessage::Event(event) => {
+                dispatch_event(private_api, event, vm)?;
+                continue;
+            }
+            ServerToClientMessage::HandshakeResponse(response) => {
+                check_handshake(response, vm)?;
+                continue;
+            }
+            ServerToClientMessage::Status(status) => {
+                trace!("request {} is {:?}", status.id, status.status);
+                continue;
+            }
+            ServerToClientMessage::Goodbye => {
+                private_api.disconnect(ChannelError::Disconnected);
+                continue;
+            }
+            ServerToClientMessage::Response(ResponseMessage { id, .. })
+            | ServerToClientMessage::ErrorResponse(ErrorResponseMessage { id, .. })
+            | ServerToClientMessage::BatchResponse(BatchResponseMessage { id, .. })
+            | ServerToClientMessage::Cancelled(id) => id,
+        };
+
+        let mut requests = private_api.requests.borrow_mut();
+        match requests.get_mut(&id) {
+            Some(state @ RequestState::Pending { .. }) => {
+                *state = RequestState::Answered(message);
+            }
+            Some(RequestState::Cancelled) => {
+                debug!("dropping the answer to cancelled request {id}");
+                requests.remove(&id);
+            }
+            Some(RequestState::Answered(_)) => {
+                warn!("dropping a duplicate answer to request {id}");
+            }
+            Some(RequestState::Batched) => {
+                warn!("dropping an answer to request {id} which is part of a batch");
+            }
+            None => warn!("dropping the answer to unknown request {id}"),
+        }
+    }
+
+    Ok(())
+}
+
 fn poll(request: RequestHandle, vm: &VirtualMachine) -> Result<MaybeFulfilled, PyBaseExceptionRef> {
     // error!("native poll({})", request.inner());
-    let api_module = "robot_control_api_internal";
-    let private_api = get_api_client(vm, api_module);
+    let private_api = get_api_client(vm, request.api_module());
     let message_id = request.inner();
 
-    let response = private_api.api.poll_response();
+    receive(&private_api, vm)?;
+
+    let state = private_api.requests.borrow_mut().remove(&message_id);
+    let response = match state {
+        Some(RequestState::Answered(response)) => response,
+        Some(RequestState::Pending {
+            deadline: Some(deadline),
+        }) if deadline <= Instant::now() => {
+            debug!("request {message_id} has not been answered in time");
+            private_api
+                .requests
+                .borrow_mut()
+                .insert(message_id, RequestState::Cancelled);
+            if let Err(error) = private_api.api.cancel(message_id) {
+                private_api.disconnect(error);
+            }
+            return Err(request_exception(vm, &RequestError::DeadlineExceeded));
+        }
+        Some(state @ RequestState::Pending { .. }) => {
+            // the answer will never arrive once the session has ended
+            private_api.check_connected(vm)?;
+            private_api.requests.borrow_mut().insert(message_id, state);
+            return Ok(MaybeFulfilled::new(message_id));
+        }
+        Some(state @ RequestState::Cancelled) => {
+            private_api.requests.borrow_mut().insert(message_id, state);
+            return Err(request_exception(vm, &RequestError::Cancelled));
+        }
+        Some(RequestState::Batched) => {
+            // the outcome will be reported by the batch
+            return Ok(MaybeFulfilled::new(message_id).with_value(vm.ctx.none()));
+        }
+        None => {
+            return Err(vm.new_value_error(format!(
+                "request {message_id} is unknown or has been completed already"
+            )));
+        }
+    };
 
     match response {
-        None => Ok(MaybeFulfilled::new(message_id)),
-        Some(response) => match response {
-            ServerToClientMessage::Response(ResponseMessage { id, result }) => {
-                assert_eq!(message_id, id, "request-response id mismatch");
-                trace!("command successfully returned: {result:?}");
-                let value = match result {
-                    Value::Unit => vm.ctx.none(),
-                    Value::Integer(_) => todo!(),
-                    Value::Float(_) => todo!(),
-                    Value::Boolean(value) => value.to_pyobject(vm),
-                    Value::String(_) => todo!(),
-                    Value::List(_) => todo!(),
-                };
-                Ok(MaybeFulfilled::new(message_id).with_value(value.into_object()))
+        ServerToClientMessage::Response(ResponseMessage { result, .. }) => {
+            trace!("command successfully returned: {result:?}");
+            let value = value_to_py(vm, result);
+            Ok(MaybeFulfilled::new(message_id).with_value(value))
+        }
+        ServerToClientMessage::ErrorResponse(ErrorResponseMessage { error, .. }) => {
+            error!("command returned an error: {error}");
+            Err(request_exception(vm, &error))
+        }
+        ServerToClientMessage::BatchResponse(BatchResponseMessage { outcomes, .. }) => {
+            trace!("batch completed with {} outcomes", outcomes.len());
+            let outcomes = outcomes
+                .into_iter()
+                .map(|outcome| match outcome {
+                    Ok(result) => value_to_py(vm, result),
+                    Err(error) => request_exception(vm, &error).into_object(),
+                })
+                .collect();
+            let outcomes = vm.ctx.new_list(outcomes).into_object();
+            Ok(MaybeFulfilled::new(message_id).with_value(outcomes))
+        }
+        ServerToClientMessage::Cancelled(_) => Err(request_exception(vm, &RequestError::Cancelled)),
+        ServerToClientMessage::Event(_)
+        | ServerToClientMessage::HandshakeResponse(_)
+        | ServerToClientMessage::Status(_)
+        | ServerToClientMessage::Goodbye => {
+            unreachable!("only answers are kept with their requests")
+        }
+    }
+}
+
+/// Asks the server to abort a pending request; its response will be dropped if it arrives anyway.
+fn cancel(request: RequestHandle, vm: &VirtualMachine) {
+    let private_api = get_api_client(vm, request.api_module());
+    let id = request.inner();
+
+    let mut requests = private_api.requests.borrow_mut();
+    match requests.get_mut(&id) {
+        Some(state @ RequestState::Pending { .. }) => {
+            debug!("cancelling request {id}");
+            *state = RequestState::Cancelled;
+            if let Err(error) = private_api.api.cancel(id) {
+                // nothing to cancel on a server that has gone away
+                private_api.disconnect(error);
             }
-            ServerToClientMessage::ErrorResponse(ErrorResponseMessage { id, message }) => {
-                assert_eq!(message_id, id, "request-response id mismatch");
-                error!("command returned an error: {message}");
-                let error = vm
-                    .invoke_exception(
-                        vm.ctx.exceptions.runtime_error.to_owned(),
-                        vec![vm.ctx.new_str(message).to_pyobject(vm)],
-                    )
-                    .expect("Constructor of \"RuntimeError\" should not fail");
-                vm.print_exception(error.clone());
-                Err(error)
+        }
+        Some(RequestState::Answered(_)) => {
+            debug!("dropping the answer to request {id} as it has been cancelled");
+            requests.remove(&id);
+        }
+        Some(RequestState::Batched) => {
+            debug!("removing request {id} from the batch");
+            requests.remove(&id);
+            if let Some(batch) = private_api.batch.borrow_mut().as_mut() {
+                batch.retain(|request| request.id != id);
             }
-        },
+        }
+        Some(RequestState::Cancelled) | None => {}
+    }
+}
+
+fn subscribe(
+    api_name: String,
+    event: String,
+    handler: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+
+    let event = event_identifier(vm, private_api.api.api(), event)?;
+    if !handler.is_callable() {
+        return Err(vm.new_type_error("event handler must be callable".to_owned()));
+    }
+
+    let mut handlers = private_api.handlers.borrow_mut();
+    let event_handlers = handlers.entry(event.clone()).or_default();
+    if event_handlers.is_empty() {
+        debug!("subscribing to event `{event}`");
+        private_api.check_channel(private_api.api.subscribe(event), vm)?;
+    }
+    event_handlers.push(handler);
+
+    Ok(())
+}
+
+fn unsubscribe(api_name: String, event: String, vm: &VirtualMachine) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+
+    let event = event_identifier(vm, private_api.api.api(), event)?;
+    if private_api.handlers.borrow_mut().remove(&event).is_some() {
+        debug!("unsubscribing from event `{event}`");
+        private_api.check_channel(private_api.api.unsubscribe(event), vm)?;
+    }
+
+    Ok(())
+}
+
+/// Invokes the handlers of all events that have arrived so far.
+///
+/// Any responses received in the meantime will be kept for the next call to `poll`.
+/// Raises a `ConnectionError` once the session with the server has ended.
+fn dispatch_events(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+    receive(&private_api, vm)?;
+    private_api.check_connected(vm)
+}
+
+/// Raises an exception if the server rejected the api revision of this client.
+fn check_handshake(response: HandshakeResponseMessage, vm: &VirtualMachine) -> PyResult<()> {
+    let HandshakeResponseMessage {
+        version,
+        fingerprint,
+        result,
+    } = response;
+
+    match result {
+        Ok(()) => {
+            debug!("server accepted the handshake (api version {version}, {fingerprint:016x})");
+            Ok(())
+        }
+        Err(error) => {
+            error!("server rejected the handshake: {error}");
+            Err(vm.new_runtime_error(format!("incompatible api: {error}")))
+        }
     }
 }
 
+fn dispatch_event(
+    private_api: &PrivateApi,
+    event: EventMessage,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let EventMessage { event, arguments } = event;
+
+    // clone the handlers so that they may (un)subscribe while being called
+    let handlers = private_api
+        .handlers
+        .borrow()
+        .get(&event)
+        .cloned()
+        .unwrap_or_default();
+    if handlers.is_empty() {
+        trace!("no handler registered for event `{event}`");
+    }
+
+    for handler in handlers {
+        let args = arguments
+            .iter()
+            .cloned()
+            .map(|argument| value_to_py(vm, argument))
+            .collect::<Vec<_>>();
+        handler.call(FuncArgs::from(args), vm)?;
+    }
+
+    Ok(())
+}
+
 fn message(
-    // api_name: Identifier,
+    api_name: String,
     name: FunctionNameConverter,
     args: PosArgs,
-    // kwargs: KwArgs,
+    mut kwargs: KwArgs,
     vm: &VirtualMachine,
 ) -> PyResult<RequestHandle> {
-    // TODO move the api selection into the caller
-    // let api_name = Identifier("robot".into());
-    // let vm_id = vm.wasm_id.as_ref().unwrap();
     debug!("native message()");
 
-    let api_module = "robot_control_api_internal";
-    let private_api = get_api_client(vm, api_module);
+    let api_module = api_module_name(api_name, vm)?;
+    let private_api = get_api_client(vm, &api_module);
+    private_api.check_connected(vm)?;
     let api = private_api.api.api();
 
+    // validation keeps the api from using this name for a parameter of its own
+    let timeout = match kwargs.pop_kwarg("timeout") {
+        Some(timeout) if !vm.is_none(&timeout) => Some(timeout_duration(timeout, vm)?),
+        _ => private_api.default_timeout.get(),
+    };
+
     let command = name.convert(vm, api)?;
 
     let function = api.functions.get(&command).expect("unknown command");
-    // TODO check that the number of given arguments matches the expected count
-    let arguments = function
-        .parameters
-        .iter()
-        .zip(args)
-        .map(|(param, arg)| match &param.typ {
-            TypeDescriptor::Integer(range) => {
-                let int = arg.try_int(vm).unwrap();
-                let primitive = int.try_to_primitive::<i64>(vm).unwrap();
-                assert!(primitive >= range.start, "integer parameter out of range");
-                assert!(primitive <= range.end, "integer parameter out of range");
-                Value::Integer(primitive)
-            }
-            TypeDescriptor::Float => {
-                let int = arg.try_float(vm).unwrap();
-                #[allow(
-                    clippy::cast_possible_truncation,
-                    reason = "the api only supports f32 at the moment"
-                )]
-                let primitive = int.to_f64() as f32;
-                Value::Float(primitive)
-            }
-            TypeDescriptor::Boolean => todo!(),
-            TypeDescriptor::String => todo!(),
-            TypeDescriptor::List(_type_descriptor) => todo!(),
-        })
-        .collect();
+    let arguments = ParameterConverter::new(args, kwargs).convert(vm, function)?;
+
+    if let Some(batch) = private_api.batch.borrow_mut().as_mut() {
+        let request = RequestMessage::new(command, arguments, timeout);
+        let id = request.id;
+        batch.push(request);
+        private_api
+            .requests
+            .borrow_mut()
+            .insert(id, RequestState::Batched);
+        return Ok(RequestHandle::new(api_module, id));
+    }
+
+    let id = private_api.check_channel(
+        private_api
+            .api
+            .send_command_with_timeout(command, arguments, timeout),
+        vm,
+    )?;
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    private_api
+        .requests
+        .borrow_mut()
+        .insert(id, RequestState::Pending { deadline });
+
+    Ok(RequestHandle::new(api_module, id))
+}
+
+/// Starts collecting requests for a batch instead of sending them right away.
+fn begin_batch(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+    let mut batch = private_api.batch.borrow_mut();
+    if batch.is_some() {
+        return Err(vm.new_runtime_error("batches cannot be nested".to_owned()));
+    }
+    *batch = Some(Vec::new());
+    Ok(())
+}
+
+/// Sends all requests collected since `begin_batch` as a single batch.
+///
+/// Polling the returned handle yields a list with the outcome of each request performed, which is
+/// either its result or the exception describing its failure.
+fn send_batch(
+    api_name: String,
+    timeout: Option<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<RequestHandle> {
+    let api_module = api_module_name(api_name, vm)?;
+    let private_api = get_api_client(vm, &api_module);
+    let Some(requests) = private_api.batch.take() else {
+        return Err(vm.new_runtime_error("no batch has been started".to_owned()));
+    };
+    private_api.check_connected(vm)?;
+    let timeout = timeout
+        .map(|timeout| timeout_duration(timeout, vm))
+        .transpose()?;
+
+    {
+        let mut pending = private_api.requests.borrow_mut();
+        for request in &requests {
+            pending.remove(&request.id);
+        }
+    }
+    debug!("sending a batch of {} requests", requests.len());
+    let id = private_api.check_channel(private_api.api.send_batch(requests), vm)?;
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    private_api
+        .requests
+        .borrow_mut()
+        .insert(id, RequestState::Pending { deadline });
+
+    Ok(RequestHandle::new(api_module, id))
+}
+
+/// Drops all requests collected since `begin_batch` without sending them.
+fn discard_batch(api_name: String, vm: &VirtualMachine) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+    for request in private_api.batch.take().unwrap_or_default() {
+        private_api.requests.borrow_mut().remove(&request.id);
+    }
+    Ok(())
+}
+
+/// Sets the time to wait for responses to requests which do not specify a timeout on their own.
+fn set_default_timeout(
+    api_name: String,
+    timeout: Option<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let private_api = get_api_client(vm, &api_module_name(api_name, vm)?);
+    let timeout = timeout
+        .map(|timeout| timeout_duration(timeout, vm))
+        .transpose()?;
+    private_api.default_timeout.set(timeout);
+    Ok(())
+}
+
+/// Converts a timeout given in seconds.
+fn timeout_duration(timeout: PyObjectRef, vm: &VirtualMachine) -> PyResult<Duration> {
+    let seconds = f64::try_from_object(vm, timeout)?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|error| vm.new_value_error(format!("invalid timeout {seconds}: {error}")))
+}
 
-    Ok(RequestHandle::new(
-        private_api.api.send_command(command, arguments),
-    ))
+fn event_identifier(
+    vm: &VirtualMachine,
+    api: &ApiDescriptor,
+    name: String,
+) -> PyResult<Identifier> {
+    let identifier = Identifier::try_from(name)
+        .map_err(|error| vm.new_value_error(format!("invalid event name: {error}")))?;
+    if api.events.contains_key(&identifier) {
+        Ok(identifier)
+    } else {
+        Err(vm.new_value_error(format!("{identifier} is not a known event name")))
+    }
 }
 
 struct FunctionNameConverter(Option<String>);
@@ -322,35 +869,62 @@ impl<'obj> TryFromBorrowedObject<'obj> for FunctionNameConverter {
     }
 }
 
-// struct ParameterConverter(PosArgs, KwArgs);
-
-// impl ParameterConverter {
-//     fn new(pos_args: PosArgs, kwargs: KwArgs) -> Self {
-//         Self(pos_args, kwargs)
-//     }
-
-//     fn convert(
-//         self,
-//         _vm: &VirtualMachine,
-//         _api: &ApiDescriptor,
-//         _command: &Identifier,
-//     ) -> PyResult<Vec<Value>> {
-//         // TODO: Extract parameters
-//         std::hint::black_box(&self.0);
-//         std::hint::black_box(&self.1);
-//         // let robot_event = api.find(command);
-//         // let actuals = vec![];
-//         // for parameter in robot_event.parameters() {
-//         //     if let Some(actual) = find_positional_match(parameter, self.0) {
-//         //         actuals.push(actual);
-//         //         continue;
-//         //     }
-//         //     if let Some(actual) = find_keyword_match(parameter, self.1) {
-//         //         actuals.push(actual);
-//         //         continue;
-//         //     }
-//         // }
-//         // return Ok(actuals)
-//         Ok(vec![])
-//     }
-// }
+/// Matches the arguments of a call against the parameters of the function.
+///
+/// Arguments may be passed by position or by keyword, using the mangled parameter names
+/// (e.g. `duration`). Omitted parameters take their default values.
+struct ParameterConverter(PosArgs, KwArgs);
+
+impl ParameterConverter {
+    fn new(pos_args: PosArgs, kwargs: KwArgs) -> Self {
+        Self(pos_args, kwargs)
+    }
+
+    /// Returns the arguments in the order of the parameters.
+    ///
+    /// # Errors
+    ///
+    /// Raises a `TypeError` for missing, duplicate or unexpected arguments, just like Python does
+    /// for its own functions. Arguments not matching their parameter are reported by
+    /// [`py_to_value`].
+    fn convert(self, vm: &VirtualMachine, function: &FunctionDescriptor) -> PyResult<Vec<Value>> {
+        let Self(pos_args, mut kwargs) = self;
+        let name = function.name.function();
+        let pos_args = pos_args.into_vec();
+        if pos_args.len() > function.parameters.len() {
+            return Err(vm.new_type_error(format!(
+                "{name}() takes {} positional arguments but {} were given",
+                function.parameters.len(),
+                pos_args.len()
+            )));
+        }
+
+        let mut pos_args = pos_args.into_iter();
+        let arguments = function
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let keyword = parameter.name.parameter().to_string();
+                let positional = pos_args.next();
+                match (positional, kwargs.pop_kwarg(&keyword), &parameter.default) {
+                    (Some(_), Some(_), _) => Err(vm.new_type_error(format!(
+                        "{name}() got multiple values for argument `{keyword}`"
+                    ))),
+                    (Some(argument), None, _) | (None, Some(argument), _) => {
+                        py_to_value(vm, &parameter.typ, &argument)
+                    }
+                    (None, None, Some(default)) => Ok(default.clone()),
+                    (None, None, None) => Err(vm
+                        .new_type_error(format!("{name}() missing required argument `{keyword}`"))),
+                }
+            })
+            .collect::<PyResult<_>>()?;
+
+        if let Some((keyword, _)) = kwargs.into_iter().next() {
+            return Err(vm.new_type_error(format!(
+                "{name}() got an unexpected keyword argument `{keyword}`"
+            )));
+        }
+        Ok(arguments)
+    }
+}
@@ -1,20 +1,249 @@
+use crate::convert::py_to_value;
 use gam3du_framework_common::{
     api::{Identifier, Value},
-    api_channel::ApiServerEndpoint,
-    message::RequestId,
+    api_channel::{ApiServerEndpoint, ChannelError},
+    message::{BatchMessage, RequestError, RequestId, RequestMessage},
 };
 use runtime_python_bindgen::PyIdentifier;
-use rustpython_vm::{pyclass, pymodule, PyObjectRef, PyPayload, PyRef, VirtualMachine};
+use rustpython_vm::{
+    function::PosArgs, pyclass, pymodule, PyObject, PyObjectRef, PyPayload, PyRef, PyResult,
+    VirtualMachine,
+};
 use std::{
     borrow::BorrowMut,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    mem,
     sync::{Arc, Mutex},
 };
+use tracing::{debug, info, trace, warn};
+use web_time::Instant;
 
-pub(crate) fn insert_api_server(
-    vm: &VirtualMachine,
-    api_module: &str,
-    api: Arc<Mutex<dyn ApiServerEndpoint>>,
-) {
+/// An api server endpoint together with the events its client has subscribed to.
+pub(crate) struct ApiServer {
+    pub(crate) endpoint: Box<dyn ApiServerEndpoint>,
+    pub(crate) subscriptions: HashSet<Identifier>,
+    /// Requests that have been passed to Python but not been answered or cancelled yet
+    pub(crate) pending: HashMap<RequestId, PendingRequest>,
+    /// Batches that have not been answered yet in the order they have been received
+    batches: Vec<Batch>,
+    /// Whether the session with the client is still going on
+    pub(crate) connected: bool,
+    /// Whether the client has introduced itself; requests are rejected until it has been accepted
+    pub(crate) handshake: Handshake,
+}
+
+/// The outcome of the client's handshake
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Handshake {
+    /// The client has not sent a handshake yet
+    Missing,
+    /// The client is compatible with the api
+    Accepted,
+    /// The client is incompatible with the api
+    Rejected,
+}
+
+/// A request that has been passed to Python
+pub(crate) struct PendingRequest {
+    /// The function being called; its answer must match the return type
+    pub(crate) command: Identifier,
+    /// The time the request expires, if any
+    pub(crate) deadline: Option<Instant>,
+}
+
+/// The progress of a batch whose requests are being performed one after another
+struct Batch {
+    /// The id the batch will be answered with
+    id: RequestId,
+    /// The requests that have not been started yet
+    remaining: VecDeque<RequestMessage>,
+    /// The outcomes of the requests performed so far
+    outcomes: Vec<Result<Value, RequestError>>,
+    /// The request currently being performed, if any
+    current: Option<RequestId>,
+}
+
+impl ApiServer {
+    pub(crate) fn new(endpoint: Box<dyn ApiServerEndpoint>) -> Self {
+        Self {
+            endpoint,
+            subscriptions: HashSet::new(),
+            pending: HashMap::new(),
+            batches: Vec::new(),
+            connected: true,
+            handshake: Handshake::Missing,
+        }
+    }
+
+    /// Fails unless the client's handshake has been accepted.
+    pub(crate) fn check_handshake(&self) -> Result<(), RequestError> {
+        match self.handshake {
+            Handshake::Accepted => Ok(()),
+            Handshake::Missing | Handshake::Rejected => Err(RequestError::HandshakeRequired),
+        }
+    }
+
+    /// Sends a message to the client unless the session has ended.
+    ///
+    /// Failing to deliver the message ends the session, unless the client just cannot keep up with
+    /// the events; endpoints never reject answers for the client being busy.
+    pub(crate) fn send(
+        &mut self,
+        send: impl FnOnce(&dyn ApiServerEndpoint) -> Result<(), ChannelError>,
+    ) {
+        if !self.connected {
+            trace!("dropping message as the session has ended");
+            return;
+        }
+        match send(&*self.endpoint) {
+            Ok(()) => {}
+            Err(ChannelError::Busy) => warn!("dropping event as the client cannot keep up"),
+            Err(error) => self.disconnect(error),
+        }
+    }
+
+    /// Delivers the outcome of a pending request to the client or to the batch it belongs to.
+    ///
+    /// Outcomes of requests that are not pending anymore (e.g. because they have been cancelled)
+    /// will be dropped.
+    pub(crate) fn answer(&mut self, id: RequestId, outcome: Result<Value, RequestError>) {
+        if self.pending.remove(&id).is_none() {
+            debug!("dropping the answer to request {id} as it is not pending anymore");
+            return;
+        }
+        self.deliver(id, outcome);
+    }
+
+    /// Delivers the outcome of a request to the client or to the batch it belongs to.
+    ///
+    /// A batch is answered as soon as its last request succeeded or any request failed.
+    pub(crate) fn deliver(&mut self, id: RequestId, outcome: Result<Value, RequestError>) {
+        let Some(batch) = self
+            .batches
+            .iter_mut()
+            .find(|batch| batch.current == Some(id))
+        else {
+            match outcome {
+                Ok(result) => self.send(|endpoint| endpoint.send_response(id, result)),
+                Err(error) => self.send(|endpoint| endpoint.send_error(id, error)),
+            }
+            return;
+        };
+
+        batch.current = None;
+        let failed = outcome.is_err();
+        batch.outcomes.push(outcome);
+        if !failed && !batch.remaining.is_empty() {
+            return;
+        }
+
+        let batch_id = batch.id;
+        let outcomes = mem::take(&mut batch.outcomes);
+        self.batches.retain(|other| other.id != batch_id);
+        debug!("batch {batch_id} has been completed");
+        self.send(|endpoint| endpoint.send_batch_response(batch_id, outcomes));
+    }
+
+    /// Accepts a batch whose requests will be handed out by [`Self::next_batch_request`].
+    pub(crate) fn start_batch(&mut self, batch: BatchMessage) {
+        let BatchMessage { id, requests } = batch;
+        if requests.is_empty() {
+            self.send(|endpoint| endpoint.send_batch_response(id, Vec::new()));
+            return;
+        }
+        self.batches.push(Batch {
+            id,
+            remaining: requests.into(),
+            outcomes: Vec::new(),
+            current: None,
+        });
+    }
+
+    /// Returns the next request of any batch that is not waiting for a request to complete.
+    pub(crate) fn next_batch_request(&mut self) -> Option<RequestMessage> {
+        let batch = self
+            .batches
+            .iter_mut()
+            .find(|batch| batch.current.is_none() && !batch.remaining.is_empty())?;
+        let request = batch.remaining.pop_front()?;
+        batch.current = Some(request.id);
+        Some(request)
+    }
+
+    /// Aborts a pending request or batch on behalf of the client.
+    ///
+    /// Returns the request that needs to be aborted by the Python module, if any.
+    pub(crate) fn cancel(&mut self, id: RequestId) -> Option<RequestId> {
+        if let Some(index) = self.batches.iter().position(|batch| batch.id == id) {
+            let batch = self.batches.remove(index);
+            self.send(|endpoint| endpoint.send_cancelled(id));
+            return batch
+                .current
+                .filter(|current| self.pending.remove(current).is_some());
+        }
+
+        self.pending.remove(&id)?;
+        if self.batches.iter().any(|batch| batch.current == Some(id)) {
+            // the batch fails just like it would for any other error
+            self.deliver(id, Err(RequestError::Cancelled));
+        } else {
+            self.send(|endpoint| endpoint.send_cancelled(id));
+        }
+        Some(id)
+    }
+
+    /// Returns whether a request or batch is still waiting for its answer.
+    pub(crate) fn is_pending(&self, id: RequestId) -> bool {
+        self.pending.contains_key(&id) || self.batches.iter().any(|batch| batch.id == id)
+    }
+
+    /// Ends the session; the pending requests will be abandoned on the next wake of the runtime.
+    pub(crate) fn disconnect(&mut self, reason: impl Display) {
+        if self.connected {
+            info!(
+                "the session with the client of api `{}` has ended: {reason}",
+                self.endpoint.api().name
+            );
+            self.connected = false;
+            self.subscriptions.clear();
+        }
+    }
+
+    /// Removes all pending requests once the session has ended and returns their ids.
+    pub(crate) fn abandon(&mut self) -> Vec<RequestId> {
+        if self.connected {
+            return Vec::new();
+        }
+        self.batches.clear();
+        self.pending.drain().map(|(id, _)| id).collect()
+    }
+
+    /// Removes all pending requests whose deadline lies before `now` and returns their ids.
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<RequestId> {
+        let expired = self
+            .pending
+            .iter()
+            .filter(|&(_, request)| request.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        for id in &expired {
+            self.pending.remove(id);
+        }
+        expired
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        if self.connected {
+            // the client might have gone away already
+            self.endpoint.goodbye().ok();
+        }
+    }
+}
+
+pub(crate) fn insert_api_server(vm: &VirtualMachine, api_module: &str, api: Arc<Mutex<ApiServer>>) {
     let api_module = vm.ctx.intern_str(api_module);
     let module = vm.import(api_module, 0).unwrap_or_else(|exception| {
         vm.print_exception(exception);
@@ -47,11 +276,11 @@ fn get_api_server(vm: &VirtualMachine, api_module: &str) -> PyRef<PrivateApiServ
 
 #[pyclass(name = "PrivateApiServer", module = false)]
 struct PrivateApiServer {
-    api: Arc<Mutex<dyn ApiServerEndpoint>>,
+    api: Arc<Mutex<ApiServer>>,
 }
 
 impl PrivateApiServer {
-    fn wrap(api: Arc<Mutex<dyn ApiServerEndpoint>>) -> Self {
+    fn wrap(api: Arc<Mutex<ApiServer>>) -> Self {
         Self { api }
     }
 
@@ -76,13 +305,53 @@ impl PyPayload for PrivateApiServer {
 
 #[pymodule]
 pub(crate) mod py_api_server {
-    use super::VirtualMachine;
+    use super::{PosArgs, PyObjectRef, PyResult, RequestError, VirtualMachine};
 
     #[pyfunction]
     fn send_boolean_response(api_name: String, request_id: u128, value: bool, vm: &VirtualMachine) {
         // just forward to a location outside of this macro so that the IDE can assist us
         super::send_boolean_response(api_name, request_id, value, vm);
     }
+
+    #[pyfunction]
+    fn send_response(
+        api_name: String,
+        request_id: u128,
+        value: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::send_response(api_name, request_id, &value, vm)
+    }
+
+    #[pyfunction]
+    fn send_busy_response(api_name: String, request_id: u128, vm: &VirtualMachine) {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::send_error_response(api_name, request_id, RequestError::Busy, vm);
+    }
+
+    #[pyfunction]
+    fn send_rejected_response(
+        api_name: String,
+        request_id: u128,
+        reason: String,
+        vm: &VirtualMachine,
+    ) {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        let error = RequestError::EngineRejected { reason };
+        super::send_error_response(api_name, request_id, error, vm);
+    }
+
+    #[pyfunction]
+    fn send_event(
+        api_name: String,
+        event_name: String,
+        args: PosArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        // just forward to a location outside of this macro so that the IDE can assist us
+        super::send_event(api_name, event_name, args, vm)
+    }
 }
 
 fn send_boolean_response(api_name: String, request_id: u128, value: bool, vm: &VirtualMachine) {
@@ -95,9 +364,123 @@ fn send_boolean_response(api_name: String, request_id: u128, value: bool, vm: &V
 
     let request_id = RequestId::try_from(request_id).unwrap();
 
-    private_api_server_module
-        .api
-        .lock()
-        .unwrap()
-        .send_response(request_id, Value::Boolean(value));
+    let mut api_server = private_api_server_module.api.lock().unwrap();
+    api_server.answer(request_id, Ok(Value::Boolean(value)));
+}
+
+/// Answers a request with a value of the type returned by the function being called.
+///
+/// Functions without return type are answered by `None`.
+fn send_response(
+    api_name: String,
+    request_id: u128,
+    value: &PyObject,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let api_server_module_name = format!(
+        "{}_api_internal",
+        Identifier::try_from(api_name).unwrap().file()
+    );
+    let mut binding = get_api_server(vm, &api_server_module_name);
+    let private_api_server_module = binding.borrow_mut();
+
+    let request_id = RequestId::try_from(request_id).unwrap();
+
+    let returns = {
+        let api_server = private_api_server_module.api.lock().unwrap();
+        let Some(request) = api_server.pending.get(&request_id) else {
+            debug!("dropping the answer to request {request_id} as it is not pending anymore");
+            return Ok(());
+        };
+        api_server
+            .endpoint
+            .api()
+            .functions
+            .get(&request.command)
+            .and_then(|function| function.returns.as_ref())
+            .map(|returns| returns.typ.clone())
+    };
+
+    // the lock must not be held while calling into Python
+    let result = match returns {
+        Some(typ) => py_to_value(vm, &typ, value)?,
+        None if vm.is_none(value) => Value::Unit,
+        None => return Err(vm.new_type_error("the function does not return a value".to_owned())),
+    };
+
+    let mut api_server = private_api_server_module.api.lock().unwrap();
+    api_server.answer(request_id, Ok(result));
+    Ok(())
+}
+
+/// Tells the client that its request could not be performed.
+fn send_error_response(
+    api_name: String,
+    request_id: u128,
+    error: RequestError,
+    vm: &VirtualMachine,
+) {
+    let api_server_module_name = format!(
+        "{}_api_internal",
+        Identifier::try_from(api_name).unwrap().file()
+    );
+    let mut binding = get_api_server(vm, &api_server_module_name);
+    let private_api_server_module = binding.borrow_mut();
+
+    let request_id = RequestId::try_from(request_id).unwrap();
+
+    let mut api_server = private_api_server_module.api.lock().unwrap();
+    api_server.answer(request_id, Err(error));
+}
+
+/// Sends an event to the client, but only if the client has subscribed to it.
+fn send_event(
+    api_name: String,
+    event_name: String,
+    args: PosArgs,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let api_server_module_name = format!(
+        "{}_api_internal",
+        Identifier::try_from(api_name).unwrap().file()
+    );
+    let mut binding = get_api_server(vm, &api_server_module_name);
+    let private_api_server_module = binding.borrow_mut();
+
+    let event = Identifier::try_from(event_name)
+        .map_err(|error| vm.new_value_error(format!("invalid event name: {error}")))?;
+    let parameter_types = {
+        let api_server = private_api_server_module.api.lock().unwrap();
+        let Some(descriptor) = api_server.endpoint.api().events.get(&event) else {
+            return Err(vm.new_value_error(format!("{event} is not a known event name")));
+        };
+        if !api_server.subscriptions.contains(&event) {
+            trace!("dropping event `{event}` as the client did not subscribe to it");
+            return Ok(());
+        }
+        descriptor
+            .parameters
+            .iter()
+            .map(|parameter| parameter.typ.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let args = args.into_vec();
+    if args.len() != parameter_types.len() {
+        return Err(vm.new_type_error(format!(
+            "event `{event}` takes {} arguments but {} were given",
+            parameter_types.len(),
+            args.len()
+        )));
+    }
+    // the lock must not be held while calling into Python
+    let arguments = parameter_types
+        .iter()
+        .zip(&args)
+        .map(|(typ, arg)| py_to_value(vm, typ, arg))
+        .collect::<PyResult<_>>()?;
+
+    let mut api_server = private_api_server_module.api.lock().unwrap();
+    api_server.send(|endpoint| endpoint.send_event(event, arguments));
+    Ok(())
 }
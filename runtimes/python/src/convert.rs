@@ -0,0 +1,178 @@
+//! Conversions between [`Value`]s and their Python representation.
+
+use gam3du_framework_common::api::{TypeDescriptor, Value};
+use runtime_python_bindgen::PyIdentifier;
+use rustpython_vm::{
+    PyObject, PyObjectRef, PyResult, VirtualMachine,
+    builtins::{PyDict, PyStr},
+    convert::IntoObject,
+};
+
+/// Converts a value into its Python representation.
+///
+/// Record fields and enumeration variants are represented by their mangled identifiers.
+pub fn value_to_py(vm: &VirtualMachine, value: Value) -> PyObjectRef {
+    match value {
+        Value::Unit => vm.ctx.none(),
+        Value::Integer(value) => vm.ctx.new_int(value).into_object(),
+        Value::Float(value) => vm.ctx.new_float(f64::from(value)).into_object(),
+        Value::Boolean(value) => vm.ctx.new_bool(value).into_object(),
+        Value::String(value) => vm.ctx.intern_str(value).to_object(),
+        Value::List(elements) => {
+            let elements = elements
+                .into_iter()
+                .map(|element| value_to_py(vm, element))
+                .collect();
+            vm.ctx.new_list(elements).into_object()
+        }
+        Value::Tuple(elements) => {
+            let elements = elements
+                .into_iter()
+                .map(|element| value_to_py(vm, element))
+                .collect();
+            vm.ctx.new_tuple(elements).into_object()
+        }
+        Value::Record(fields) => {
+            let dict = vm.ctx.new_dict();
+            for (name, field) in fields {
+                dict.set_item(
+                    name.parameter().to_string().as_str(),
+                    value_to_py(vm, field),
+                    vm,
+                )
+                .expect("setting an item of a fresh dict should not fail");
+            }
+            dict.into_object()
+        }
+        Value::Enumeration(variant) => vm
+            .ctx
+            .new_str(variant.parameter().to_string())
+            .into_object(),
+        Value::Optional(None) => vm.ctx.none(),
+        Value::Optional(Some(inner)) => value_to_py(vm, *inner),
+    }
+}
+
+/// Converts a Python object into a value of the given type.
+///
+/// This is the inverse of [`value_to_py`]: lists and tuples may be given as any sequence, records
+/// as a dict keyed by the mangled field names and enumeration variants by their mangled (or
+/// canonical) names. Integers are checked against their range.
+///
+/// # Errors
+///
+/// Raises a `TypeError` if the object does not represent a value of the type and a `ValueError`
+/// if it does but is not a member of the type (e.g. an integer out of range).
+pub fn py_to_value(
+    vm: &VirtualMachine,
+    typ: &TypeDescriptor,
+    object: &PyObject,
+) -> PyResult<Value> {
+    let mismatch = || {
+        vm.new_type_error(format!(
+            "expected a value of type `{}` but got `{}`",
+            runtime_python_bindgen::typ(typ),
+            &*object.class().name()
+        ))
+    };
+
+    match typ {
+        TypeDescriptor::Integer(range) => {
+            let int = object.try_index(vm).map_err(|_error| mismatch())?;
+            let value = int.try_to_primitive::<i64>(vm)?;
+            if !range.contains(&value) {
+                // the upper bound is exclusive but students expect to see the largest value
+                return Err(vm.new_value_error(format!(
+                    "{value} is out of range {}..={}",
+                    range.start,
+                    range.end.saturating_sub(1)
+                )));
+            }
+            Ok(Value::Integer(value))
+        }
+        TypeDescriptor::Float => {
+            let float = object.try_float(vm).map_err(|_error| mismatch())?;
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "floats are transmitted with single precision"
+            )]
+            let value = float.to_f64() as f32;
+            Ok(Value::Float(value))
+        }
+        TypeDescriptor::Boolean => Ok(Value::Boolean(object.try_to_bool(vm)?)),
+        TypeDescriptor::String => {
+            let string = object.downcast_ref::<PyStr>().ok_or_else(mismatch)?;
+            Ok(Value::String(string.as_str().to_owned()))
+        }
+        TypeDescriptor::List(element_type) => {
+            if object.downcast_ref::<PyStr>().is_some() {
+                // strings are sequences as well, but never meant to be a list
+                return Err(mismatch());
+            }
+            let elements = vm
+                .extract_elements_with(object, |element| py_to_value(vm, element_type, &element))?;
+            Ok(Value::List(elements))
+        }
+        TypeDescriptor::Tuple(element_types) => {
+            if object.downcast_ref::<PyStr>().is_some() {
+                return Err(mismatch());
+            }
+            let elements = vm.extract_elements_with(object, Ok)?;
+            if elements.len() != element_types.len() {
+                return Err(vm.new_value_error(format!(
+                    "expected {} elements but got {}",
+                    element_types.len(),
+                    elements.len()
+                )));
+            }
+            element_types
+                .iter()
+                .zip(&elements)
+                .map(|(element_type, element)| py_to_value(vm, element_type, element))
+                .collect::<PyResult<_>>()
+                .map(Value::Tuple)
+        }
+        TypeDescriptor::Record(field_types) => {
+            let dict = object.downcast_ref::<PyDict>().ok_or_else(mismatch)?;
+            for (key, _) in dict {
+                let known = key.downcast_ref::<PyStr>().is_some_and(|field| {
+                    field_types
+                        .keys()
+                        .any(|name| name.parameter().to_string() == field.as_str())
+                });
+                if !known {
+                    let key = key.repr(vm)?;
+                    return Err(vm.new_value_error(format!("unknown field {}", key.as_str())));
+                }
+            }
+            field_types
+                .iter()
+                .map(|(name, field_type)| {
+                    let key = name.parameter().to_string();
+                    let field = dict
+                        .get_item_opt(key.as_str(), vm)?
+                        .ok_or_else(|| vm.new_value_error(format!("missing field `{key}`")))?;
+                    Ok((name.clone(), py_to_value(vm, field_type, &field)?))
+                })
+                .collect::<PyResult<_>>()
+                .map(Value::Record)
+        }
+        TypeDescriptor::Enumeration(variants) => {
+            let variant = object.downcast_ref::<PyStr>().ok_or_else(mismatch)?;
+            variants
+                .iter()
+                .find(|known| {
+                    known.parameter().to_string() == variant.as_str() || known.0 == variant.as_str()
+                })
+                .map(|known| Value::Enumeration(known.clone()))
+                .ok_or_else(|| {
+                    vm.new_value_error(format!("unknown variant {:?}", variant.as_str()))
+                })
+        }
+        TypeDescriptor::Optional(_) if vm.is_none(object) => Ok(Value::Optional(None)),
+        TypeDescriptor::Optional(inner_type) => {
+            let inner = py_to_value(vm, inner_type, object)?;
+            Ok(Value::Optional(Some(Box::new(inner))))
+        }
+    }
+}
@@ -12,8 +12,10 @@
 
 mod api_client;
 mod api_server;
+mod convert;
 mod runner;
 
+pub use convert::{py_to_value, value_to_py};
 pub use runner::{PythonRunnerThread, PythonRuntime, PythonRuntimeBuilder};
 
 pub struct Config {
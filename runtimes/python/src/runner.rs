@@ -1,21 +1,25 @@
 use crate::{
     api_client::{insert_api_client, py_api_client},
-    api_server::{insert_api_server, py_api_server},
+    api_server::{insert_api_server, py_api_server, ApiServer, Handshake, PendingRequest},
+    convert::value_to_py,
 };
 use gam3du_framework_common::{
-    api::{Identifier, Value},
+    api::Identifier,
     api_channel::{ApiClientEndpoint, ApiServerEndpoint},
-    message::{ClientToServerMessage, RequestMessage},
+    message::{
+        ClientToServerMessage, HandshakeMessage, RequestError, RequestId, RequestMessage,
+        RequestStatus, SubscribeMessage, UnsubscribeMessage,
+    },
     module::Module,
 };
 use runtime_python_bindgen::PyIdentifier;
 use rustpython_vm::{
-    builtins::{PyBaseException, PyStrInterned},
+    builtins::{PyBaseException, PyBaseExceptionRef, PyStrInterned},
     convert::IntoObject,
     frozen::FrozenModulesIter,
     function::FuncArgs,
     signal::{user_signal_channel, UserSignal, UserSignalReceiver, UserSignalSender},
-    Interpreter, PyObjectRef, PyRef, Settings,
+    Interpreter, PyObjectRef, PyRef, Settings, VirtualMachine,
 };
 use std::{
     collections::HashMap,
@@ -26,7 +30,8 @@ use std::{
     },
     thread::{self, JoinHandle},
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use web_time::Instant;
 
 /// This indirection is necessary because we can't pass `rustpython_vm::stdlib::StdlibInitFunc`
 /// to a new thread (`std::thread::spawn` requires `Send`).
@@ -42,9 +47,10 @@ pub struct PythonRuntimeBuilder {
     user_signal_receiver: Option<UserSignalReceiver>,
 
     api_clients: HashMap<Identifier, Box<dyn ApiClientEndpoint>>,
-    api_servers: HashMap<Identifier, Arc<Mutex<dyn ApiServerEndpoint>>>,
+    api_servers: HashMap<Identifier, Arc<Mutex<ApiServer>>>,
     native_modules: HashMap<String, StdlibInitFunc>,
     frozen_modules: HashMap<String, FrozenModulesIter<'static>>,
+    source_modules: HashMap<String, String>,
 }
 
 impl PythonRuntimeBuilder {
@@ -58,6 +64,7 @@ impl PythonRuntimeBuilder {
             api_servers: HashMap::new(),
             native_modules: HashMap::new(),
             frozen_modules: HashMap::new(),
+            source_modules: HashMap::new(),
         }
     }
 
@@ -75,7 +82,7 @@ impl PythonRuntimeBuilder {
             self.api_servers
                 .insert(
                     api_server.api().name.clone(),
-                    Arc::new(Mutex::new(api_server))
+                    Arc::new(Mutex::new(ApiServer::new(Box::new(api_server))))
                 )
                 .is_none(),
             "duplicate api name"
@@ -100,6 +107,20 @@ impl PythonRuntimeBuilder {
         );
     }
 
+    /// Adds a module whose Python source code is only known at runtime (e.g. because it has been
+    /// generated from an api descriptor).
+    ///
+    /// The module will be imported right after all other modules have been registered, so it may
+    /// import native and frozen modules.
+    pub fn add_source_module(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        assert!(
+            self.source_modules
+                .insert(name.into(), source.into())
+                .is_none(),
+            "duplicate source module name"
+        );
+    }
+
     pub fn enable_user_signals(&mut self) -> UserSignalSender {
         let (user_signal_sender, user_signal_receiver) = user_signal_channel();
         assert!(
@@ -120,6 +141,7 @@ impl PythonRuntimeBuilder {
             api_servers,
             native_modules,
             frozen_modules,
+            source_modules,
         } = self;
 
         let id = VM_ID.fetch_add(1, Ordering::Relaxed).to_string();
@@ -208,6 +230,14 @@ impl PythonRuntimeBuilder {
                 //     .unwrap();
                 insert_api_server(vm, &api_module, Arc::clone(api_server));
             }
+
+            for (name, source) in source_modules {
+                debug!("importing source module {name}");
+                if let Err(exception) = rustpython_vm::import::import_source(vm, &name, &source) {
+                    vm.print_exception(exception);
+                    panic!("failed to import source module {name}");
+                }
+            }
         });
 
         PythonRuntime {
@@ -235,7 +265,7 @@ impl PythonRuntimeBuilder {
 pub struct PythonRuntime {
     main_module_name: &'static PyStrInterned,
     pub interpreter: Interpreter,
-    api_server_endpoints: Vec<Arc<Mutex<dyn ApiServerEndpoint>>>,
+    api_server_endpoints: Vec<Arc<Mutex<ApiServer>>>,
     pub module: Option<PyObjectRef>,
 }
 
@@ -285,56 +315,237 @@ impl Module for PythonRuntime {
         for api_server_endpoint in &mut self.api_server_endpoints {
             'next_message: loop {
                 let incoming_message = {
-                    let Some(message) = api_server_endpoint.lock().unwrap().poll_request() else {
+                    let mut api_server = api_server_endpoint.lock().unwrap();
+                    if !api_server.connected {
                         break 'next_message;
-                    };
-                    message
+                    }
+                    match api_server.endpoint.poll_request() {
+                        Ok(Some(message)) => message,
+                        Ok(None) => break 'next_message,
+                        Err(error) => {
+                            api_server.disconnect(error);
+                            break 'next_message;
+                        }
+                    }
                 };
                 // }
 
                 // while let Some(incoming_message) = api_server_endpoint.lock().unwrap().poll_request() {
-                let ClientToServerMessage::Request(request) = incoming_message;
-
-                let RequestMessage {
-                    id,
-                    command,
-                    arguments,
-                } = request;
-
-                let module = self.module.as_mut().expect("cannot wake() before init()");
-                self.interpreter.enter(|vm| {
-                    let py_id = vm.ctx.new_int(id.0.get()).into_object();
-                    let mut args = vec![py_id];
-                    args.extend(arguments.into_iter().map(|argument| match argument {
-                        Value::Unit => todo!(),
-                        Value::Integer(value) => vm.ctx.new_int(value).into_object(),
-                        Value::Float(value) => vm.ctx.new_float(f64::from(value)).into_object(),
-                        Value::Boolean(value) => vm.ctx.new_bool(value).into_object(),
-                        Value::String(value) => vm.ctx.intern_str(value).to_object(),
-                        Value::List(_value) => todo!(),
-                    }));
-
-                    let args = FuncArgs::from(args);
-                    let handler_function_name =
-                        vm.ctx.intern_str(format!("on_{}", command.function()));
-                    let callback = match module.get_attr(handler_function_name.as_str(), vm) {
-                        Ok(callback) => callback,
-                        Err(exception) => {
-                            vm.print_exception(exception);
-                            panic!("missing callback function");
+                let request = match incoming_message {
+                    ClientToServerMessage::Request(request) => request,
+                    ClientToServerMessage::Handshake(handshake) => {
+                        let HandshakeMessage {
+                            ref api, version, ..
+                        } = handshake;
+                        let mut api_server = api_server_endpoint.lock().unwrap();
+                        match api_server.endpoint.answer_handshake(&handshake) {
+                            Ok(Ok(())) => {
+                                info!("accepted client of api `{api}` version {version}");
+                                api_server.handshake = Handshake::Accepted;
+                            }
+                            Ok(Err(error)) => {
+                                error!("rejected client: {error}");
+                                api_server.handshake = Handshake::Rejected;
+                            }
+                            Err(error) => api_server.disconnect(error),
+                        }
+                        continue 'next_message;
+                    }
+                    ClientToServerMessage::Subscribe(SubscribeMessage { event }) => {
+                        let mut api_server = api_server_endpoint.lock().unwrap();
+                        if api_server.endpoint.api().events.contains_key(&event) {
+                            debug!("client subscribed to event `{event}`");
+                            api_server.subscriptions.insert(event);
+                        } else {
+                            warn!("client tried to subscribe to unknown event `{event}`");
                         }
-                    };
-                    match callback.call(args, vm) {
-                        Ok(_result) => {}
-                        Err(exception) => {
-                            vm.print_exception(exception);
-                            panic!("missing callback function");
+                        continue 'next_message;
+                    }
+                    ClientToServerMessage::Unsubscribe(UnsubscribeMessage { event }) => {
+                        debug!("client unsubscribed from event `{event}`");
+                        api_server_endpoint
+                            .lock()
+                            .unwrap()
+                            .subscriptions
+                            .remove(&event);
+                        continue 'next_message;
+                    }
+                    ClientToServerMessage::Cancel(id) => {
+                        let running = api_server_endpoint.lock().unwrap().cancel(id);
+                        if let Some(running) = running {
+                            debug!("cancelling request {running}");
+                            // the lock must not be held while calling into Python
+                            let module = self.module.as_ref().expect("cannot wake() before init()");
+                            cancel_request(&self.interpreter, module, running);
+                        } else {
+                            debug!("nothing left to abort for request {id}");
                         }
+                        continue 'next_message;
+                    }
+                    ClientToServerMessage::Query(id) => {
+                        let mut api_server = api_server_endpoint.lock().unwrap();
+                        let status = if api_server.is_pending(id) {
+                            RequestStatus::Pending
+                        } else {
+                            RequestStatus::Unknown
+                        };
+                        api_server.send(|endpoint| endpoint.send_status(id, status));
+                        continue 'next_message;
                     }
-                });
+                    ClientToServerMessage::Goodbye => {
+                        api_server_endpoint
+                            .lock()
+                            .unwrap()
+                            .disconnect("the client said goodbye");
+                        continue 'next_message;
+                    }
+                    ClientToServerMessage::Batch(batch) => {
+                        debug!(
+                            "starting batch {} of {} requests",
+                            batch.id,
+                            batch.requests.len()
+                        );
+                        api_server_endpoint.lock().unwrap().start_batch(batch);
+                        continue 'next_message;
+                    }
+                };
+
+                let module = self.module.as_ref().expect("cannot wake() before init()");
+                dispatch_request(&self.interpreter, module, api_server_endpoint, request);
             }
+
+            // each completed request of a batch makes way for the next one
+            loop {
+                // the lock must not be held while calling into Python
+                let request = api_server_endpoint.lock().unwrap().next_batch_request();
+                let Some(request) = request else {
+                    break;
+                };
+                let module = self.module.as_ref().expect("cannot wake() before init()");
+                dispatch_request(&self.interpreter, module, api_server_endpoint, request);
+            }
+
+            let expired = api_server_endpoint.lock().unwrap().expire(Instant::now());
+            for id in expired {
+                debug!("request {id} has exceeded its deadline");
+                // the lock must not be held while calling into Python
+                let module = self.module.as_ref().expect("cannot wake() before init()");
+                cancel_request(&self.interpreter, module, id);
+                api_server_endpoint
+                    .lock()
+                    .unwrap()
+                    .deliver(id, Err(RequestError::DeadlineExceeded));
+            }
+
+            // a vanished client is not interested in its requests anymore
+            let abandoned = api_server_endpoint.lock().unwrap().abandon();
+            for id in abandoned {
+                debug!("abandoning request {id} as the session has ended");
+                let module = self.module.as_ref().expect("cannot wake() before init()");
+                cancel_request(&self.interpreter, module, id);
+            }
+        }
+    }
+}
+
+/// Checks a request and passes it to its handler in the Python module.
+///
+/// Requests of clients without an accepted handshake are rejected without calling into Python.
+///
+/// Omitted trailing arguments are filled in from the default values of their parameters.
+///
+/// The handler is called `on_<function>` and receives the request id followed by the arguments.
+/// It answers the request later on using the api server module.
+fn dispatch_request(
+    interpreter: &Interpreter,
+    module: &PyObjectRef,
+    api_server_endpoint: &Mutex<ApiServer>,
+    mut request: RequestMessage,
+) {
+    {
+        let mut api_server = api_server_endpoint.lock().unwrap();
+        if let Some(function) = api_server.endpoint.api().functions.get(&request.command) {
+            function.fill_defaults(&mut request.arguments);
+        }
+        let checked = api_server
+            .check_handshake()
+            .and_then(|()| api_server.endpoint.check_request(&request));
+        if let Err(error) = checked {
+            warn!("rejecting request {}: {error}", request.id);
+            api_server.deliver(request.id, Err(error));
+            return;
         }
+        let deadline = request.timeout.map(|timeout| Instant::now() + timeout);
+        let pending = PendingRequest {
+            command: request.command.clone(),
+            deadline,
+        };
+        api_server.pending.insert(request.id, pending);
     }
+
+    let RequestMessage {
+        id,
+        command,
+        arguments,
+        timeout: _,
+    } = request;
+
+    interpreter.enter(|vm| {
+        let py_id = vm.ctx.new_int(id.0.get()).into_object();
+        let mut args = vec![py_id];
+        args.extend(
+            arguments
+                .into_iter()
+                .map(|argument| value_to_py(vm, argument)),
+        );
+
+        let args = FuncArgs::from(args);
+        let handler_function_name = vm.ctx.intern_str(format!("on_{}", command.function()));
+        let error = match module.get_attr(handler_function_name.as_str(), vm) {
+            Ok(callback) => match callback.call(args, vm) {
+                Ok(_result) => return,
+                Err(exception) => RequestError::EngineRejected {
+                    reason: exception_message(vm, &exception),
+                },
+            },
+            Err(exception) => {
+                vm.print_exception(exception);
+                error!(
+                    "missing callback function `{}`",
+                    handler_function_name.as_str()
+                );
+                RequestError::UnknownFunction { function: command }
+            }
+        };
+
+        // the handler will not answer the request anymore
+        api_server_endpoint.lock().unwrap().answer(id, Err(error));
+    });
+}
+
+/// Prints an exception raised by a request handler and returns its message for the client.
+fn exception_message(vm: &VirtualMachine, exception: &PyBaseExceptionRef) -> String {
+    vm.print_exception(exception.clone());
+    exception.as_object().str(vm).map_or_else(
+        |_| "the request handler failed".to_owned(),
+        |message| message.as_str().to_owned(),
+    )
+}
+
+/// Tells the Python module to abort a request by calling its `on_cancel` handler.
+///
+/// Any response sent for this request afterwards will be dropped.
+fn cancel_request(interpreter: &Interpreter, module: &PyObjectRef, id: RequestId) {
+    interpreter.enter(|vm| {
+        let Ok(callback) = module.get_attr("on_cancel", vm) else {
+            warn!("cannot cancel request {id} as there is no `on_cancel` handler");
+            return;
+        };
+        let py_id = vm.ctx.new_int(id.0.get()).into_object();
+        if let Err(exception) = callback.call((py_id,), vm) {
+            vm.print_exception(exception);
+        }
+    });
 }
 
 pub struct PythonRunnerThread {
@@ -0,0 +1,270 @@
+#[allow(unused_imports, reason = "not every api requires all of them")]
+use gam3du_framework_common::{
+    api::{ApiValue, ApiVersion, ConversionError, Identifier, TypeDescriptor, Value},
+    api_channel::{ApiClientEndpoint, ApiError, ChannelError},
+    message::{EventMessage, HandshakeMessage, ServerToClientMessage},
+};
+use std::{borrow::Cow, time::{Duration, Instant}};
+
+/// A simple robot that can be moved across a 2D-plane and draw lines
+///
+/// Once upon a time there was a lonely robot with the serial number `#C0D1E`. …
+pub struct RobotControlClient<Endpoint: ApiClientEndpoint> {
+    endpoint: Endpoint,
+    events: Vec<EventMessage>,
+    responses: Vec<ServerToClientMessage>,
+    timeout: Option<Duration>,
+}
+
+impl<Endpoint: ApiClientEndpoint> RobotControlClient<Endpoint> {
+    /// The version of the api this client has been generated for
+    pub const VERSION: ApiVersion = ApiVersion::new(0, 2, 0);
+
+    /// The fingerprint of the api descriptor this client has been generated for
+    pub const FINGERPRINT: u64 = 0x1b1a_1d62_658e_bcb6;
+
+    /// Wraps the endpoint and tells the server which revision of the api to expect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached.
+    pub fn new(endpoint: Endpoint) -> Result<Self, ChannelError> {
+        let handshake = HandshakeMessage {
+            api: Identifier(Cow::Borrowed("robot control")),
+            version: Self::VERSION,
+            fingerprint: Self::FINGERPRINT,
+        };
+        endpoint.send_to_server(handshake.into())?;
+        Ok(Self {
+            endpoint,
+            events: Vec::new(),
+            responses: Vec::new(),
+            timeout: None,
+        })
+    }
+
+    /// Ends the session; the server will cancel all pending requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has gone away already.
+    pub fn close(&self) -> Result<(), ChannelError> {
+        self.endpoint.goodbye()
+    }
+
+    /// Limits the time to wait for each response; `None` waits indefinitely.
+    ///
+    /// The timeout is sent along with each request, so the server gives up on it as well.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the wrapped endpoint.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Returns all events that have been received while waiting for responses.
+    pub fn take_events(&mut self) -> Vec<EventMessage> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns all answers to other requests that have been received while waiting for responses.
+    ///
+    /// These might be late answers to requests that timed out or answers to requests sent by
+    /// someone else sharing the endpoint.
+    pub fn take_responses(&mut self) -> Vec<ServerToClientMessage> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Starts receiving `robot stopped` events (see [`Self::take_events`]).
+    ///
+    /// The robot finished its current animation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has gone away.
+    pub fn subscribe_robot_stopped(&self) -> Result<(), ChannelError> {
+        self.endpoint.subscribe(Identifier(Cow::Borrowed("robot stopped")))
+    }
+
+    /// Stops receiving `robot stopped` events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server has gone away.
+    pub fn unsubscribe_robot_stopped(&self) -> Result<(), ChannelError> {
+        self.endpoint.unsubscribe(Identifier(Cow::Borrowed("robot stopped")))
+    }
+
+    /// Sets the height of the tile at the robot's current location
+    ///
+    /// - `height`: height of the tile
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn set_height(&mut self, height: f32) -> Result<(), ApiError> {
+        let arguments = vec![
+            height.into_value(),
+        ];
+        self.call("set height", arguments)?;
+        Ok(())
+    }
+
+    /// Makes the robot move to the next tile in its current orientation
+    ///
+    /// - `duration`: Duration of the move animation (`None` sends `Value::Integer(500)`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn move_forward(&mut self, duration: Option<u16>) -> Result<bool, ApiError> {
+        let arguments = vec![
+            duration.map_or_else(|| Value::Integer(500), ApiValue::into_value),
+        ];
+        let result = self.call("move forward", arguments)?;
+        Ok(ApiValue::try_from_value(result)?)
+    }
+
+    /// Makes the robot jump to the next tile in its current orientation
+    ///
+    /// - `duration`: Duration of the jump animation (`None` sends `Value::Integer(500)`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn jump(&mut self, duration: Option<u16>) -> Result<bool, ApiError> {
+        let arguments = vec![
+            duration.map_or_else(|| Value::Integer(500), ApiValue::into_value),
+        ];
+        let result = self.call("jump", arguments)?;
+        Ok(ApiValue::try_from_value(result)?)
+    }
+
+    /// Makes the robot move to the next tile in its current orientation and draw a line onto the floor
+    ///
+    /// - `duration`: Duration of the move animation (`None` sends `Value::Integer(500)`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn draw_forward(&mut self, duration: Option<u16>) -> Result<bool, ApiError> {
+        let arguments = vec![
+            duration.map_or_else(|| Value::Integer(500), ApiValue::into_value),
+        ];
+        let result = self.call("draw forward", arguments)?;
+        Ok(ApiValue::try_from_value(result)?)
+    }
+
+    /// Turns the robot 45° in a counter-clockwise direction
+    ///
+    /// - `duration`: Duration of the move animation (`None` sends `Value::Integer(300)`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn turn_left(&mut self, duration: Option<u16>) -> Result<(), ApiError> {
+        let arguments = vec![
+            duration.map_or_else(|| Value::Integer(300), ApiValue::into_value),
+        ];
+        self.call("turn left", arguments)?;
+        Ok(())
+    }
+
+    /// Turns the robot 45° in a clockwise direction
+    ///
+    /// - `duration`: Duration of the move animation (`None` sends `Value::Integer(300)`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn turn_right(&mut self, duration: Option<u16>) -> Result<(), ApiError> {
+        let arguments = vec![
+            duration.map_or_else(|| Value::Integer(300), ApiValue::into_value),
+        ];
+        self.call("turn right", arguments)?;
+        Ok(())
+    }
+
+    /// Set the color of the robot
+    ///
+    /// - `red`: Amount of red (0.0 to 1.0)
+    /// - `green`: Amount of green (0.0 to 1.0)
+    /// - `blue`: Amount of blue (0.0 to 1.0)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn robot_color_rgb(&mut self, red: f32, green: f32, blue: f32) -> Result<(), ApiError> {
+        let arguments = vec![
+            red.into_value(),
+            green.into_value(),
+            blue.into_value(),
+        ];
+        self.call("robot color rgb", arguments)?;
+        Ok(())
+    }
+
+    /// Fill the current tile with the robots current color
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects this client or the request, or has gone away.
+    pub fn paint_tile(&mut self) -> Result<(), ApiError> {
+        let arguments = Vec::new();
+        self.call("paint tile", arguments)?;
+        Ok(())
+    }
+
+    fn call(&mut self, function: &'static str, arguments: Vec<Value>) -> Result<Value, ApiError> {
+        const MIN_POLL_INTERVAL: Duration = Duration::from_micros(50);
+        const MAX_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+        let id = self.endpoint.send_command_with_timeout(
+            Identifier(Cow::Borrowed(function)),
+            arguments,
+            self.timeout,
+        )?;
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut poll_interval = MIN_POLL_INTERVAL;
+        loop {
+            let message = self.endpoint.poll_response()?;
+            if message.is_some() {
+                poll_interval = MIN_POLL_INTERVAL;
+            }
+            match message {
+                Some(ServerToClientMessage::Response(response)) if response.id == id => {
+                    return Ok(response.result);
+                }
+                Some(ServerToClientMessage::ErrorResponse(response)) if response.id == id => {
+                    return Err(ApiError::Rejected(response.error));
+                }
+                Some(ServerToClientMessage::Cancelled(cancelled)) if cancelled == id => {
+                    return Err(ApiError::Cancelled);
+                }
+                Some(ServerToClientMessage::HandshakeResponse(response)) => response.result?,
+                Some(ServerToClientMessage::Event(event)) => self.events.push(event),
+                Some(ServerToClientMessage::Goodbye) => {
+                    return Err(ChannelError::Disconnected.into());
+                }
+                Some(
+                    response @ (ServerToClientMessage::Response(_)
+                    | ServerToClientMessage::ErrorResponse(_)
+                    | ServerToClientMessage::Cancelled(_)
+                    | ServerToClientMessage::Status(_)
+                    | ServerToClientMessage::BatchResponse(_)),
+                ) => self.responses.push(response),
+                None if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                    self.endpoint.cancel(id)?;
+                    return Err(ApiError::TimedOut);
+                }
+                None => {
+                    std::thread::sleep(poll_interval);
+                    poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
+                }
+            }
+        }
+    }
+}
+
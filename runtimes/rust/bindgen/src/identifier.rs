@@ -0,0 +1,56 @@
+use gam3du_framework_common::api;
+
+/// Keywords which may only be used as raw identifiers
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords which cannot even be used as raw identifiers
+const RESERVED: &[&str] = &["crate", "self", "super", "Self"];
+
+/// Name mangling of [`api::Identifier`]s according to the Rust naming conventions
+pub trait RustIdentifier {
+    /// `lower_snake_case` for functions, parameters and fields
+    fn snake(&self) -> String;
+    /// `UpperCamelCase` for types and enum variants
+    fn camel(&self) -> String;
+}
+
+impl RustIdentifier for api::Identifier {
+    fn snake(&self) -> String {
+        escape(self.as_ref().replace(' ', "_"))
+    }
+
+    fn camel(&self) -> String {
+        let camel = self
+            .as_ref()
+            .split(' ')
+            .map(|word| {
+                let mut characters = word.chars();
+                characters.next().map_or_else(String::new, |first| {
+                    let mut capitalized = first.to_ascii_uppercase().to_string();
+                    capitalized.push_str(characters.as_str());
+                    capitalized
+                })
+            })
+            .collect();
+        escape(camel)
+    }
+}
+
+/// Makes sure the name is a valid Rust identifier.
+fn escape(name: String) -> String {
+    if name.starts_with(|character: char| character.is_ascii_digit()) {
+        format!("_{name}")
+    } else if RESERVED.contains(&name.as_str()) {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
@@ -0,0 +1,527 @@
+//! Generates typed Rust clients for api descriptors.
+//!
+//! Each function of the api becomes a method on a struct wrapping any [`ApiClientEndpoint`].
+//! Parameters with a default value are taken as `Option`s; passing `None` sends the default.
+//!
+//! The generated code only depends on `gam3du-framework-common` and is meant to be placed in a
+//! module of its own (e.g. by `include!`-ing it from a build script's output).
+//!
+//! [`ApiClientEndpoint`]: gam3du_framework_common::api_channel::ApiClientEndpoint
+
+mod identifier;
+mod types;
+
+use gam3du_framework_common::api::{
+    ApiDescriptor, EventDescriptor, FunctionDescriptor, Identifier, RichText,
+};
+pub use identifier::RustIdentifier;
+use std::io::{self, Write};
+use types::{Types, value_expr};
+
+/// A function of the api together with the Rust types of its parameters and return value
+struct Function<'api> {
+    descriptor: &'api FunctionDescriptor,
+    parameter_types: Vec<String>,
+    return_type: Option<String>,
+}
+
+/// Generates the client and all types it requires.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails or if the api uses types that cannot be represented
+/// (tuples with more than four elements).
+pub fn generate(out: &mut impl Write, api: &ApiDescriptor) -> io::Result<()> {
+    // all types need to be known before the client can be generated
+    let mut types = Types::default();
+    let functions = api
+        .functions
+        .values()
+        .map(|function| resolve_function(function, &mut types))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    generate_imports(out)?;
+    generate_client(out, api, &functions)?;
+    types.generate(out)?;
+
+    Ok(())
+}
+
+fn resolve_function<'api>(
+    function: &'api FunctionDescriptor,
+    types: &mut Types,
+) -> io::Result<Function<'api>> {
+    let function_name = function.name.camel();
+    let parameter_types = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = format!("{function_name}{}", parameter.name.camel());
+            types.rust_type(&parameter.typ, &name)
+        })
+        .collect::<io::Result<_>>()?;
+    let return_type = function
+        .returns
+        .as_ref()
+        .map(|returns| types.rust_type(&returns.typ, &format!("{function_name}Return")))
+        .transpose()?;
+
+    Ok(Function {
+        descriptor: function,
+        parameter_types,
+        return_type,
+    })
+}
+
+fn generate_imports(out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "#[allow(unused_imports, reason = \"not every api requires all of them\")]"
+    )?;
+    writeln!(out, "use gam3du_framework_common::{{")?;
+    writeln!(
+        out,
+        "    api::{{ApiValue, ApiVersion, ConversionError, Identifier, TypeDescriptor, Value}},"
+    )?;
+    writeln!(
+        out,
+        "    api_channel::{{ApiClientEndpoint, ApiError, ChannelError}},"
+    )?;
+    writeln!(
+        out,
+        "    message::{{EventMessage, HandshakeMessage, ServerToClientMessage}},"
+    )?;
+    writeln!(out, "}};")?;
+    writeln!(
+        out,
+        "use std::{{borrow::Cow, time::{{Duration, Instant}}}};"
+    )?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn generate_client(
+    out: &mut impl Write,
+    api: &ApiDescriptor,
+    functions: &[Function<'_>],
+) -> io::Result<()> {
+    let client = format!("{}Client", api.name.camel());
+    let version = api.version;
+
+    generate_documentation(out, "", &api.caption, &api.description)?;
+    writeln!(out, "pub struct {client}<Endpoint: ApiClientEndpoint> {{")?;
+    writeln!(out, "    endpoint: Endpoint,")?;
+    writeln!(out, "    events: Vec<EventMessage>,")?;
+    writeln!(out, "    responses: Vec<ServerToClientMessage>,")?;
+    writeln!(out, "    timeout: Option<Duration>,")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "impl<Endpoint: ApiClientEndpoint> {client}<Endpoint> {{"
+    )?;
+    writeln!(
+        out,
+        "    /// The version of the api this client has been generated for"
+    )?;
+    writeln!(
+        out,
+        "    pub const VERSION: ApiVersion = ApiVersion::new({}, {}, {});",
+        version.major, version.minor, version.patch
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    /// The fingerprint of the api descriptor this client has been generated for"
+    )?;
+    let fingerprint = api.fingerprint();
+    writeln!(
+        out,
+        "    pub const FINGERPRINT: u64 = 0x{:04x}_{:04x}_{:04x}_{:04x};",
+        fingerprint >> 48,
+        (fingerprint >> 32) & 0xffff,
+        (fingerprint >> 16) & 0xffff,
+        fingerprint & 0xffff
+    )?;
+    writeln!(out)?;
+
+    generate_session(out, api)?;
+
+    writeln!(
+        out,
+        "    /// Limits the time to wait for each response; `None` waits indefinitely."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// The timeout is sent along with each request, so the server gives up on it as well."
+    )?;
+    writeln!(
+        out,
+        "    pub fn set_timeout(&mut self, timeout: Option<Duration>) {{"
+    )?;
+    writeln!(out, "        self.timeout = timeout;")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(out, "    /// Returns the wrapped endpoint.")?;
+    writeln!(out, "    pub fn endpoint(&self) -> &Endpoint {{")?;
+    writeln!(out, "        &self.endpoint")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "    /// Returns all events that have been received while waiting for responses."
+    )?;
+    writeln!(
+        out,
+        "    pub fn take_events(&mut self) -> Vec<EventMessage> {{"
+    )?;
+    writeln!(out, "        std::mem::take(&mut self.events)")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "    /// Returns all answers to other requests that have been received while waiting for responses."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// These might be late answers to requests that timed out or answers to requests sent by"
+    )?;
+    writeln!(out, "    /// someone else sharing the endpoint.")?;
+    writeln!(
+        out,
+        "    pub fn take_responses(&mut self) -> Vec<ServerToClientMessage> {{"
+    )?;
+    writeln!(out, "        std::mem::take(&mut self.responses)")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    for event in api.events.values() {
+        generate_event(out, event)?;
+    }
+
+    for function in functions {
+        generate_function(out, function)?;
+    }
+
+    generate_call(out)?;
+
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Generates the methods starting and ending a session with the server.
+fn generate_session(out: &mut impl Write, api: &ApiDescriptor) -> io::Result<()> {
+    writeln!(
+        out,
+        "    /// Wraps the endpoint and tells the server which revision of the api to expect."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// Returns an error if the server cannot be reached."
+    )?;
+    writeln!(
+        out,
+        "    pub fn new(endpoint: Endpoint) -> Result<Self, ChannelError> {{"
+    )?;
+    writeln!(out, "        let handshake = HandshakeMessage {{")?;
+    writeln!(out, "            api: {},", identifier_expr(&api.name))?;
+    writeln!(out, "            version: Self::VERSION,")?;
+    writeln!(out, "            fingerprint: Self::FINGERPRINT,")?;
+    writeln!(out, "        }};")?;
+    writeln!(out, "        endpoint.send_to_server(handshake.into())?;")?;
+    writeln!(out, "        Ok(Self {{")?;
+    writeln!(out, "            endpoint,")?;
+    writeln!(out, "            events: Vec::new(),")?;
+    writeln!(out, "            responses: Vec::new(),")?;
+    writeln!(out, "            timeout: None,")?;
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "    /// Ends the session; the server will cancel all pending requests."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// Returns an error if the server has gone away already."
+    )?;
+    writeln!(
+        out,
+        "    pub fn close(&self) -> Result<(), ChannelError> {{"
+    )?;
+    writeln!(out, "        self.endpoint.goodbye()")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_event(out: &mut impl Write, event: &EventDescriptor) -> io::Result<()> {
+    let name = event.name.snake();
+    let name = name.trim_start_matches("r#");
+
+    writeln!(
+        out,
+        "    /// Starts receiving `{}` events (see [`Self::take_events`]).",
+        event.name
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// {}",
+        markdown(&event.caption).replace('\n', " ")
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// Returns an error if the server has gone away.")?;
+    writeln!(
+        out,
+        "    pub fn subscribe_{name}(&self) -> Result<(), ChannelError> {{"
+    )?;
+    writeln!(
+        out,
+        "        self.endpoint.subscribe({})",
+        identifier_expr(&event.name)
+    )?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(out, "    /// Stops receiving `{}` events.", event.name)?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// Returns an error if the server has gone away.")?;
+    writeln!(
+        out,
+        "    pub fn unsubscribe_{name}(&self) -> Result<(), ChannelError> {{"
+    )?;
+    writeln!(
+        out,
+        "        self.endpoint.unsubscribe({})",
+        identifier_expr(&event.name)
+    )?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_function(out: &mut impl Write, function: &Function<'_>) -> io::Result<()> {
+    let Function {
+        descriptor,
+        ref parameter_types,
+        ref return_type,
+    } = *function;
+
+    generate_documentation(out, "    ", &descriptor.caption, &descriptor.description)?;
+    if !descriptor.parameters.is_empty() {
+        writeln!(out, "    ///")?;
+        for parameter in &descriptor.parameters {
+            write!(
+                out,
+                "    /// - `{}`: {}",
+                parameter.name.snake(),
+                markdown(&parameter.caption).replace('\n', " ")
+            )?;
+            if let Some(ref default) = parameter.default {
+                write!(out, " (`None` sends `{}`)", value_expr(default))?;
+            }
+            writeln!(out)?;
+        }
+    }
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// Returns an error if the server rejects this client or the request, or has gone away."
+    )?;
+
+    write!(out, "    pub fn {}(&mut self", descriptor.name.snake())?;
+    for (parameter, typ) in descriptor.parameters.iter().zip(parameter_types) {
+        if parameter.default.is_some() {
+            write!(out, ", {}: Option<{typ}>", parameter.name.snake())?;
+        } else {
+            write!(out, ", {}: {typ}", parameter.name.snake())?;
+        }
+    }
+    writeln!(
+        out,
+        ") -> Result<{}, ApiError> {{",
+        return_type.as_deref().unwrap_or("()")
+    )?;
+
+    if descriptor.parameters.is_empty() {
+        writeln!(out, "        let arguments = Vec::new();")?;
+    } else {
+        writeln!(out, "        let arguments = vec![")?;
+        for parameter in &descriptor.parameters {
+            let name = parameter.name.snake();
+            match parameter.default {
+                Some(ref default) => writeln!(
+                    out,
+                    "            {name}.map_or_else(|| {}, ApiValue::into_value),",
+                    value_expr(default)
+                )?,
+                None => writeln!(out, "            {name}.into_value(),")?,
+            }
+        }
+        writeln!(out, "        ];")?;
+    }
+
+    let name = &descriptor.name;
+    if return_type.is_some() {
+        writeln!(
+            out,
+            "        let result = self.call({:?}, arguments)?;",
+            name.as_ref()
+        )?;
+        writeln!(out, "        Ok(ApiValue::try_from_value(result)?)")?;
+    } else {
+        writeln!(out, "        self.call({:?}, arguments)?;", name.as_ref())?;
+        writeln!(out, "        Ok(())")?;
+    }
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Generates the method sending a request and blocking until the response arrives.
+///
+/// While nothing arrives, the time between polls doubles up to a few milliseconds.
+fn generate_call(out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "    fn call(&mut self, function: &'static str, arguments: Vec<Value>) -> Result<Value, ApiError> {{
+        const MIN_POLL_INTERVAL: Duration = Duration::from_micros(50);
+        const MAX_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+        let id = self.endpoint.send_command_with_timeout(
+            Identifier(Cow::Borrowed(function)),
+            arguments,
+            self.timeout,
+        )?;
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut poll_interval = MIN_POLL_INTERVAL;
+        loop {{
+            let message = self.endpoint.poll_response()?;
+            if message.is_some() {{
+                poll_interval = MIN_POLL_INTERVAL;
+            }}
+            match message {{
+                Some(ServerToClientMessage::Response(response)) if response.id == id => {{
+                    return Ok(response.result);
+                }}
+                Some(ServerToClientMessage::ErrorResponse(response)) if response.id == id => {{
+                    return Err(ApiError::Rejected(response.error));
+                }}
+                Some(ServerToClientMessage::Cancelled(cancelled)) if cancelled == id => {{
+                    return Err(ApiError::Cancelled);
+                }}
+                Some(ServerToClientMessage::HandshakeResponse(response)) => response.result?,
+                Some(ServerToClientMessage::Event(event)) => self.events.push(event),
+                Some(ServerToClientMessage::Goodbye) => {{
+                    return Err(ChannelError::Disconnected.into());
+                }}
+                Some(
+                    response @ (ServerToClientMessage::Response(_)
+                    | ServerToClientMessage::ErrorResponse(_)
+                    | ServerToClientMessage::Cancelled(_)
+                    | ServerToClientMessage::Status(_)
+                    | ServerToClientMessage::BatchResponse(_)),
+                ) => self.responses.push(response),
+                None if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {{
+                    self.endpoint.cancel(id)?;
+                    return Err(ApiError::TimedOut);
+                }}
+                None => {{
+                    std::thread::sleep(poll_interval);
+                    poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
+                }}
+            }}
+        }}
+    }}"
+    )
+}
+
+/// Writes the caption and (if it adds anything) the description as doc comment.
+fn generate_documentation(
+    out: &mut impl Write,
+    indentation: &str,
+    caption: &RichText,
+    description: &RichText,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "{indentation}/// {}",
+        markdown(caption).replace('\n', " ")
+    )?;
+    if description.0 != caption.0 && !description.0.is_empty() {
+        writeln!(out, "{indentation}///")?;
+        for line in markdown(description).lines() {
+            if line.is_empty() {
+                writeln!(out, "{indentation}///")?;
+            } else {
+                writeln!(out, "{indentation}/// {line}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders the markup of a text for rustdoc; links name the methods of the client.
+fn markdown(text: &RichText) -> String {
+    let name = |target: &Identifier| format!("Self::{}", target.snake().trim_start_matches("r#"));
+    text.document().to_markdown(&name, None)
+}
+
+/// Renders a Rust expression constructing the given identifier.
+fn identifier_expr(identifier: &Identifier) -> String {
+    format!("Identifier(Cow::Borrowed({:?}))", identifier.as_ref())
+}
+
+#[cfg(test)]
+#[expect(
+    clippy::unwrap_used,
+    reason = "failures to generate the client fail the test"
+)]
+mod tests {
+    use super::generate;
+    use gam3du_framework_common::api::ApiDescriptor;
+
+    /// The snapshot needs to compile against the current framework as well.
+    #[expect(dead_code, unreachable_pub, reason = "the client is not used")]
+    mod robot_control {
+        include!("../snapshots/robot_control.rs");
+    }
+
+    #[test]
+    fn robot_control_client_matches_snapshot() {
+        let json = include_bytes!("../../../../applications/robot/control.api.json");
+        let api = ApiDescriptor::load(json).unwrap();
+        let mut generated = Vec::new();
+        generate(&mut generated, &api).unwrap();
+        assert_eq!(
+            String::from_utf8(generated).unwrap(),
+            include_str!("../snapshots/robot_control.rs"),
+            "`snapshots/robot_control.rs` needs to be replaced by the generated client"
+        );
+    }
+}
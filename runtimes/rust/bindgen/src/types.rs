@@ -0,0 +1,409 @@
+//! Maps [`TypeDescriptor`]s onto Rust types and generates named types for records and
+//! enumerations.
+
+use crate::{RustIdentifier, identifier_expr};
+use gam3du_framework_common::api::{Identifier, TypeDescriptor, Value};
+use std::io::{self, Write};
+
+/// Records and enumerations are anonymous within an api descriptor, but need a name in Rust.
+enum Definition {
+    Record {
+        name: String,
+        descriptor: TypeDescriptor,
+        fields: Vec<(Identifier, String)>,
+    },
+    Enumeration {
+        name: String,
+        descriptor: TypeDescriptor,
+        variants: Vec<Identifier>,
+    },
+}
+
+impl Definition {
+    fn name(&self) -> &str {
+        match *self {
+            Self::Record { ref name, .. } | Self::Enumeration { ref name, .. } => name,
+        }
+    }
+
+    fn descriptor(&self) -> &TypeDescriptor {
+        match *self {
+            Self::Record { ref descriptor, .. } | Self::Enumeration { ref descriptor, .. } => {
+                descriptor
+            }
+        }
+    }
+}
+
+/// Collects all named types required by the generated client.
+#[derive(Default)]
+pub(crate) struct Types {
+    definitions: Vec<Definition>,
+}
+
+impl Types {
+    /// Returns the Rust type representing the given type descriptor.
+    ///
+    /// Records and enumerations will be named after `name` (in `UpperCamelCase`) unless an
+    /// identical type has been defined before, in which case the existing type will be reused.
+    pub(crate) fn rust_type(&mut self, typ: &TypeDescriptor, name: &str) -> io::Result<String> {
+        Ok(match *typ {
+            TypeDescriptor::Integer(ref range) => integer_type(range.start, range.end).into(),
+            TypeDescriptor::Float => "f32".into(),
+            TypeDescriptor::Boolean => "bool".into(),
+            TypeDescriptor::String => "String".into(),
+            TypeDescriptor::List(ref element_type) => {
+                format!("Vec<{}>", self.rust_type(element_type, name)?)
+            }
+            TypeDescriptor::Tuple(ref element_types) => match element_types.as_slice() {
+                [] => "()".into(),
+                [single] => format!("({},)", self.rust_type(single, name)?),
+                elements if elements.len() <= 4 => {
+                    let elements = elements
+                        .iter()
+                        .enumerate()
+                        .map(|(index, element_type)| {
+                            self.rust_type(element_type, &format!("{name}{index}"))
+                        })
+                        .collect::<io::Result<Vec<_>>>()?;
+                    format!("({})", elements.join(", "))
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("{name}: tuples with more than four elements are not supported"),
+                    ));
+                }
+            },
+            TypeDescriptor::Record(ref field_types) => {
+                if let Some(existing) = self.existing(typ) {
+                    return Ok(existing);
+                }
+                let fields = field_types
+                    .iter()
+                    .map(|(field, field_type)| {
+                        let field_name = format!("{name}{}", field.camel());
+                        Ok((field.clone(), self.rust_type(field_type, &field_name)?))
+                    })
+                    .collect::<io::Result<_>>()?;
+                self.define(name, |name| Definition::Record {
+                    name,
+                    descriptor: typ.clone(),
+                    fields,
+                })
+            }
+            TypeDescriptor::Enumeration(ref variants) => {
+                if let Some(existing) = self.existing(typ) {
+                    return Ok(existing);
+                }
+                self.define(name, |name| Definition::Enumeration {
+                    name,
+                    descriptor: typ.clone(),
+                    variants: variants.clone(),
+                })
+            }
+            TypeDescriptor::Optional(ref inner_type) => {
+                format!("Option<{}>", self.rust_type(inner_type, name)?)
+            }
+        })
+    }
+
+    /// Returns the name of an already defined type with the same descriptor.
+    fn existing(&self, typ: &TypeDescriptor) -> Option<String> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.descriptor() == typ)
+            .map(|definition| definition.name().to_owned())
+    }
+
+    /// Adds a new definition under a unique name.
+    fn define(&mut self, name: &str, definition: impl FnOnce(String) -> Definition) -> String {
+        let mut unique_name = name.to_owned();
+        let mut counter = 1_u32;
+        while self
+            .definitions
+            .iter()
+            .any(|existing| existing.name() == unique_name)
+        {
+            counter += 1;
+            unique_name = format!("{name}{counter}");
+        }
+        self.definitions.push(definition(unique_name.clone()));
+        unique_name
+    }
+
+    pub(crate) fn generate(&self, out: &mut impl Write) -> io::Result<()> {
+        for definition in &self.definitions {
+            match *definition {
+                Definition::Record {
+                    ref name,
+                    ref descriptor,
+                    ref fields,
+                } => generate_record(out, name, descriptor, fields)?,
+                Definition::Enumeration {
+                    ref name,
+                    ref descriptor,
+                    ref variants,
+                } => generate_enumeration(out, name, descriptor, variants)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn generate_record(
+    out: &mut impl Write,
+    name: &str,
+    descriptor: &TypeDescriptor,
+    fields: &[(Identifier, String)],
+) -> io::Result<()> {
+    writeln!(out, "#[derive(Clone, Debug, PartialEq)]")?;
+    writeln!(out, "pub struct {name} {{")?;
+    for (field, typ) in fields {
+        writeln!(out, "    pub {field}: {typ},", field = field.snake())?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    writeln!(out, "impl ApiValue for {name} {{")?;
+    generate_type_descriptor(out, descriptor)?;
+
+    writeln!(out, "    fn into_value(self) -> Value {{")?;
+    writeln!(out, "        Value::Record(")?;
+    writeln!(out, "            [")?;
+    for (field, _) in fields {
+        writeln!(
+            out,
+            "                ({}, self.{}.into_value()),",
+            identifier_expr(field),
+            field.snake()
+        )?;
+    }
+    writeln!(out, "            ]")?;
+    writeln!(out, "            .into_iter()")?;
+    writeln!(out, "            .collect(),")?;
+    writeln!(out, "        )")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "    fn try_from_value(value: Value) -> Result<Self, ConversionError> {{"
+    )?;
+    writeln!(out, "        let Value::Record(fields) = value else {{")?;
+    writeln!(
+        out,
+        "            return Err(ConversionError::TypeMismatch {{"
+    )?;
+    writeln!(
+        out,
+        "                expected: Box::new(Self::type_descriptor()),"
+    )?;
+    writeln!(out, "                value: Box::new(value),")?;
+    writeln!(out, "            }});")?;
+    writeln!(out, "        }};")?;
+    writeln!(out, "        let field = |name: &'static str| {{")?;
+    writeln!(out, "            fields")?;
+    writeln!(
+        out,
+        "                .get(&Identifier(Cow::Borrowed(name)))"
+    )?;
+    writeln!(out, "                .cloned()")?;
+    writeln!(out, "                .unwrap_or(Value::Unit)")?;
+    writeln!(out, "        }};")?;
+    writeln!(out, "        Ok(Self {{")?;
+    for (field, _) in fields {
+        writeln!(
+            out,
+            "            {}: ApiValue::try_from_value(field({:?}))?,",
+            field.snake(),
+            field.as_ref()
+        )?;
+    }
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_enumeration(
+    out: &mut impl Write,
+    name: &str,
+    descriptor: &TypeDescriptor,
+    variants: &[Identifier],
+) -> io::Result<()> {
+    writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]")?;
+    writeln!(out, "pub enum {name} {{")?;
+    for variant in variants {
+        writeln!(out, "    {},", variant.camel())?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    writeln!(out, "impl ApiValue for {name} {{")?;
+    generate_type_descriptor(out, descriptor)?;
+
+    writeln!(out, "    fn into_value(self) -> Value {{")?;
+    writeln!(out, "        let variant = match self {{")?;
+    for variant in variants {
+        writeln!(
+            out,
+            "            Self::{} => {:?},",
+            variant.camel(),
+            variant.as_ref()
+        )?;
+    }
+    writeln!(out, "        }};")?;
+    writeln!(
+        out,
+        "        Value::Enumeration(Identifier(Cow::Borrowed(variant)))"
+    )?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "    fn try_from_value(value: Value) -> Result<Self, ConversionError> {{"
+    )?;
+    writeln!(
+        out,
+        "        if let Value::Enumeration(ref variant) = value {{"
+    )?;
+    writeln!(out, "            match variant.as_ref() {{")?;
+    for variant in variants {
+        writeln!(
+            out,
+            "                {:?} => return Ok(Self::{}),",
+            variant.as_ref(),
+            variant.camel()
+        )?;
+    }
+    writeln!(out, "                _ => {{}}")?;
+    writeln!(out, "            }}")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "        Err(ConversionError::TypeMismatch {{")?;
+    writeln!(
+        out,
+        "            expected: Box::new(Self::type_descriptor()),"
+    )?;
+    writeln!(out, "            value: Box::new(value),")?;
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn generate_type_descriptor(out: &mut impl Write, descriptor: &TypeDescriptor) -> io::Result<()> {
+    writeln!(out, "    fn type_descriptor() -> TypeDescriptor {{")?;
+    writeln!(out, "        {}", type_descriptor_expr(descriptor))?;
+    writeln!(out, "    }}")?;
+    writeln!(out)
+}
+
+/// Picks the smallest integer type being able to represent all values of the range.
+fn integer_type(start: i64, end: i64) -> &'static str {
+    let max = end.saturating_sub(1);
+    if start >= 0 {
+        if max <= u8::MAX.into() {
+            "u8"
+        } else if max <= u16::MAX.into() {
+            "u16"
+        } else if max <= u32::MAX.into() {
+            "u32"
+        } else {
+            "u64"
+        }
+    } else if start >= i8::MIN.into() && max <= i8::MAX.into() {
+        "i8"
+    } else if start >= i16::MIN.into() && max <= i16::MAX.into() {
+        "i16"
+    } else if start >= i32::MIN.into() && max <= i32::MAX.into() {
+        "i32"
+    } else {
+        "i64"
+    }
+}
+
+/// Renders a Rust expression constructing the given type descriptor.
+pub(crate) fn type_descriptor_expr(descriptor: &TypeDescriptor) -> String {
+    match *descriptor {
+        TypeDescriptor::Integer(ref range) => {
+            format!("TypeDescriptor::Integer({}..{})", range.start, range.end)
+        }
+        TypeDescriptor::Float => "TypeDescriptor::Float".into(),
+        TypeDescriptor::Boolean => "TypeDescriptor::Boolean".into(),
+        TypeDescriptor::String => "TypeDescriptor::String".into(),
+        TypeDescriptor::List(ref element_type) => format!(
+            "TypeDescriptor::List(Box::new({}))",
+            type_descriptor_expr(element_type)
+        ),
+        TypeDescriptor::Tuple(ref element_types) => {
+            let element_types = element_types
+                .iter()
+                .map(type_descriptor_expr)
+                .collect::<Vec<_>>();
+            format!("TypeDescriptor::Tuple(vec![{}])", element_types.join(", "))
+        }
+        TypeDescriptor::Record(ref field_types) => {
+            let field_types = field_types
+                .iter()
+                .map(|(field, field_type)| {
+                    format!(
+                        "({}, {})",
+                        identifier_expr(field),
+                        type_descriptor_expr(field_type)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "TypeDescriptor::Record([{}].into_iter().collect())",
+                field_types.join(", ")
+            )
+        }
+        TypeDescriptor::Enumeration(ref variants) => {
+            let variants = variants.iter().map(identifier_expr).collect::<Vec<_>>();
+            format!("TypeDescriptor::Enumeration(vec![{}])", variants.join(", "))
+        }
+        TypeDescriptor::Optional(ref inner_type) => format!(
+            "TypeDescriptor::Optional(Box::new({}))",
+            type_descriptor_expr(inner_type)
+        ),
+    }
+}
+
+/// Renders a Rust expression constructing the given value.
+pub(crate) fn value_expr(value: &Value) -> String {
+    let values = |values: &[Value]| values.iter().map(value_expr).collect::<Vec<_>>().join(", ");
+    match *value {
+        Value::Unit => "Value::Unit".into(),
+        Value::Integer(value) => format!("Value::Integer({value})"),
+        Value::Float(value) => format!("Value::Float({value:?})"),
+        Value::Boolean(value) => format!("Value::Boolean({value})"),
+        Value::String(ref value) => format!("Value::String(String::from({value:?}))"),
+        Value::List(ref elements) => format!("Value::List(vec![{}])", values(elements)),
+        Value::Tuple(ref elements) => format!("Value::Tuple(vec![{}])", values(elements)),
+        Value::Record(ref fields) => {
+            let fields = fields
+                .iter()
+                .map(|(field, field_value)| {
+                    format!("({}, {})", identifier_expr(field), value_expr(field_value))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "Value::Record([{}].into_iter().collect())",
+                fields.join(", ")
+            )
+        }
+        Value::Enumeration(ref variant) => {
+            format!("Value::Enumeration({})", identifier_expr(variant))
+        }
+        Value::Optional(None) => "Value::Optional(None)".into(),
+        Value::Optional(Some(ref inner)) => {
+            format!("Value::Optional(Some(Box::new({})))", value_expr(inner))
+        }
+    }
+}
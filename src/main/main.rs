@@ -40,6 +40,10 @@ pub(crate) fn main() -> anyhow::Result<std::process::ExitCode> {
             tasks::robot_web::run(&shell, args)?;
             Ok(ExitCode::SUCCESS)
         }
+        "api" => {
+            tasks::api::run(&shell, args)?;
+            Ok(ExitCode::SUCCESS)
+        }
         unknown => {
             error!("unknown command: {unknown}");
             print_help();
@@ -50,10 +54,55 @@ pub(crate) fn main() -> anyhow::Result<std::process::ExitCode> {
 
 fn print_help() {
     eprintln!("Start the robot application on the local machine:");
-    eprintln!("\tcargo robot");
+    eprintln!("\tcargo robot [--release]");
+    eprintln!();
+
+    eprintln!("… controlled by an api client connecting via TCP instead of the Python script:");
+    eprintln!("\tcargo robot --listen <127.0.0.1:8001>");
+    eprintln!();
+
+    eprintln!("… controlled by HTTP requests carrying JSON instead of the Python script:");
+    eprintln!("\tcargo robot --http <127.0.0.1:8000>");
+    eprintln!();
+
+    eprintln!("… controlled by a program talking JSON lines via stdin/stdout:");
+    eprintln!("\tcargo robot --stdio");
+    eprintln!("\tcargo robot --spawn <program> [<arguments>…]");
+    eprintln!();
+
+    eprintln!("… recording the api traffic of any of the above to a file:");
+    eprintln!("\tcargo robot --record <file> [<options>…]");
+    eprintln!();
+
+    eprintln!("… replaying a recording instead of running a controller:");
+    eprintln!("\tcargo robot --replay <file>");
     eprintln!();
 
     eprintln!("Start the robot application as a web service:");
     eprintln!("\tcargo robot-web");
     eprintln!();
+
+    eprintln!("Compare two revisions of an api descriptor:");
+    eprintln!("\tcargo api diff <old.api.json> <new.api.json>");
+    eprintln!();
+
+    eprintln!("Generate a reference of an api descriptor as Markdown or HTML:");
+    eprintln!("\tcargo api docs [--locale <de>] <api.json> <output.md|output.html>");
+    eprintln!();
+
+    eprintln!("Generate a typed Rust client for an api descriptor:");
+    eprintln!("\tcargo api rust <api.json> [<output.rs>]");
+    eprintln!();
+
+    eprintln!("Generate a JavaScript client module for an api descriptor:");
+    eprintln!("\tcargo api javascript <api.json> <output.mjs>");
+    eprintln!();
+
+    eprintln!("Export the JSON Schema of api descriptors:");
+    eprintln!("\tcargo api schema [<output.json>]");
+    eprintln!();
+
+    eprintln!("Check api descriptors for mistakes:");
+    eprintln!("\tcargo api check <api.json>...");
+    eprintln!();
 }
@@ -1,2 +1,3 @@
+pub(super) mod api;
 pub(super) mod robot_native;
 pub(super) mod robot_web;
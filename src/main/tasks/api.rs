@@ -0,0 +1,21 @@
+use anyhow::Context;
+use pico_args::Arguments;
+use xshell::Shell;
+
+const PACKAGE_NAME: &str = "application-api-tool";
+
+pub(crate) fn run(shell: &Shell, args: Arguments) -> anyhow::Result<()> {
+    let tool_args = args.finish();
+
+    // stay in the project root so that relative paths given by the user remain valid
+    xshell::cmd!(
+        shell,
+        "cargo run --quiet --manifest-path workspace-common/Cargo.toml --package {PACKAGE_NAME} --"
+    )
+    .args(tool_args)
+    .env("CARGO_TARGET_DIR", "target")
+    .run()
+    .context(format!("Failed to run {PACKAGE_NAME}"))?;
+
+    Ok(())
+}
@@ -8,15 +8,16 @@ pub(crate) fn run(shell: &Shell, mut args: Arguments) -> anyhow::Result<()> {
     let is_release = args.contains("--release");
     let release_flag: &[_] = if is_release { &["--release"] } else { &[] };
 
-    let cargo_args = args.finish();
+    // all remaining options are meant for the application (e.g. `--http <address>`)
+    let application_args = args.finish();
 
     shell.change_dir("workspace-common");
 
     xshell::cmd!(
         shell,
-        "cargo run --jobs -1 --package {PACKAGE_NAME} {release_flag...}"
+        "cargo run --jobs -1 --package {PACKAGE_NAME} {release_flag...} --"
     )
-    .args(cargo_args)
+    .args(application_args)
     // .quiet()
     .run()
     .context(format!("Failed to build {PACKAGE_NAME}"))?;